@@ -0,0 +1,95 @@
+// Terrain material: blends four albedo texture layers (vegetation, earth,
+// rock, snow) using splat weights stored in the mesh vertex color, darkens them
+// under the fog of war, then hands the result to the standard PBR lighting
+// path.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+@group(2) @binding(100) var<uniform> vegetation_color: vec4<f32>;
+@group(2) @binding(101) var<uniform> earth_color: vec4<f32>;
+@group(2) @binding(102) var<uniform> rock_color: vec4<f32>;
+@group(2) @binding(103) var<uniform> snow_color: vec4<f32>;
+@group(2) @binding(104) var<uniform> fog_bounds: vec4<f32>;
+@group(2) @binding(105) var fog_texture: texture_2d<f32>;
+@group(2) @binding(106) var fog_sampler: sampler;
+@group(2) @binding(107) var vegetation_texture: texture_2d<f32>;
+@group(2) @binding(108) var vegetation_sampler: sampler;
+@group(2) @binding(109) var earth_texture: texture_2d<f32>;
+@group(2) @binding(110) var earth_sampler: sampler;
+@group(2) @binding(111) var rock_texture: texture_2d<f32>;
+@group(2) @binding(112) var rock_sampler: sampler;
+@group(2) @binding(113) var snow_texture: texture_2d<f32>;
+@group(2) @binding(114) var snow_sampler: sampler;
+
+// World units covered by one repeat of the layer textures, up close and far
+// off; mixing the two hides the tiling
+const NEAR_TILE_SIZE: f32 = 24.0;
+const FAR_TILE_SIZE: f32 = 170.0;
+// Albedo multiplier for never-seen and previously-seen ground; the fog
+// texture stores 0 for unexplored, 0.5 for explored and 1 for visible
+const UNEXPLORED_BRIGHTNESS: f32 = 0.04;
+const EXPLORED_BRIGHTNESS: f32 = 0.4;
+
+// Without a fog texture the fallback image is white, so everything is visible
+fn fog_brightness(world_xz: vec2<f32>) -> f32 {
+    let uv = (world_xz - fog_bounds.xy) / fog_bounds.zw;
+    let fog = textureSample(fog_texture, fog_sampler, uv).r;
+    let explored = mix(UNEXPLORED_BRIGHTNESS, EXPLORED_BRIGHTNESS, saturate(fog * 2.0));
+    return mix(explored, 1.0, saturate(fog * 2.0 - 1.0));
+}
+
+// Layer albedo at a world XZ position, sampled at two scales
+fn layer_albedo(t: texture_2d<f32>, s: sampler, world_xz: vec2<f32>) -> vec3<f32> {
+    let near = textureSample(t, s, world_xz / NEAR_TILE_SIZE).rgb;
+    let far = textureSample(t, s, world_xz / FAR_TILE_SIZE).rgb;
+    return mix(near, far, 0.35);
+}
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+
+#ifdef VERTEX_COLORS
+    var weights = max(in.color, vec4<f32>(0.0));
+#else
+    var weights = vec4<f32>(1.0, 0.0, 0.0, 0.0);
+#endif
+    weights = weights / max(dot(weights, vec4<f32>(1.0)), 0.0001);
+
+    // Without a texture the fallback image is white, leaving the plain tint
+    let p = in.world_position.xz;
+    let color = vegetation_color.rgb * layer_albedo(vegetation_texture, vegetation_sampler, p) * weights.x
+        + earth_color.rgb * layer_albedo(earth_texture, earth_sampler, p) * weights.y
+        + rock_color.rgb * layer_albedo(rock_texture, rock_sampler, p) * weights.z
+        + snow_color.rgb * layer_albedo(snow_texture, snow_sampler, p) * weights.w;
+    let fog = fog_brightness(in.world_position.xz);
+    pbr_input.material.base_color = vec4<f32>(color * fog, 1.0);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+    return out;
+}
@@ -5,9 +5,16 @@ use bevy::time::{Timer, TimerMode};
 use bevy::window::PrimaryWindow;
 
 use super::{AppState, Experiment};
+use crate::fog_of_war::{FogOfWar, FogPlane, FogRevealer, HiddenByFog};
+use crate::growth::{
+    CrimsonColony, DEFAULT_INITIAL_GROWTH_AGE, DEFAULT_MAX_GROWTH_AGE, FULL_HEALTH,
+    GROWTH_UPDATE_FREQUENCY, GrowthPatch, PATCH_SPACING, growth_color,
+};
+use crate::minimap::{Minimap, MinimapCaption, SpawnMinimap};
+use crate::orbital_strike::{OrbitalStrike, ScorchMark};
 
 // Grid and positioning constants
-const GRID_SIZE: f32 = 2f32;
+const GRID_SIZE: f32 = PATCH_SPACING;
 const SPOT_SIZE: f32 = 2f32;
 const POSITION_TOLERANCE: f32 = 1f32;
 const TERRAIN_HEIGHT_OFFSET: f32 = 0.01f32;
@@ -15,54 +22,21 @@ const TERRAIN_HEIGHT_OFFSET: f32 = 0.01f32;
 // Terrain setup constants
 const TERRAIN_SIZE: f32 = 200f32;
 const CAMERA_HEIGHT: f32 = 50f32;
+const FOG_CELL_SIZE: f32 = GRID_SIZE;
+const SCANNER_RADIUS: f32 = 25f32; // vision around the cursor while fog of war is on
 const GROUND_COLOR: (f32, f32, f32) = (0.3f32, 0.5f32, 0.3f32);
 const DIRECTIONAL_LIGHT_POS: (f32, f32, f32) = (0f32, 10f32, 0f32);
 
-// Update timing constants
-const GROWTH_UPDATE_FREQUENCY: f32 = 0.2f32; // 5Hz
-
-// Game balance constants
-const DEFAULT_GROWTH_RATE: f32 = 0.5f32; // How fast spots mature (0-1 per second)
-const DEFAULT_RADIUS_EXPANSION_RATE: f32 = 1f32; // How fast growth spreads (units per second)
-const DEFAULT_INITIAL_GROWTH_AGE: f32 = 0f32; // Starting age for new growth spots
-const DEFAULT_INITIAL_RADIUS: f32 = 0f32; // Starting radius for new growth origins
-const DEFAULT_MAX_GROWTH_AGE: f32 = 1f32; // Maximum age (fully mature)
-const MAX_GROWTH_RADIUS: f32 = 120f32; // Maximum radius to prevent infinite expansion
+// Orbital strike constants
+const STRIKE_RADIUS: f32 = 15f32;
+const STRIKE_STRENGTH: f32 = 1.5; // Destroys young growth, leaves mature growth withering
 
 // Visual constants
 const GROWTH_BASE_COLOR: (f32, f32, f32) = (1f32, 0f32, 0f32); // Red color for growth spots
 
-// Visual aging constants (red to black interpolation)
-const GROWTH_VISUAL_AGE_THRESHOLD: f32 = 1f32; // Age when visual updates stop
-
-// Component for Crimson Sprawl colonies
-#[derive(Component)]
-pub struct CrimsonColony {
-    pub radius: f32,
-    pub expansion_complete: bool,
-    pub expansion_rate: f32,
-    pub maturation_rate: f32,
-    pub max_radius: f32,
-}
-
-impl Default for CrimsonColony {
-    fn default() -> Self {
-        Self {
-            radius: DEFAULT_INITIAL_RADIUS,
-            expansion_complete: false,
-            expansion_rate: DEFAULT_RADIUS_EXPANSION_RATE,
-            maturation_rate: DEFAULT_GROWTH_RATE,
-            max_radius: MAX_GROWTH_RADIUS,
-        }
-    }
-}
-
+/// Follows the cursor over the ground, revealing growth around it
 #[derive(Component)]
-pub struct GrowthPatch {
-    pub _colony_entity: Entity,
-    pub age: f32,             // 0.0 to 1.0 (fully mature)
-    pub maturation_rate: f32, // How fast it ages per second
-}
+pub struct FogScanner;
 
 #[derive(Component)]
 pub struct Ground;
@@ -70,9 +44,6 @@ pub struct Ground;
 #[derive(Resource)]
 pub struct GrowthUpdateTimer(pub Timer);
 
-// Patch spacing is consistent across all colonies
-const PATCH_SPACING: f32 = GRID_SIZE;
-
 #[derive(Resource, Default)]
 pub struct GrowthState {
     pub is_complete: bool, // True when all growth is fully mature and expansion is done
@@ -107,6 +78,8 @@ impl Experiment for CrimsonSprawlExperiment {
                 tick_growth_timer.run_if(growth_not_complete),
                 // Systems that need 60fps responsiveness
                 spawn_crimson_colony.run_if(mouse_just_clicked),
+                fire_orbital_strike.run_if(mouse_just_right_clicked),
+                (toggle_fog_of_war, move_fog_scanner),
             )
                 .run_if(in_state(AppState::CrimsonSprawl)),
         )
@@ -120,6 +93,7 @@ impl Experiment for CrimsonSprawlExperiment {
                 crimson_expansion_system,
                 crimson_spreading_system,
                 check_crimson_completion,
+                report_growth_coverage,
             )
                 .run_if(
                     in_state(AppState::CrimsonSprawl)
@@ -135,6 +109,10 @@ fn mouse_just_clicked(mouse: Res<ButtonInput<MouseButton>>) -> bool {
     mouse.just_pressed(MouseButton::Left)
 }
 
+fn mouse_just_right_clicked(mouse: Res<ButtonInput<MouseButton>>) -> bool {
+    mouse.just_pressed(MouseButton::Right)
+}
+
 fn growth_not_complete(growth_state: Res<GrowthState>) -> bool {
     !growth_state.is_complete
 }
@@ -199,7 +177,9 @@ fn spawn_crimson_patch(
             _colony_entity: colony_entity,
             age: DEFAULT_INITIAL_GROWTH_AGE,
             maturation_rate: colony.maturation_rate,
+            health: FULL_HEALTH,
         },
+        HiddenByFog::Visible,
     ));
 
     true // Successfully spawned
@@ -209,6 +189,7 @@ fn setup_crimson_experiment(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
 ) {
     // Simple flat terrain plane
     let terrain = Mesh::from(
@@ -243,12 +224,47 @@ fn setup_crimson_experiment(
     ));
 
     // Fixed top-down 2D camera
+    let camera = commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_translation(Vec3::new(0f32, CAMERA_HEIGHT, 0f32))
+                .looking_at(Vec3::ZERO, Vec3::Z),
+            GlobalTransform::default(),
+        ))
+        .id();
+
+    // The camera only sees the middle of the ground; the minimap shows all of it
+    commands.queue(SpawnMinimap {
+        area: Rect::from_center_size(Vec2::ZERO, Vec2::splat(TERRAIN_SIZE)),
+        ui_camera: camera,
+    });
+
+    // Fog of war starts off; F turns it on and the cursor becomes the only
+    // window onto the growth
+    let mut fog = FogOfWar::new(
+        FogPlane::Xz,
+        Vec2::splat(-TERRAIN_SIZE / 2f32),
+        Vec2::splat(TERRAIN_SIZE),
+        FOG_CELL_SIZE,
+        &mut images,
+    );
+    fog.set_enabled(false);
+    commands.insert_resource(fog);
     commands.spawn((
-        Camera3d::default(),
-        Transform::from_translation(Vec3::new(0f32, CAMERA_HEIGHT, 0f32))
-            .looking_at(Vec3::ZERO, Vec3::Z),
+        FogScanner,
+        FogRevealer {
+            radius: SCANNER_RADIUS,
+        },
+        Transform::default(),
         GlobalTransform::default(),
     ));
+
+    info!("Crimson sprawl loaded! Controls:");
+    info!("  LMB - Seed a colony");
+    info!("  RMB - Orbital strike");
+    info!("  F - Toggle fog of war (growth only shows near the cursor)");
+    info!("  M - Toggle tactical map");
+    info!("  ESC - Return to launcher");
 }
 
 #[allow(clippy::type_complexity)]
@@ -260,22 +276,52 @@ fn cleanup_crimson_experiment(
             With<GrowthPatch>,
             With<CrimsonColony>,
             With<Ground>,
+            With<FogScanner>,
+            With<Minimap>,
             With<Camera3d>,
             With<DirectionalLight>,
+            With<ScorchMark>,
         )>,
     >,
 ) {
     for entity in entities.iter() {
         commands.entity(entity).despawn();
     }
+    commands.remove_resource::<FogOfWar>();
+}
+
+fn toggle_fog_of_war(mut fog: ResMut<FogOfWar>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::KeyF) {
+        let enabled = !fog.enabled();
+        fog.set_enabled(enabled);
+        info!("Fog of war {}", if enabled { "on" } else { "off" });
+    }
+}
+
+fn move_fog_scanner(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), Without<Minimap>>,
+    mut scanner: Single<&mut Transform, With<FogScanner>>,
+) {
+    let (cam, cam_tf) = camera.into_inner();
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| cam.viewport_to_world(cam_tf, cursor).ok())
+    else {
+        return;
+    };
+    if let Some(distance) = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y)) {
+        scanner.translation = ray.get_point(distance);
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
 fn spawn_crimson_colony(
     window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&Camera, &GlobalTransform)>,
+    camera: Single<(&Camera, &GlobalTransform), Without<Minimap>>,
     ground_tf: Single<&GlobalTransform, With<Ground>>,
     existing_patches: Query<&Transform, With<GrowthPatch>>,
+    scorch_marks: Query<&ScorchMark>,
     mut growth_state: ResMut<GrowthState>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -297,6 +343,11 @@ fn spawn_crimson_colony(
         let grid_aligned_point = snap_to_grid(world_point);
         let final_position = grid_aligned_point + Vec3::Y * TERRAIN_HEIGHT_OFFSET;
 
+        // Nothing takes root on scorched ground
+        if scorch_marks.iter().any(|mark| mark.covers(final_position)) {
+            return;
+        }
+
         // Create colony origin entity
         let colony_entity = commands
             .spawn((
@@ -325,6 +376,46 @@ fn spawn_crimson_colony(
     }
 }
 
+/// Fires an orbital strike at the ground under the cursor
+fn fire_orbital_strike(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), Without<Minimap>>,
+    ground_tf: Single<&GlobalTransform, With<Ground>>,
+    mut growth_state: ResMut<GrowthState>,
+    mut strikes: EventWriter<OrbitalStrike>,
+) {
+    let (cam, cam_tf) = camera.into_inner();
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| cam.viewport_to_world(cam_tf, cursor).ok())
+    else {
+        return;
+    };
+    if let Some(distance) =
+        ray.intersect_plane(ground_tf.translation(), InfinitePlane3d::new(Vec3::Y))
+    {
+        strikes.write(OrbitalStrike {
+            center: ray.get_point(distance),
+            radius: STRIKE_RADIUS,
+            strength: STRIKE_STRENGTH,
+        });
+        // Struck colonies recoil and have to grow back
+        growth_state.is_complete = false;
+    }
+}
+
+/// Shows how much of the ground the growth covers under the minimap
+fn report_growth_coverage(
+    patches: Query<(), With<GrowthPatch>>,
+    mut captions: Query<&mut Text, With<MinimapCaption>>,
+) {
+    let covered = patches.iter().count() as f32 * SPOT_SIZE * SPOT_SIZE;
+    let coverage = covered / (TERRAIN_SIZE * TERRAIN_SIZE) * 100f32;
+    for mut caption in &mut captions {
+        caption.0 = format!("Growth coverage {coverage:.1}%");
+    }
+}
+
 fn patch_maturation_system(time: Res<Time>, mut patch_q: Query<&mut GrowthPatch>) {
     // Only process patches that aren't fully mature
     for mut patch in patch_q.iter_mut() {
@@ -344,27 +435,7 @@ fn update_patch_visuals(
 ) {
     for (patch, mesh_mat) in patch_q.iter_mut() {
         if let Some(material) = materials.get_mut(&mesh_mat.0) {
-            // Interpolate from red (new) to black (mature)
-            // Red -> Brown -> Dark Brown -> Black
-            let age_normalized = (patch.age / GROWTH_VISUAL_AGE_THRESHOLD).clamp(0.0, 1.0);
-
-            let (r, g, b) = if age_normalized < 0.5 {
-                // First half: Red (1,0,0) -> Brown (0.6,0.3,0.1)
-                let t = age_normalized * 2.0; // 0.0 to 1.0
-                let r = 1.0 - t * 0.4; // 1.0 -> 0.6
-                let g = t * 0.3; // 0.0 -> 0.3
-                let b = t * 0.1; // 0.0 -> 0.1
-                (r, g, b)
-            } else {
-                // Second half: Brown (0.6,0.3,0.1) -> Black (0,0,0)
-                let t = (age_normalized - 0.5) * 2.0; // 0.0 to 1.0
-                let r = 0.6 - t * 0.6; // 0.6 -> 0.0
-                let g = 0.3 - t * 0.3; // 0.3 -> 0.0
-                let b = 0.1 - t * 0.1; // 0.1 -> 0.0
-                (r, g, b)
-            };
-
-            material.base_color = Color::srgb(r, g, b);
+            material.base_color = growth_color(patch.age);
         }
     }
 }
@@ -388,6 +459,7 @@ fn crimson_expansion_system(mut colony_q: Query<&mut CrimsonColony>) {
 fn crimson_spreading_system(
     colony_q: Query<(Entity, &CrimsonColony, &Transform)>,
     existing_patches: Query<&Transform, With<GrowthPatch>>,
+    scorch_marks: Query<&ScorchMark>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -419,6 +491,11 @@ fn crimson_spreading_system(
                     continue; // Skip positions outside terrain
                 }
 
+                // Scorched ground blocks regrowth until it cools
+                if scorch_marks.iter().any(|mark| mark.covers(grid_pos)) {
+                    continue;
+                }
+
                 let distance_from_origin = grid_pos.distance(origin_pos);
 
                 // Only spawn if within radius and not too close to origin
@@ -0,0 +1,435 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    platform::collections::HashSet,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{
+    chunks::{LoadedChunks, chunk_of},
+    grid::GridShape,
+};
+use crate::terrain::tiles::TileKind;
+
+// Atlas layout; `textures_16x16.png` and the generated transition atlas share it
+const TILE_PIXELS: u32 = 16;
+const ATLAS_COLUMNS: u32 = 32;
+
+// Transition shapes, in atlas pixels
+const BLOB_BORDER: f32 = 4f32; // how far an edge eats into the tile
+const BLOB_ROUNDING: f32 = 3f32; // radius of outer corners
+const BLOB_OVERHANG: f32 = 4f32; // arms reach past the tile so rounding never shows at seams
+const WANG_FEATHER: f32 = 0.08; // half-width of the soft edge, in tile units
+
+// Neighbour bits, clockwise from north
+const N: u8 = 1;
+const NE: u8 = 2;
+const E: u8 = 4;
+const SE: u8 = 8;
+const S: u8 = 16;
+const SW: u8 = 32;
+const W: u8 = 64;
+const NW: u8 = 128;
+
+// Tile offsets for each neighbour bit; tile rows run south to north
+const NEIGHBOURS: [(IVec2, u8); 8] = [
+    (IVec2::new(0, 1), N),
+    (IVec2::new(1, 1), NE),
+    (IVec2::new(1, 0), E),
+    (IVec2::new(1, -1), SE),
+    (IVec2::new(0, -1), S),
+    (IVec2::new(-1, -1), SW),
+    (IVec2::new(-1, 0), W),
+    (IVec2::new(-1, 1), NW),
+];
+
+/// Atlas indices in `textures_16x16.png` (32 columns, index = row * 32 + column)
+/// for each kind of ground; kinds with several entries pick one per tile
+fn atlas_indices(kind: TileKind) -> &'static [u32] {
+    match kind {
+        TileKind::DeepWater => &[257],
+        TileKind::ShallowWater => &[256],
+        TileKind::Sand => &[385],
+        TileKind::Marsh => &[555],
+        TileKind::Grass => &[514, 555],
+        TileKind::Scrub => &[417, 514],
+        TileKind::Dirt => &[417, 800],
+        TileKind::Rock => &[160, 161],
+        TileKind::Snow => &[224],
+    }
+}
+
+/// Plain ground tile for a kind at a tile position
+fn ground_index(kind: TileKind, pos: UVec2) -> u32 {
+    let indices = atlas_indices(kind);
+    // Cheap positional hash so variants don't form stripes
+    let roll = pos.x.wrapping_mul(73_856_093) ^ pos.y.wrapping_mul(19_349_663);
+    indices[roll as usize % indices.len()]
+}
+
+/// How transition tiles are chosen from a tile's neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutotileRules {
+    /// 47 shapes from all eight neighbours; diagonals only count when both
+    /// adjacent edges match
+    Blob47,
+    /// 16 shapes from the four corners; a corner is inside when all three
+    /// tiles sharing it match
+    Wang2Corner,
+}
+
+impl AutotileRules {
+    fn shapes(&self) -> u32 {
+        match self {
+            AutotileRules::Blob47 => 47,
+            AutotileRules::Wang2Corner => 16,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            AutotileRules::Blob47 => AutotileRules::Wang2Corner,
+            AutotileRules::Wang2Corner => AutotileRules::Blob47,
+        }
+    }
+}
+
+/// Clears diagonal bits whose adjacent edges aren't both set; the 47 blob
+/// shapes are the distinct results
+fn canonical_blob(mask: u8) -> u8 {
+    let mut mask = mask;
+    for (diagonal, sides) in [(NE, N | E), (SE, S | E), (SW, S | W), (NW, N | W)] {
+        if mask & sides != sides {
+            mask &= !diagonal;
+        }
+    }
+    mask
+}
+
+/// Corner bits (NW, NE, SE, SW as bits 0-3) inside a neighbour mask
+fn wang_corners(mask: u8) -> u8 {
+    [N | NW | W, N | NE | E, S | SE | E, S | SW | W]
+        .iter()
+        .enumerate()
+        .filter(|(_, corner)| mask & **corner == **corner)
+        .fold(0, |corners, (bit, _)| corners | 1 << bit)
+}
+
+/// Picks transition tiles and owns the generated atlas they come from
+#[derive(Resource)]
+pub struct Autotiler {
+    pub rules: AutotileRules,
+    /// Canonical blob masks in atlas order
+    blob_masks: Vec<u8>,
+    /// Atlas shape for every raw neighbour mask
+    blob_lookup: [u8; 256],
+    /// `textures_16x16.png`, the source of every transition's texture
+    source: Handle<Image>,
+    /// Generated transition atlas
+    atlas: Option<Handle<Image>>,
+    /// Rules the current atlas was built for
+    built_for: Option<AutotileRules>,
+}
+
+impl Autotiler {
+    pub fn new(source: Handle<Image>) -> Self {
+        let mut blob_masks: Vec<u8> = (0..=255u8).map(canonical_blob).collect();
+        blob_masks.sort_unstable();
+        blob_masks.dedup();
+        let mut blob_lookup = [0u8; 256];
+        for (mask, shape) in blob_lookup.iter_mut().enumerate() {
+            let canonical = canonical_blob(mask as u8);
+            *shape = blob_masks
+                .iter()
+                .position(|other| *other == canonical)
+                .expect("every canonical mask is listed") as u8;
+        }
+
+        Self {
+            rules: AutotileRules::Blob47,
+            blob_masks,
+            blob_lookup,
+            source,
+            atlas: None,
+            built_for: None,
+        }
+    }
+
+    pub fn source(&self) -> Handle<Image> {
+        self.source.clone()
+    }
+
+    /// Transition atlas for the current rules, once it has been built
+    pub fn atlas(&self) -> Option<Handle<Image>> {
+        self.atlas
+            .clone()
+            .filter(|_| self.built_for == Some(self.rules))
+    }
+
+    /// Ground index and, where the tile borders lower ground, transition
+    /// index of a map tile. Transition shapes only fit square cells, so other
+    /// grid shapes draw every tile in its own ground.
+    pub fn tiles_for(&self, grid: &TileGrid, shape: GridShape, pos: UVec2) -> (u32, Option<u32>) {
+        if shape != GridShape::Square {
+            return (ground_index(grid.kind(pos), pos), None);
+        }
+        let (background, mask) = grid.appearance(pos);
+        (
+            ground_index(background, pos),
+            mask.map(|mask| self.transition_index(grid.kind(pos), mask)),
+        )
+    }
+
+    /// Transition atlas index for a kind drawn with a neighbour mask
+    fn transition_index(&self, kind: TileKind, mask: u8) -> u32 {
+        let shape = match self.rules {
+            AutotileRules::Blob47 => self.blob_lookup[mask as usize] as u32,
+            AutotileRules::Wang2Corner => wang_corners(mask) as u32,
+        };
+        kind.layer() as u32 * self.rules.shapes() + shape
+    }
+
+    /// Coverage of one transition tile pixel, from its center in tile pixels
+    /// (y down, north at the top)
+    fn shape_alpha(&self, shape: u32, pixel: Vec2) -> f32 {
+        match self.rules {
+            AutotileRules::Blob47 => blob_alpha(self.blob_masks[shape as usize], pixel),
+            AutotileRules::Wang2Corner => wang_alpha(shape as u8, pixel),
+        }
+    }
+
+    /// Every kind's texture cut into every transition shape, one kind per run
+    /// of tiles
+    fn build_atlas(&self, source: &Image) -> Option<Image> {
+        if !matches!(
+            source.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm
+        ) {
+            warn!("Autotile source texture isn't 8-bit RGBA; skipping transitions");
+            return None;
+        }
+        let source_data = source.data.as_ref()?;
+        let source_width = source.width();
+        let source_columns = source_width / TILE_PIXELS;
+
+        let shapes = self.rules.shapes();
+        let tiles = TileKind::ALL.len() as u32 * shapes;
+        let rows = tiles.div_ceil(ATLAS_COLUMNS);
+        let width = ATLAS_COLUMNS * TILE_PIXELS;
+        let mut data = vec![0u8; (width * rows * TILE_PIXELS * 4) as usize];
+
+        for kind in TileKind::ALL {
+            let source_tile = atlas_indices(kind)[0];
+            let source_origin = UVec2::new(
+                source_tile % source_columns * TILE_PIXELS,
+                source_tile / source_columns * TILE_PIXELS,
+            );
+            for shape in 0..shapes {
+                let tile = kind.layer() as u32 * shapes + shape;
+                let origin = UVec2::new(
+                    tile % ATLAS_COLUMNS * TILE_PIXELS,
+                    tile / ATLAS_COLUMNS * TILE_PIXELS,
+                );
+                for y in 0..TILE_PIXELS {
+                    for x in 0..TILE_PIXELS {
+                        let alpha = self.shape_alpha(shape, Vec2::new(x as f32, y as f32) + 0.5);
+                        let from = (((source_origin.y + y) * source_width + source_origin.x + x)
+                            * 4) as usize;
+                        let to = (((origin.y + y) * width + origin.x + x) * 4) as usize;
+                        data[to..to + 3].copy_from_slice(&source_data[from..from + 3]);
+                        data[to + 3] = (source_data[from + 3] as f32 * alpha) as u8;
+                    }
+                }
+            }
+        }
+
+        Some(Image::new(
+            Extent3d {
+                width,
+                height: rows * TILE_PIXELS,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            source.texture_descriptor.format,
+            RenderAssetUsages::default(),
+        ))
+    }
+}
+
+/// Blob coverage: the union of a center square, an arm toward each matching
+/// edge, and a fill toward each matching corner, with rounded outer corners
+fn blob_alpha(mask: u8, pixel: Vec2) -> f32 {
+    let size = TILE_PIXELS as f32;
+    let (low, high) = (BLOB_BORDER, size - BLOB_BORDER);
+    let (out, far) = (-BLOB_OVERHANG, size + BLOB_OVERHANG);
+    let pieces = [
+        (0, Rect::new(low, low, high, high)),
+        (N, Rect::new(low, out, high, high)),
+        (S, Rect::new(low, low, high, far)),
+        (W, Rect::new(out, low, high, high)),
+        (E, Rect::new(low, low, far, high)),
+        (NE, Rect::new(low, out, far, high)),
+        (NW, Rect::new(out, out, high, high)),
+        (SE, Rect::new(low, low, far, far)),
+        (SW, Rect::new(out, low, high, far)),
+    ];
+    let distance = pieces
+        .iter()
+        .filter(|(bit, _)| mask & bit == *bit)
+        .map(|(_, rect)| {
+            // Rounded box distance: shrink, measure, then grow back
+            let half = rect.half_size() - BLOB_ROUNDING;
+            let offset = (pixel - rect.center()).abs() - half;
+            offset.max(Vec2::ZERO).length() + offset.max_element().min(0f32) - BLOB_ROUNDING
+        })
+        .fold(f32::MAX, f32::min);
+    (0.5 - distance).clamp(0f32, 1f32)
+}
+
+/// Wang coverage: corner values blended across the tile, cut at one half
+fn wang_alpha(corners: u8, pixel: Vec2) -> f32 {
+    let uv = pixel / TILE_PIXELS as f32;
+    let corner = |bit: u8| if corners & 1 << bit != 0 { 1f32 } else { 0f32 };
+    let top = corner(0) + (corner(1) - corner(0)) * uv.x;
+    let bottom = corner(3) + (corner(2) - corner(3)) * uv.x;
+    let value = top + (bottom - top) * uv.y;
+    ((value - 0.5 + WANG_FEATHER) / (2f32 * WANG_FEATHER)).clamp(0f32, 1f32)
+}
+
+/// Ground kind of every map tile, with the tiles whose appearance is stale
+#[derive(Resource)]
+pub struct TileGrid {
+    size: UVec2,
+    kinds: Vec<TileKind>,
+    dirty: HashSet<UVec2>,
+}
+
+impl TileGrid {
+    pub fn from_fn(size: UVec2, kind: impl Fn(UVec2) -> TileKind) -> Self {
+        let kinds = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+            .map(kind)
+            .collect();
+        Self {
+            size,
+            kinds,
+            dirty: HashSet::default(),
+        }
+    }
+
+    pub fn kind(&self, pos: UVec2) -> TileKind {
+        self.kinds[(pos.y * self.size.x + pos.x) as usize]
+    }
+
+    fn get(&self, pos: IVec2) -> Option<TileKind> {
+        (pos.cmpge(IVec2::ZERO).all() && pos.as_uvec2().cmplt(self.size).all())
+            .then(|| self.kind(pos.as_uvec2()))
+    }
+
+    /// Changes one tile; it and its neighbours are redrawn on the next refresh
+    pub fn set(&mut self, pos: UVec2, kind: TileKind) {
+        if pos.cmpge(self.size).any() || self.kind(pos) == kind {
+            return;
+        }
+        self.kinds[(pos.y * self.size.x + pos.x) as usize] = kind;
+        let center = pos.as_ivec2();
+        self.dirty.insert(pos);
+        for (offset, _) in NEIGHBOURS {
+            let neighbour = center + offset;
+            if self.get(neighbour).is_some() {
+                self.dirty.insert(neighbour.as_uvec2());
+            }
+        }
+    }
+
+    /// Ground drawn under a tile and, where a lower kind borders it, the
+    /// neighbour mask its own kind is cut with on top
+    fn appearance(&self, pos: UVec2) -> (TileKind, Option<u8>) {
+        let kind = self.kind(pos);
+        let mut background = kind;
+        let mut mask = 0u8;
+        for (offset, bit) in NEIGHBOURS {
+            // The map edge counts as more of the same
+            let neighbour = self.get(pos.as_ivec2() + offset).unwrap_or(kind);
+            if neighbour.layer() >= kind.layer() {
+                mask |= bit;
+            } else if neighbour.layer() < background.layer() {
+                background = neighbour;
+            }
+        }
+        if background == kind {
+            (kind, None)
+        } else {
+            (background, Some(mask))
+        }
+    }
+}
+
+/// B switches between blob and Wang rules
+pub fn switch_autotile_rules(mut autotiler: ResMut<Autotiler>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::KeyB) {
+        autotiler.rules = autotiler.rules.next();
+        info!("Autotile rules: {:?}", autotiler.rules);
+    }
+}
+
+/// Builds the transition atlas once the source texture has loaded, and again
+/// whenever the rules change; chunks pick the new atlas up as they respawn
+pub fn build_autotile_atlas(mut autotiler: ResMut<Autotiler>, mut images: ResMut<Assets<Image>>) {
+    if autotiler.built_for == Some(autotiler.rules) {
+        return;
+    }
+    let Some(atlas) = images
+        .get(&autotiler.source)
+        .and_then(|source| autotiler.build_atlas(source))
+    else {
+        return;
+    };
+    autotiler.atlas = Some(images.add(atlas));
+    autotiler.built_for = Some(autotiler.rules);
+}
+
+/// Redraws every loaded tile whose neighbourhood changed since the last
+/// refresh; unloaded chunks are drawn fresh when they stream in
+pub fn refresh_dirty_tiles(
+    mut grid: ResMut<TileGrid>,
+    autotiler: Res<Autotiler>,
+    shape: Res<GridShape>,
+    loaded: Res<LoadedChunks>,
+    storages: Query<&TileStorage>,
+    mut tiles: Query<(&mut TileTextureIndex, &mut TileVisible)>,
+) {
+    if grid.dirty.is_empty() || autotiler.atlas().is_none() {
+        return;
+    }
+
+    for pos in std::mem::take(&mut grid.dirty) {
+        let (chunk, tile_pos) = chunk_of(pos);
+        let Some(layers) = loaded.chunks.get(&chunk) else {
+            continue;
+        };
+        let (ground_tile, transition) = autotiler.tiles_for(&grid, *shape, pos);
+
+        if let Some(tile) = storages
+            .get(layers.ground)
+            .ok()
+            .and_then(|storage| storage.get(&tile_pos))
+            && let Ok((mut index, _)) = tiles.get_mut(tile)
+        {
+            index.0 = ground_tile;
+        }
+        if let Some(tile) = storages
+            .get(layers.transitions)
+            .ok()
+            .and_then(|storage| storage.get(&tile_pos))
+            && let Ok((mut index, mut visible)) = tiles.get_mut(tile)
+        {
+            visible.0 = transition.is_some();
+            if let Some(transition) = transition {
+                index.0 = transition;
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::{input::mouse::MouseWheel, prelude::*, window::PrimaryWindow};
+
+use super::grid::GridShape;
+
+// Zoom limits, as orthographic scale; zoomed out further the view streams
+// in more chunks than it's worth
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 3f32;
+const ZOOM_STEP: f32 = 1.15; // scale factor per wheel notch
+
+// Edge scrolling
+const EDGE_SCROLL_MARGIN: f32 = 12f32; // logical pixels from the window edge
+const EDGE_SCROLL_SPEED: f32 = 900f32; // screen pixels per second, so it feels the same at any zoom
+
+/// The map view's camera
+#[derive(Component)]
+pub struct MapCamera;
+
+/// Middle-drag pans, the wheel zooms about the cursor, and holding the cursor
+/// at a window edge scrolls; the view stays over the map
+pub fn control_map_camera(
+    time: Res<Time>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    camera: Single<(&mut Transform, &mut Projection), With<MapCamera>>,
+    shape: Res<GridShape>,
+    mut last_cursor: Local<Option<Vec2>>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection.as_mut() else {
+        return;
+    };
+    let mut position = transform.translation.xy();
+
+    // Drag: the ground under the cursor follows it. The delta is taken from
+    // the cursor in logical pixels, like the projection, rather than raw
+    // device motion; screen y points down
+    let drag = match (window.cursor_position(), *last_cursor) {
+        (Some(cursor), Some(last)) => cursor - last,
+        _ => Vec2::ZERO,
+    };
+    *last_cursor = window.cursor_position();
+    let dragging = mouse.pressed(MouseButton::Middle);
+    if dragging {
+        position += Vec2::new(-drag.x, drag.y) * projection.scale;
+    }
+
+    // Offset of the cursor from the window center, in logical pixels with y up
+    let cursor = window
+        .cursor_position()
+        .map(|cursor| (cursor - window.size() / 2f32) * Vec2::new(1f32, -1f32));
+
+    let notches: f32 = wheel.read().map(|event| event.y.signum()).sum();
+    if notches != 0f32 {
+        let scale = (projection.scale * ZOOM_STEP.powf(-notches)).clamp(MIN_SCALE, MAX_SCALE);
+        // Keep the point under the cursor fixed while zooming
+        let offset = cursor.unwrap_or(Vec2::ZERO);
+        position += offset * (projection.scale - scale);
+        projection.scale = scale;
+    }
+
+    if !dragging
+        && window.focused
+        && let Some(cursor) = window.cursor_position()
+    {
+        let size = window.size();
+        let mut direction = Vec2::ZERO;
+        if cursor.x < EDGE_SCROLL_MARGIN {
+            direction.x -= 1f32;
+        }
+        if cursor.x > size.x - EDGE_SCROLL_MARGIN {
+            direction.x += 1f32;
+        }
+        if cursor.y < EDGE_SCROLL_MARGIN {
+            direction.y += 1f32;
+        }
+        if cursor.y > size.y - EDGE_SCROLL_MARGIN {
+            direction.y -= 1f32;
+        }
+        position += direction.normalize_or_zero()
+            * EDGE_SCROLL_SPEED
+            * projection.scale
+            * time.delta_secs();
+    }
+
+    // The view's center never leaves the map
+    let map = shape.map_rect();
+    position = position.clamp(map.min, map.max);
+    transform.translation = position.extend(transform.translation.z);
+}
@@ -0,0 +1,217 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{
+    CHUNK_TILES, FOG_TILE_INDEX, MAP_TILES, TILE_SIZE,
+    autotile::{Autotiler, TileGrid},
+    camera::MapCamera,
+    grid::GridShape,
+    growth::{GROWTH_TILE_INDEX, TileGrowth},
+    picking::PickableTilemap,
+};
+use crate::fog_of_war::FogTilemapOverlay;
+
+// Layer depths
+const GROUND_LAYER_Z: f32 = 0f32;
+const TRANSITION_LAYER_Z: f32 = 1f32;
+const GROWTH_LAYER_Z: f32 = 1.8;
+const FOG_LAYER_Z: f32 = 2f32;
+
+// Chunks stay loaded this far past the edge of the view, in chunks, so
+// small camera moves don't churn them
+const UNLOAD_MARGIN: f32 = 1f32;
+
+/// The tilemaps making up one loaded chunk
+pub struct ChunkLayers {
+    pub ground: Entity,
+    pub transitions: Entity,
+    pub growth: Entity,
+    fog: Entity,
+}
+
+/// Chunks currently spawned, by chunk coordinate
+#[derive(Resource, Default)]
+pub struct LoadedChunks {
+    pub chunks: HashMap<UVec2, ChunkLayers>,
+    /// Transition atlas and grid shape the loaded chunks were built with
+    built_with: Option<(Handle<Image>, GridShape)>,
+}
+
+impl LoadedChunks {
+    fn unload(&mut self, commands: &mut Commands, chunk: UVec2) {
+        if let Some(layers) = self.chunks.remove(&chunk) {
+            // Tiles are children of their tilemap, so they go with it
+            for layer in [layers.ground, layers.transitions, layers.growth, layers.fog] {
+                commands.entity(layer).despawn();
+            }
+        }
+    }
+}
+
+/// Chunk holding a map tile, and the tile's position inside it
+pub fn chunk_of(pos: UVec2) -> (UVec2, TilePos) {
+    let local = pos % CHUNK_TILES;
+    (
+        pos / CHUNK_TILES,
+        TilePos {
+            x: local.x,
+            y: local.y,
+        },
+    )
+}
+
+/// Spawns one chunk-sized tilemap at depth `z`; `tile` gives the texture
+/// index for each map tile, or `None` to leave it hidden
+fn spawn_layer(
+    commands: &mut Commands,
+    shape: GridShape,
+    chunk: UVec2,
+    texture: Handle<Image>,
+    z: f32,
+    tile: impl Fn(UVec2) -> Option<u32>,
+) -> Entity {
+    let size = TilemapSize {
+        x: CHUNK_TILES,
+        y: CHUNK_TILES,
+    };
+    let first = chunk * CHUNK_TILES;
+    let mut storage = TileStorage::empty(size);
+    let tilemap = commands.spawn_empty().id();
+    for y in 0..CHUNK_TILES {
+        for x in 0..CHUNK_TILES {
+            let tile_pos = TilePos { x, y };
+            let index = tile(first + UVec2::new(x, y));
+            let entity = commands
+                .spawn((
+                    TileBundle {
+                        position: tile_pos,
+                        tilemap_id: TilemapId(tilemap),
+                        texture_index: TileTextureIndex(index.unwrap_or_default()),
+                        visible: TileVisible(index.is_some()),
+                        ..default()
+                    },
+                    ChildOf(tilemap),
+                ))
+                .id();
+            storage.set(&tile_pos, entity);
+        }
+    }
+
+    // Without an anchor the first tile's center sits on the transform. Chunks
+    // start on even rows and columns, so offset hex lines keep their parity
+    // and a chunk's tiles land where the whole map's would.
+    commands.entity(tilemap).insert(TilemapBundle {
+        grid_size: TILE_SIZE.into(),
+        map_type: shape.map_type(),
+        size,
+        storage,
+        texture: TilemapTexture::Single(texture),
+        tile_size: TILE_SIZE,
+        anchor: TilemapAnchor::None,
+        transform: Transform::from_translation(shape.tile_center(first).extend(z)),
+        ..default()
+    });
+    tilemap
+}
+
+/// Loads chunks overlapping the camera's view and unloads those well outside it
+pub fn stream_chunks(
+    mut commands: Commands,
+    camera: Single<(&GlobalTransform, &Projection), With<MapCamera>>,
+    mut loaded: ResMut<LoadedChunks>,
+    grid: Res<TileGrid>,
+    autotiler: Res<Autotiler>,
+    shape: Res<GridShape>,
+    mut growth: ResMut<TileGrowth>,
+) {
+    let Some(atlas) = autotiler.atlas() else {
+        return;
+    };
+    // New transition rules mean a new atlas, and a new shape moves every
+    // tile; either way rebuild everything
+    let built_with = (atlas.clone(), *shape);
+    if loaded.built_with.as_ref() != Some(&built_with) {
+        let chunks: Vec<UVec2> = loaded.chunks.keys().copied().collect();
+        for chunk in chunks {
+            loaded.unload(&mut commands, chunk);
+        }
+        loaded.built_with = Some(built_with);
+    }
+
+    let (transform, projection) = *camera;
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+    let center = transform.translation().xy();
+    let view = Rect::from_corners(center + projection.area.min, center + projection.area.max);
+    let keep = view.inflate(UNLOAD_MARGIN * CHUNK_TILES as f32 * TILE_SIZE.x);
+
+    let stale: Vec<UVec2> = loaded
+        .chunks
+        .keys()
+        .copied()
+        .filter(|chunk| shape.chunk_rect(*chunk).intersect(keep).is_empty())
+        .collect();
+    for chunk in stale {
+        loaded.unload(&mut commands, chunk);
+    }
+
+    // Shapes other than square don't map chunks to axis-aligned world
+    // blocks, so each chunk's bounds are checked against the view
+    let chunk_count = MAP_TILES / CHUNK_TILES;
+    let visible = (0..chunk_count.y)
+        .flat_map(|y| (0..chunk_count.x).map(move |x| UVec2::new(x, y)))
+        .filter(|chunk| !shape.chunk_rect(*chunk).intersect(view).is_empty());
+    for chunk in visible {
+        if loaded.chunks.contains_key(&chunk) {
+            continue;
+        }
+        let ground = spawn_layer(
+            &mut commands,
+            *shape,
+            chunk,
+            autotiler.source(),
+            GROUND_LAYER_Z,
+            |pos| Some(autotiler.tiles_for(&grid, *shape, pos).0),
+        );
+        commands.entity(ground).insert(PickableTilemap {
+            origin: chunk * CHUNK_TILES,
+        });
+        let transitions = spawn_layer(
+            &mut commands,
+            *shape,
+            chunk,
+            atlas.clone(),
+            TRANSITION_LAYER_Z,
+            |pos| autotiler.tiles_for(&grid, *shape, pos).1,
+        );
+        // Every tile is spawned; the growth refresh shows and tints them
+        let growth_layer = spawn_layer(
+            &mut commands,
+            *shape,
+            chunk,
+            autotiler.source(),
+            GROWTH_LAYER_Z,
+            |_| Some(GROWTH_TILE_INDEX),
+        );
+        growth.mark_chunk_dirty(chunk);
+        let fog = spawn_layer(
+            &mut commands,
+            *shape,
+            chunk,
+            autotiler.source(),
+            FOG_LAYER_Z,
+            |_| Some(FOG_TILE_INDEX),
+        );
+        commands.entity(fog).insert(FogTilemapOverlay);
+        loaded.chunks.insert(
+            chunk,
+            ChunkLayers {
+                ground,
+                transitions,
+                growth: growth_layer,
+                fog,
+            },
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use bevy_ecs_tilemap::{
+    helpers::hex_grid::{
+        axial::AxialPos,
+        cube::{CubePos, FractionalCubePos},
+        neighbors::HexDirection,
+    },
+    prelude::*,
+};
+
+use super::{CHUNK_TILES, MAP_TILES, TILE_SIZE};
+
+// Edge-sharing neighbours on square and diamond grids
+const SQUARE_NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Nudges hex lines off tile edges so rounding picks one side consistently
+const HEX_LINE_NUDGE: f32 = 1e-4;
+
+/// Layout the map's tiles are drawn in; tile coordinates stay the same, so
+/// the ground, growth and selections carry over when switching. Every shape
+/// fits its cells in a `TILE_SIZE` box; hex rows and columns are offset on
+/// odd lines.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridShape {
+    #[default]
+    Square,
+    /// Pointy-topped hexes in offset rows
+    HexRow,
+    /// Flat-topped hexes in offset columns
+    HexColumn,
+    IsoDiamond,
+}
+
+impl GridShape {
+    pub const ALL: [GridShape; 4] = [
+        GridShape::Square,
+        GridShape::HexRow,
+        GridShape::HexColumn,
+        GridShape::IsoDiamond,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|shape| *shape == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn map_type(self) -> TilemapType {
+        match self {
+            GridShape::Square => TilemapType::Square,
+            GridShape::HexRow => TilemapType::Hexagon(HexCoordSystem::RowOdd),
+            GridShape::HexColumn => TilemapType::Hexagon(HexCoordSystem::ColumnOdd),
+            GridShape::IsoDiamond => TilemapType::Isometric(IsoCoordSystem::Diamond),
+        }
+    }
+
+    fn hex_coord_system(self) -> Option<HexCoordSystem> {
+        match self.map_type() {
+            TilemapType::Hexagon(coord_system) => Some(coord_system),
+            _ => None,
+        }
+    }
+
+    /// Tile center relative to the center of tile (0, 0)
+    fn unanchored_center(self, pos: UVec2) -> Vec2 {
+        TilePos::from(pos).center_in_world(
+            &MAP_TILES.into(),
+            &TILE_SIZE.into(),
+            &TILE_SIZE,
+            &self.map_type(),
+            &TilemapAnchor::None,
+        )
+    }
+
+    /// Box around every tile center, relative to the center of tile (0, 0);
+    /// offset lines make the second row and column extremes too
+    fn unanchored_center_bounds(self) -> Rect {
+        let edges = |size: u32| [0, 1, size - 2, size - 1];
+        let mut bounds = Rect::from_center_size(self.unanchored_center(UVec2::ZERO), Vec2::ZERO);
+        for y in edges(MAP_TILES.y) {
+            for x in edges(MAP_TILES.x) {
+                bounds = bounds.union_point(self.unanchored_center(UVec2::new(x, y)));
+            }
+        }
+        bounds
+    }
+
+    /// World position of the center of tile (0, 0), placing the map's center
+    /// on the origin
+    fn origin(self) -> Vec2 {
+        -self.unanchored_center_bounds().center()
+    }
+
+    /// World position of a tile's center
+    pub fn tile_center(self, pos: UVec2) -> Vec2 {
+        self.origin() + self.unanchored_center(pos)
+    }
+
+    /// Tile under a world position, if it's on the map
+    pub fn tile_at(self, position: Vec2) -> Option<UVec2> {
+        TilePos::from_world_pos(
+            &(position - self.origin()),
+            &MAP_TILES.into(),
+            &TILE_SIZE.into(),
+            &TILE_SIZE,
+            &self.map_type(),
+            &TilemapAnchor::None,
+        )
+        .map(UVec2::from)
+    }
+
+    /// Map area in world units, centered on the origin
+    pub fn map_rect(self) -> Rect {
+        let bounds = self.unanchored_center_bounds();
+        Rect::from_center_size(
+            Vec2::ZERO,
+            bounds.size() + Vec2::new(TILE_SIZE.x, TILE_SIZE.y),
+        )
+    }
+
+    /// World area a chunk's tiles cover, give or take the offset of odd lines
+    pub fn chunk_rect(self, chunk: UVec2) -> Rect {
+        let first = chunk * CHUNK_TILES;
+        let last = first + UVec2::splat(CHUNK_TILES - 1);
+        let corners = [
+            first,
+            UVec2::new(last.x, first.y),
+            UVec2::new(first.x, last.y),
+            last,
+        ];
+        let origin = self.origin();
+        let bounds = corners
+            .into_iter()
+            .map(|corner| {
+                Rect::from_center_size(origin + self.unanchored_center(corner), Vec2::ZERO)
+            })
+            .reduce(|a, b| a.union(b))
+            .unwrap_or_default();
+        bounds.inflate(TILE_SIZE.x)
+    }
+
+    /// Tiles sharing an edge with `pos`: four on square and diamond grids,
+    /// six on hex grids
+    pub fn neighbours(self, pos: UVec2) -> Vec<UVec2> {
+        match self.hex_coord_system() {
+            Some(coord_system) => {
+                let axial = AxialPos::from_tile_pos_given_coord_system(&pos.into(), coord_system);
+                (0..6usize)
+                    .filter_map(|direction| {
+                        axial
+                            .offset(HexDirection::from(direction))
+                            .as_tile_pos_given_coord_system_and_map_size(
+                                coord_system,
+                                &MAP_TILES.into(),
+                            )
+                    })
+                    .map(UVec2::from)
+                    .collect()
+            }
+            None => SQUARE_NEIGHBOURS
+                .iter()
+                .map(|offset| pos.as_ivec2() + *offset)
+                .filter(|next| {
+                    next.cmpge(IVec2::ZERO).all() && next.as_uvec2().cmplt(MAP_TILES).all()
+                })
+                .map(|next| next.as_uvec2())
+                .collect(),
+        }
+    }
+
+    /// Steps between two tiles moving only to neighbours
+    pub fn distance(self, a: UVec2, b: UVec2) -> u32 {
+        match self.hex_coord_system() {
+            Some(coord_system) => {
+                let a = AxialPos::from_tile_pos_given_coord_system(&a.into(), coord_system);
+                let b = AxialPos::from_tile_pos_given_coord_system(&b.into(), coord_system);
+                a.distance_from(&b).unsigned_abs()
+            }
+            None => {
+                let delta = (a.as_ivec2() - b.as_ivec2()).abs();
+                (delta.x + delta.y) as u32
+            }
+        }
+    }
+
+    /// Connected run of tiles from `a` to `b`, both included, each a
+    /// neighbour of the one before
+    pub fn line(self, a: UVec2, b: UVec2) -> Vec<UVec2> {
+        let Some(coord_system) = self.hex_coord_system() else {
+            // Walk the grid, stepping along whichever axis the straight line
+            // crosses into next
+            let delta = b.as_ivec2() - a.as_ivec2();
+            let (steps, sign) = (delta.abs(), delta.signum());
+            let mut tile = a.as_ivec2();
+            let mut taken = IVec2::ZERO;
+            let mut tiles = vec![a];
+            while taken != steps {
+                // Compares (taken.x + 0.5) / steps.x with the same for y,
+                // cross-multiplied to stay exact
+                let x_first = (1 + 2 * taken.x) * steps.y <= (1 + 2 * taken.y) * steps.x;
+                if taken.y == steps.y || (taken.x < steps.x && x_first) {
+                    tile.x += sign.x;
+                    taken.x += 1;
+                } else {
+                    tile.y += sign.y;
+                    taken.y += 1;
+                }
+                tiles.push(tile.as_uvec2());
+            }
+            return tiles;
+        };
+
+        // Sample the straight line in cube coordinates and round to hexes
+        let cube = |pos: UVec2| {
+            CubePos::from(AxialPos::from_tile_pos_given_coord_system(
+                &pos.into(),
+                coord_system,
+            ))
+        };
+        let (from, to) = (cube(a), cube(b));
+        let count = self.distance(a, b).max(1);
+        (0..=count)
+            .filter_map(|i| {
+                let t = i as f32 / count as f32;
+                let lerp =
+                    |from: i32, to: i32, nudge: f32| from as f32 + nudge + (to - from) as f32 * t;
+                let rounded = FractionalCubePos::new(
+                    lerp(from.q, to.q, HEX_LINE_NUDGE),
+                    lerp(from.r, to.r, HEX_LINE_NUDGE),
+                    lerp(from.s, to.s, -2f32 * HEX_LINE_NUDGE),
+                )
+                .round();
+                AxialPos::from(rounded)
+                    .as_tile_pos_given_coord_system_and_map_size(coord_system, &MAP_TILES.into())
+            })
+            .map(UVec2::from)
+            .collect()
+    }
+
+    /// Tiles exactly `radius` steps from `center`
+    pub fn ring(self, center: UVec2, radius: u32) -> Vec<UVec2> {
+        self.within(center, radius)
+            .filter(|pos| self.distance(center, *pos) == radius)
+            .collect()
+    }
+
+    /// Tiles at most `radius` steps from `center`
+    pub fn area(self, center: UVec2, radius: u32) -> Vec<UVec2> {
+        self.within(center, radius)
+            .filter(|pos| self.distance(center, *pos) <= radius)
+            .collect()
+    }
+
+    /// Map tiles in the box `radius` tiles around `center`; no grid reaches
+    /// further than that in `radius` steps
+    fn within(self, center: UVec2, radius: u32) -> impl Iterator<Item = UVec2> {
+        let min = center.saturating_sub(UVec2::splat(radius));
+        let max = (center + UVec2::splat(radius)).min(MAP_TILES - 1);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| UVec2::new(x, y)))
+    }
+}
+
+/// G cycles the grid shape
+pub fn switch_grid_shape(mut shape: ResMut<GridShape>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::KeyG) {
+        *shape = shape.next();
+        info!("Grid shape: {:?}", *shape);
+    }
+}
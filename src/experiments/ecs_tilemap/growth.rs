@@ -0,0 +1,248 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{
+    CHUNK_TILES, MAP_TILES, TILE_SIZE,
+    autotile::TileGrid,
+    chunks::{LoadedChunks, chunk_of},
+    grid::GridShape,
+    map_assets::MapProperties,
+    picking::TileCursor,
+};
+use crate::growth::{
+    DEFAULT_GROWTH_RATE, DEFAULT_INITIAL_GROWTH_AGE, DEFAULT_MAX_GROWTH_AGE,
+    DEFAULT_RADIUS_EXPANSION_RATE, GROWTH_UPDATE_FREQUENCY, MAX_GROWTH_RADIUS, PATCH_SPACING,
+    growth_color,
+};
+use crate::terrain::tiles::TileKind;
+
+// One tile stands for one growth patch of the 3D sprawl, so its rates carry over
+const EXPANSION_RATE: f32 = DEFAULT_RADIUS_EXPANSION_RATE / PATCH_SPACING; // tiles per second
+const MAX_RADIUS: f32 = MAX_GROWTH_RADIUS / PATCH_SPACING; // tiles
+
+/// Growth tiles are this atlas tile tinted by age
+pub const GROWTH_TILE_INDEX: u32 = 224; // snow; pale enough to take the tint
+
+/// A colony spreading from one tile
+struct TileColony {
+    origin: UVec2,
+    /// In tiles
+    radius: f32,
+    /// Grown tiles that may still spread once the radius allows
+    frontier: Vec<UVec2>,
+}
+
+/// Crimson growth on the map's tile grid
+#[derive(Resource)]
+pub struct TileGrowth {
+    /// Age of the growth on each tile, if any
+    ages: Vec<Option<f32>>,
+    /// Tiles still getting older
+    maturing: Vec<UVec2>,
+    colonies: Vec<TileColony>,
+    /// Tiles an authored map marks `blocked`
+    blocked: HashSet<UVec2>,
+    /// Tiles whose growth tile needs redrawing
+    dirty: HashSet<UVec2>,
+}
+
+impl Default for TileGrowth {
+    fn default() -> Self {
+        Self {
+            ages: vec![None; (MAP_TILES.x * MAP_TILES.y) as usize],
+            maturing: Vec::new(),
+            colonies: Vec::new(),
+            blocked: HashSet::default(),
+            dirty: HashSet::default(),
+        }
+    }
+}
+
+impl TileGrowth {
+    fn index(pos: UVec2) -> usize {
+        (pos.y * MAP_TILES.x + pos.x) as usize
+    }
+
+    pub fn age(&self, pos: UVec2) -> Option<f32> {
+        self.ages[Self::index(pos)]
+    }
+
+    /// Water and rock stop the sprawl, as do tiles an authored map blocks
+    fn can_grow(&self, grid: &TileGrid, pos: UVec2) -> bool {
+        !self.blocked.contains(&pos)
+            && !matches!(
+                grid.kind(pos),
+                TileKind::DeepWater | TileKind::ShallowWater | TileKind::Rock
+            )
+    }
+
+    fn seed(&mut self, pos: UVec2) {
+        self.ages[Self::index(pos)] = Some(DEFAULT_INITIAL_GROWTH_AGE);
+        self.maturing.push(pos);
+        self.dirty.insert(pos);
+    }
+
+    /// Starts a colony on a tile; false if nothing can grow there
+    fn place_colony(&mut self, grid: &TileGrid, origin: UVec2) -> bool {
+        if !self.can_grow(grid, origin) {
+            return false;
+        }
+        if self.age(origin).is_none() {
+            self.seed(origin);
+        }
+        self.colonies.push(TileColony {
+            origin,
+            radius: 0f32,
+            frontier: vec![origin],
+        });
+        true
+    }
+
+    /// Widens every colony and floods its new radius from the frontier, so
+    /// growth flows around blocked tiles instead of jumping them; it spreads
+    /// to the neighbours of the current grid shape
+    fn spread(&mut self, grid: &TileGrid, shape: GridShape, delta: f32) {
+        let mut colonies = std::mem::take(&mut self.colonies);
+        for colony in &mut colonies {
+            colony.radius = (colony.radius + EXPANSION_RATE * delta).min(MAX_RADIUS);
+            let reach = colony.radius * TILE_SIZE.x;
+            let origin = shape.tile_center(colony.origin);
+            let mut queue = std::mem::take(&mut colony.frontier);
+            while let Some(tile) = queue.pop() {
+                let mut waiting = false;
+                for next in shape.neighbours(tile) {
+                    if self.age(next).is_some() || !self.can_grow(grid, next) {
+                        continue;
+                    }
+                    if shape.tile_center(next).distance(origin) > reach {
+                        waiting = true;
+                        continue;
+                    }
+                    self.seed(next);
+                    queue.push(next);
+                }
+                if waiting {
+                    colony.frontier.push(tile);
+                }
+            }
+        }
+        // A colony is done once it has nowhere left to go
+        colonies.retain(|colony| colony.radius < MAX_RADIUS && !colony.frontier.is_empty());
+        self.colonies = colonies;
+    }
+
+    fn mature(&mut self, delta: f32) {
+        let mut maturing = std::mem::take(&mut self.maturing);
+        maturing.retain(|pos| {
+            let age = self.ages[Self::index(*pos)].get_or_insert_default();
+            *age = (*age + DEFAULT_GROWTH_RATE * delta).min(DEFAULT_MAX_GROWTH_AGE);
+            self.dirty.insert(*pos);
+            *age < DEFAULT_MAX_GROWTH_AGE
+        });
+        self.maturing = maturing;
+    }
+
+    /// Queues every tile of a freshly spawned chunk for drawing
+    pub fn mark_chunk_dirty(&mut self, chunk: UVec2) {
+        let first = chunk * CHUNK_TILES;
+        for y in 0..CHUNK_TILES {
+            for x in 0..CHUNK_TILES {
+                self.dirty.insert(first + UVec2::new(x, y));
+            }
+        }
+    }
+}
+
+/// Right click seeds a colony on the tile under the cursor
+pub fn place_colony(
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor: Res<TileCursor>,
+    grid: Res<TileGrid>,
+    mut growth: ResMut<TileGrowth>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let Some(pos) = cursor.hovered.map(UVec2::from) else {
+        return;
+    };
+    if growth.place_colony(&grid, pos) {
+        info!("Colony seeded at tile {pos}");
+    } else {
+        info!("Nothing grows on {:?} at tile {pos}", grid.kind(pos));
+    }
+}
+
+/// Spreads and ages the growth at the 3D sprawl's update rate
+pub fn grow_tiles(
+    time: Res<Time>,
+    grid: Res<TileGrid>,
+    shape: Res<GridShape>,
+    mut growth: ResMut<TileGrowth>,
+    mut elapsed: Local<f32>,
+) {
+    *elapsed += time.delta_secs();
+    if *elapsed < GROWTH_UPDATE_FREQUENCY {
+        return;
+    }
+    *elapsed -= GROWTH_UPDATE_FREQUENCY;
+    growth.spread(&grid, *shape, GROWTH_UPDATE_FREQUENCY);
+    growth.mature(GROWTH_UPDATE_FREQUENCY);
+}
+
+/// Records tiles of authored maps whose tileset marks them `blocked`; runs
+/// after transforms propagate so the maps' placement is known. Authored maps
+/// are laid out in square tiles whatever the current grid shape.
+pub fn collect_blocked_tiles(
+    tiles: Query<(&TilePos, &TilemapId, &MapProperties), Added<MapProperties>>,
+    tilemaps: Query<(&GlobalTransform, &TilemapGridSize)>,
+    mut growth: ResMut<TileGrowth>,
+) {
+    for (tile_pos, tilemap_id, properties) in &tiles {
+        if !properties.flag("blocked") {
+            continue;
+        }
+        let Ok((transform, grid_size)) = tilemaps.get(tilemap_id.0) else {
+            continue;
+        };
+        // Without an anchor the first tile's center sits on the tilemap's transform
+        let local = Vec2::new(
+            tile_pos.x as f32 * grid_size.x,
+            tile_pos.y as f32 * grid_size.y,
+        );
+        let center = transform.transform_point(local.extend(0f32)).xy();
+        if let Some(pos) = GridShape::Square.tile_at(center) {
+            growth.blocked.insert(pos);
+        }
+    }
+}
+
+/// Tints loaded growth tiles by age and hides tiles without growth
+pub fn refresh_growth_tiles(
+    mut growth: ResMut<TileGrowth>,
+    loaded: Res<LoadedChunks>,
+    storages: Query<&TileStorage>,
+    mut tiles: Query<(&mut TileColor, &mut TileVisible)>,
+) {
+    if growth.dirty.is_empty() {
+        return;
+    }
+    for pos in std::mem::take(&mut growth.dirty) {
+        let (chunk, tile_pos) = chunk_of(pos);
+        let Some(tile) = loaded
+            .chunks
+            .get(&chunk)
+            .and_then(|layers| storages.get(layers.growth).ok())
+            .and_then(|storage| storage.get(&tile_pos))
+        else {
+            continue;
+        };
+        if let Ok((mut color, mut visible)) = tiles.get_mut(tile) {
+            let age = growth.age(pos);
+            visible.0 = age.is_some();
+            if let Some(age) = age {
+                color.0 = growth_color(age);
+            }
+        }
+    }
+}
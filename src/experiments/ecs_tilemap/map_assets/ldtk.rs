@@ -0,0 +1,466 @@
+use std::io;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use bevy_ecs_tilemap::prelude::TileFlip;
+use serde::Deserialize;
+
+use super::{
+    LayerContent, MapLayer, MapObjectDef, MapProperties, MapTile, MapTileset, PropertyValue,
+    TileMap, invalid_data,
+};
+
+// Tile flip bits in LDtk's `f` field
+const FLIP_X: u8 = 1;
+const FLIP_Y: u8 = 2;
+
+/// Loads an LDtk project (`.ldtk`); the asset is its first level and every
+/// level is also a labeled asset named after its identifier. Levels saved in
+/// separate files aren't supported.
+#[derive(Default)]
+pub struct LdtkLoader;
+
+impl AssetLoader for LdtkLoader {
+    type Asset = TileMap;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> io::Result<TileMap> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let project: LdtkProject = serde_json::from_slice(&bytes)?;
+        if project.external_levels {
+            return Err(invalid_data(
+                "levels saved in separate files aren't supported",
+            ));
+        }
+
+        // Every level shares the project's tilesets
+        let mut tileset_indices = HashMap::default();
+        let mut tilesets = Vec::new();
+        for tileset in &project.defs.tilesets {
+            // Internal icon sets have no image on disk
+            let Some(path) = &tileset.rel_path else {
+                continue;
+            };
+            if tileset.padding > 0f32 {
+                warn!("Tileset padding isn't supported; tiles from {path} may be offset");
+            }
+            let path = load_context
+                .asset_path()
+                .resolve_embed(path)
+                .map_err(|error| invalid_data(error.to_string()))?;
+            tileset_indices.insert(tileset.uid, tilesets.len());
+            tilesets.push(MapTileset {
+                image: load_context.load(path),
+                tile_size: Vec2::splat(tileset.tile_grid_size),
+                spacing: tileset.spacing,
+                tile_properties: tileset
+                    .custom_data
+                    .iter()
+                    .map(|tile| {
+                        let properties = [(
+                            "custom_data".to_owned(),
+                            PropertyValue::String(tile.data.clone()),
+                        )];
+                        (
+                            tile.tile_id,
+                            MapProperties(properties.into_iter().collect()),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut levels = Vec::with_capacity(project.levels.len());
+        for level in &project.levels {
+            let map = ldtk_level(level, &tilesets, &tileset_indices)?;
+            load_context.add_labeled_asset(level.identifier.clone(), map.clone());
+            levels.push(map);
+        }
+        levels
+            .into_iter()
+            .next()
+            .ok_or_else(|| invalid_data("project has no levels"))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ldtk"]
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkProject {
+    defs: LdtkDefs,
+    levels: Vec<LdtkLevel>,
+    #[serde(default)]
+    external_levels: bool,
+}
+
+#[derive(Deserialize)]
+struct LdtkDefs {
+    tilesets: Vec<LdtkTileset>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkTileset {
+    uid: i64,
+    rel_path: Option<String>,
+    tile_grid_size: f32,
+    #[serde(default)]
+    spacing: f32,
+    #[serde(default)]
+    padding: f32,
+    #[serde(default)]
+    custom_data: Vec<LdtkCustomData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkCustomData {
+    tile_id: u32,
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLevel {
+    identifier: String,
+    px_hei: f32,
+    #[serde(default)]
+    field_instances: Vec<LdtkField>,
+    layer_instances: Option<Vec<LdtkLayer>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLayer {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    kind: String,
+    #[serde(rename = "__cWid")]
+    columns: u32,
+    #[serde(rename = "__cHei")]
+    rows: u32,
+    #[serde(rename = "__gridSize")]
+    grid_size: f32,
+    #[serde(rename = "__tilesetDefUid")]
+    tileset_uid: Option<i64>,
+    #[serde(rename = "__opacity")]
+    opacity: f32,
+    #[serde(rename = "__pxTotalOffsetX")]
+    offset_x: f32,
+    #[serde(rename = "__pxTotalOffsetY")]
+    offset_y: f32,
+    visible: bool,
+    #[serde(default)]
+    grid_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    entity_instances: Vec<LdtkEntity>,
+}
+
+#[derive(Deserialize)]
+struct LdtkTile {
+    /// Top-left corner in the layer, y down
+    px: [f32; 2],
+    t: u32,
+    f: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkEntity {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    /// Pivot point in the layer, y down
+    px: [f32; 2],
+    width: f32,
+    height: f32,
+    #[serde(rename = "__pivot")]
+    pivot: [f32; 2],
+    #[serde(default)]
+    field_instances: Vec<LdtkField>,
+}
+
+#[derive(Deserialize)]
+struct LdtkField {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    kind: String,
+    #[serde(rename = "__value")]
+    value: serde_json::Value,
+}
+
+fn ldtk_level(
+    level: &LdtkLevel,
+    tilesets: &[MapTileset],
+    tileset_indices: &HashMap<i64, usize>,
+) -> io::Result<TileMap> {
+    let layers = level
+        .layer_instances
+        .as_ref()
+        .ok_or_else(|| invalid_data(format!("level {} has no layer data", level.identifier)))?;
+    Ok(TileMap {
+        tilesets: tilesets.to_vec(),
+        // LDtk lists the top layer first
+        layers: layers
+            .iter()
+            .rev()
+            .filter_map(|layer| ldtk_layer(layer, level.px_hei, tileset_indices))
+            .collect(),
+        properties: ldtk_fields(&level.field_instances),
+    })
+}
+
+/// A layer, or `None` for IntGrid layers with no tiles to draw
+fn ldtk_layer(
+    layer: &LdtkLayer,
+    level_height: f32,
+    tileset_indices: &HashMap<i64, usize>,
+) -> Option<MapLayer> {
+    let mut offset = Vec2::new(layer.offset_x, -layer.offset_y);
+    let content = if layer.kind == "Entities" {
+        LayerContent::Objects(
+            layer
+                .entity_instances
+                .iter()
+                .map(|entity| ldtk_entity(entity, level_height))
+                .collect(),
+        )
+    } else {
+        // Tiles layers are painted by hand; IntGrid and auto layers generate theirs
+        let placed = if layer.kind == "Tiles" {
+            &layer.grid_tiles
+        } else {
+            &layer.auto_layer_tiles
+        };
+        let tileset = *tileset_indices.get(&layer.tileset_uid?)?;
+        let size = UVec2::new(layer.columns, layer.rows);
+        let mut tiles = vec![None; (size.x * size.y) as usize];
+        for tile in placed {
+            let cell = (Vec2::from(tile.px) / layer.grid_size).as_uvec2();
+            if cell.cmpge(size).any() {
+                continue;
+            }
+            // Stacked tiles are listed bottom first; only the top one is kept
+            let y = size.y - 1 - cell.y;
+            tiles[(y * size.x + cell.x) as usize] = Some(MapTile {
+                tileset,
+                id: tile.t,
+                flip: TileFlip {
+                    x: tile.f & FLIP_X != 0,
+                    y: tile.f & FLIP_Y != 0,
+                    d: false,
+                },
+            });
+        }
+        // The grid can overhang the level's bottom edge; keep its top aligned
+        offset.y += level_height - size.y as f32 * layer.grid_size;
+        LayerContent::Tiles {
+            size,
+            grid_size: Vec2::splat(layer.grid_size),
+            tiles,
+        }
+    };
+    Some(MapLayer {
+        name: layer.identifier.clone(),
+        offset,
+        opacity: layer.opacity,
+        visible: layer.visible,
+        properties: MapProperties::default(),
+        content,
+    })
+}
+
+fn ldtk_entity(entity: &LdtkEntity, level_height: f32) -> MapObjectDef {
+    let size = Vec2::new(entity.width, entity.height);
+    let center = Vec2::from(entity.px) - Vec2::from(entity.pivot) * size + size / 2f32;
+    MapObjectDef {
+        name: entity.identifier.clone(),
+        class: entity.identifier.clone(),
+        center: Vec2::new(center.x, level_height - center.y),
+        size,
+        properties: ldtk_fields(&entity.field_instances),
+    }
+}
+
+fn ldtk_fields(fields: &[LdtkField]) -> MapProperties {
+    MapProperties(
+        fields
+            .iter()
+            .filter_map(|field| {
+                let kind = match field.kind.as_str() {
+                    "Int" => "int",
+                    "Float" => "float",
+                    "Bool" => "bool",
+                    "Color" => "color",
+                    _ => "string",
+                };
+                let value = PropertyValue::from_json(kind, &field.value)?;
+                Some((field.identifier.clone(), value))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 40px tall level: an entity layer over a 2x3 tile layer of 16px cells,
+    // whose grid overhangs the bottom edge by 8px
+    const LEVEL: &str = r##"{
+  "identifier": "Level_0",
+  "pxHei": 40,
+  "fieldInstances": [
+    { "__identifier": "tint", "__type": "Color", "__value": "#ff0000" },
+    { "__identifier": "waves", "__type": "Int", "__value": 3 },
+    { "__identifier": "boss", "__type": "String", "__value": null }
+  ],
+  "layerInstances": [
+    {
+      "__identifier": "Spawns",
+      "__type": "Entities",
+      "__cWid": 3,
+      "__cHei": 3,
+      "__gridSize": 16,
+      "__tilesetDefUid": null,
+      "__opacity": 1,
+      "__pxTotalOffsetX": 0,
+      "__pxTotalOffsetY": 0,
+      "visible": true,
+      "entityInstances": [
+        {
+          "__identifier": "Tower",
+          "px": [20, 30],
+          "width": 10,
+          "height": 4,
+          "__pivot": [0.5, 1],
+          "fieldInstances": [{ "__identifier": "range", "__type": "Float", "__value": 2.5 }]
+        }
+      ]
+    },
+    {
+      "__identifier": "Ground",
+      "__type": "Tiles",
+      "__cWid": 2,
+      "__cHei": 3,
+      "__gridSize": 16,
+      "__tilesetDefUid": 7,
+      "__opacity": 0.5,
+      "__pxTotalOffsetX": 2,
+      "__pxTotalOffsetY": 4,
+      "visible": false,
+      "gridTiles": [
+        { "px": [0, 0], "t": 4, "f": 0 },
+        { "px": [16, 0], "t": 5, "f": 3 },
+        { "px": [16, 32], "t": 6, "f": 2 },
+        { "px": [48, 0], "t": 9, "f": 0 }
+      ]
+    },
+    {
+      "__identifier": "Collision",
+      "__type": "IntGrid",
+      "__cWid": 2,
+      "__cHei": 3,
+      "__gridSize": 16,
+      "__tilesetDefUid": null,
+      "__opacity": 1,
+      "__pxTotalOffsetX": 0,
+      "__pxTotalOffsetY": 0,
+      "visible": true
+    }
+  ]
+}"##;
+
+    fn level() -> TileMap {
+        let level: LdtkLevel = serde_json::from_str(LEVEL).unwrap();
+        let tileset_indices = [(7, 0)].into_iter().collect();
+        ldtk_level(&level, &[], &tileset_indices).unwrap()
+    }
+
+    #[test]
+    fn layers_are_bottom_first_without_bare_int_grids() {
+        let names: Vec<String> = level().layers.into_iter().map(|layer| layer.name).collect();
+        assert_eq!(names, ["Ground", "Spawns"]);
+    }
+
+    #[test]
+    fn tiles_flip_rows_and_keep_the_top_aligned() {
+        let map = level();
+        let ground = &map.layers[0];
+        let LayerContent::Tiles {
+            size,
+            grid_size,
+            tiles,
+        } = &ground.content
+        else {
+            panic!("expected a tile layer");
+        };
+        assert_eq!(*size, UVec2::new(2, 3));
+        assert_eq!(*grid_size, Vec2::splat(16f32));
+
+        // The top row in the file is the last row here; tiles off the grid
+        // are dropped
+        let ids: Vec<Option<u32>> = tiles.iter().map(|tile| tile.map(|tile| tile.id)).collect();
+        assert_eq!(ids, [None, Some(6), None, None, Some(4), Some(5)]);
+        let flipped = tiles[5].unwrap().flip;
+        assert!(flipped.x && flipped.y && !flipped.d);
+        let flipped = tiles[1].unwrap().flip;
+        assert!(!flipped.x && flipped.y);
+
+        // 48px of grid on a 40px level is pulled down 8px, on top of the
+        // layer's own offset with y flipped
+        assert_eq!(ground.offset, Vec2::new(2f32, -12f32));
+        assert_eq!(ground.opacity, 0.5);
+        assert!(!ground.visible);
+    }
+
+    #[test]
+    fn entities_are_centered_from_their_pivot_with_y_up() {
+        let map = level();
+        let LayerContent::Objects(objects) = &map.layers[1].content else {
+            panic!("expected an object layer");
+        };
+        let [tower] = &objects[..] else {
+            panic!("expected one entity");
+        };
+        // Pivot at the bottom middle: (20, 30) is the middle of its bottom edge
+        assert_eq!(tower.class, "Tower");
+        assert_eq!(tower.center, Vec2::new(20f32, 12f32));
+        assert_eq!(tower.size, Vec2::new(10f32, 4f32));
+        assert_eq!(tower.properties.float("range"), Some(2.5));
+    }
+
+    #[test]
+    fn level_fields_become_properties() {
+        let properties = level().properties;
+        assert_eq!(
+            properties.color("tint"),
+            Some(Color::srgb(1f32, 0f32, 0f32))
+        );
+        assert_eq!(properties.0["waves"], PropertyValue::Int(3));
+        assert!(!properties.0.contains_key("boss"));
+    }
+
+    #[test]
+    fn levels_without_layer_data_are_rejected() {
+        let level: LdtkLevel =
+            serde_json::from_str(r#"{ "identifier": "Level_1", "pxHei": 16 }"#).unwrap();
+        assert!(ldtk_level(&level, &[], &HashMap::default()).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+use std::{fmt, io};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_ecs_tilemap::prelude::*;
+
+mod ldtk;
+mod tiled;
+
+use self::{ldtk::LdtkLoader, tiled::TiledLoader};
+
+// Depth between consecutive layers of one map
+const LAYER_SPACING: f32 = 0.01;
+
+/// Value of a custom property set in the map editor
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Color(Color),
+    String(String),
+}
+
+impl PropertyValue {
+    /// Parses a property from its editor type name (`bool`, `int`, `float`,
+    /// `color`; anything else is kept as a string) and text value
+    fn parse(kind: &str, value: &str) -> Option<Self> {
+        match kind {
+            "bool" => Some(Self::Bool(value == "true")),
+            "int" | "object" => value.parse().ok().map(Self::Int),
+            "float" => value.parse().ok().map(Self::Float),
+            "color" => parse_color(value).map(Self::Color),
+            _ => Some(Self::String(value.to_owned())),
+        }
+    }
+
+    /// Same as [`PropertyValue::parse`] for a JSON value; `null` is no value
+    fn from_json(kind: &str, value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(value) => Self::parse(kind, value),
+            value => Self::parse(kind, &value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyValue::Bool(value) => write!(f, "{value}"),
+            PropertyValue::Int(value) => write!(f, "{value}"),
+            PropertyValue::Float(value) => write!(f, "{value}"),
+            PropertyValue::Color(value) => write!(f, "{}", value.to_srgba().to_hex()),
+            PropertyValue::String(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// `#RRGGBB`, or Tiled's `#AARRGGBB`
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let hex = match hex.len() {
+        8 => format!("{}{}", &hex[2..], &hex[..2]),
+        _ => hex.to_owned(),
+    };
+    Srgba::hex(hex).ok().map(Color::from)
+}
+
+/// Custom properties of a map, layer, tile or object
+#[derive(Component, Debug, Clone, Default)]
+pub struct MapProperties(pub HashMap<String, PropertyValue>);
+
+impl MapProperties {
+    pub fn float(&self, name: &str) -> Option<f32> {
+        match self.0.get(name)? {
+            PropertyValue::Float(value) => Some(*value as f32),
+            PropertyValue::Int(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
+
+    /// True only for a `bool` property set to true
+    pub fn flag(&self, name: &str) -> bool {
+        matches!(self.0.get(name), Some(PropertyValue::Bool(true)))
+    }
+
+    pub fn color(&self, name: &str) -> Option<Color> {
+        match self.0.get(name)? {
+            PropertyValue::Color(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MapProperties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.0.keys().collect();
+        names.sort();
+        write!(f, "{{")?;
+        for (i, name) in names.into_iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            write!(f, "{separator}{name}: {}", self.0[name])?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A tileset image; tile ids count along rows from the top-left tile
+#[derive(Debug, Clone)]
+pub struct MapTileset {
+    pub image: Handle<Image>,
+    pub tile_size: Vec2,
+    /// Gap between tiles in the image, in pixels
+    pub spacing: f32,
+    pub tile_properties: HashMap<u32, MapProperties>,
+}
+
+/// One placed tile
+#[derive(Debug, Clone, Copy)]
+pub struct MapTile {
+    /// Index into [`TileMap::tilesets`]
+    pub tileset: usize,
+    pub id: u32,
+    pub flip: TileFlip,
+}
+
+/// A point or area placed on an object layer
+#[derive(Debug, Clone)]
+pub struct MapObjectDef {
+    pub name: String,
+    pub class: String,
+    /// Center, in map pixels from the bottom-left corner
+    pub center: Vec2,
+    /// Zero for point objects
+    pub size: Vec2,
+    pub properties: MapProperties,
+}
+
+#[derive(Debug, Clone)]
+pub enum LayerContent {
+    Tiles {
+        size: UVec2,
+        grid_size: Vec2,
+        /// Row-major, rows running south to north
+        tiles: Vec<Option<MapTile>>,
+    },
+    Objects(Vec<MapObjectDef>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MapLayer {
+    pub name: String,
+    /// In map pixels, y up
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub visible: bool,
+    pub properties: MapProperties,
+    pub content: LayerContent,
+}
+
+/// A map authored in Tiled (`.tmx`, `.tmj`) or one level of an LDtk project
+/// (`.ldtk`; the first level, or `project.ldtk#Level_Name`)
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TileMap {
+    pub tilesets: Vec<MapTileset>,
+    /// Bottom layer first
+    pub layers: Vec<MapLayer>,
+    pub properties: MapProperties,
+}
+
+/// Spawns a map's layers as tilemaps and its objects as entities under this
+/// entity, once the map has loaded; the map's bottom-left corner sits on the
+/// entity's transform. Respawns if the file changes on disk.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct TileMapInstance(pub Handle<TileMap>);
+
+/// Marks instances whose map has been spawned
+#[derive(Component)]
+struct TileMapSpawned;
+
+/// An object from a map's object layer
+#[derive(Component, Debug)]
+pub struct MapObject {
+    pub class: String,
+    pub layer: String,
+    pub size: Vec2,
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+pub struct MapAssetsPlugin;
+
+impl Plugin for MapAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TileMap>()
+            .register_asset_loader(TiledLoader)
+            .register_asset_loader(LdtkLoader)
+            .add_systems(Update, (respawn_changed_maps, spawn_tile_maps).chain());
+    }
+}
+
+fn spawn_tile_maps(
+    mut commands: Commands,
+    maps: Res<Assets<TileMap>>,
+    instances: Query<(Entity, &TileMapInstance), Without<TileMapSpawned>>,
+) {
+    for (root, instance) in &instances {
+        let Some(map) = maps.get(&instance.0) else {
+            continue;
+        };
+        for (depth, layer) in map.layers.iter().enumerate() {
+            let z = depth as f32 * LAYER_SPACING;
+            match &layer.content {
+                LayerContent::Tiles {
+                    size,
+                    grid_size,
+                    tiles,
+                } => {
+                    // A tilemap draws from one texture, so a layer mixing
+                    // tilesets becomes one tilemap per tileset
+                    for (index, tileset) in map.tilesets.iter().enumerate() {
+                        let tiles = tiles.iter().enumerate().filter_map(|(i, tile)| {
+                            tile.filter(|tile| tile.tileset == index)
+                                .map(|tile| (i as u32, tile))
+                        });
+                        spawn_tile_layer(
+                            &mut commands,
+                            root,
+                            layer,
+                            (*size, *grid_size, z),
+                            tileset,
+                            tiles,
+                        );
+                    }
+                }
+                LayerContent::Objects(objects) => {
+                    for object in objects {
+                        let position = layer.offset + object.center;
+                        commands.spawn((
+                            Name::new(object.name.clone()),
+                            MapObject {
+                                class: object.class.clone(),
+                                layer: layer.name.clone(),
+                                size: object.size,
+                            },
+                            object.properties.clone(),
+                            Transform::from_translation(position.extend(z)),
+                            if layer.visible {
+                                Visibility::Inherited
+                            } else {
+                                Visibility::Hidden
+                            },
+                            ChildOf(root),
+                        ));
+                    }
+                }
+            }
+        }
+        commands
+            .entity(root)
+            .insert((TileMapSpawned, map.properties.clone()));
+    }
+}
+
+/// Spawns the tiles of one layer that come from one tileset; `tiles` yields
+/// each tile with its index in the layer
+fn spawn_tile_layer(
+    commands: &mut Commands,
+    root: Entity,
+    layer: &MapLayer,
+    (size, grid_size, z): (UVec2, Vec2, f32),
+    tileset: &MapTileset,
+    tiles: impl Iterator<Item = (u32, MapTile)>,
+) {
+    let map_size = TilemapSize {
+        x: size.x,
+        y: size.y,
+    };
+    let mut storage = TileStorage::empty(map_size);
+    let tilemap = commands.spawn(ChildOf(root)).id();
+    let mut count = 0;
+    for (i, tile) in tiles {
+        let position = TilePos {
+            x: i % size.x,
+            y: i / size.x,
+        };
+        let mut entity = commands.spawn((
+            TileBundle {
+                position,
+                tilemap_id: TilemapId(tilemap),
+                texture_index: TileTextureIndex(tile.id),
+                flip: tile.flip,
+                color: TileColor(Color::WHITE.with_alpha(layer.opacity)),
+                ..default()
+            },
+            ChildOf(tilemap),
+        ));
+        if let Some(properties) = tileset.tile_properties.get(&tile.id) {
+            entity.insert(properties.clone());
+        }
+        storage.set(&position, entity.id());
+        count += 1;
+    }
+    if count == 0 {
+        commands.entity(tilemap).despawn();
+        return;
+    }
+
+    // Without an anchor the first tile's center sits on the transform
+    let first_tile = layer.offset + grid_size / 2f32;
+    commands.entity(tilemap).insert((
+        Name::new(layer.name.clone()),
+        layer.properties.clone(),
+        TilemapBundle {
+            grid_size: TilemapGridSize {
+                x: grid_size.x,
+                y: grid_size.y,
+            },
+            map_type: TilemapType::Square,
+            size: map_size,
+            spacing: TilemapSpacing {
+                x: tileset.spacing,
+                y: tileset.spacing,
+            },
+            storage,
+            texture: TilemapTexture::Single(tileset.image.clone()),
+            tile_size: TilemapTileSize {
+                x: tileset.tile_size.x,
+                y: tileset.tile_size.y,
+            },
+            anchor: TilemapAnchor::None,
+            transform: Transform::from_translation(first_tile.extend(z)),
+            visibility: if layer.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            ..default()
+        },
+    ));
+}
+
+/// Clears instances whose map was edited so they spawn again
+fn respawn_changed_maps(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<TileMap>>,
+    instances: Query<(Entity, &TileMapInstance), With<TileMapSpawned>>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        for (root, instance) in &instances {
+            if instance.0.id() == *id {
+                info!("Map changed on disk, respawning");
+                commands
+                    .entity(root)
+                    .despawn_related::<Children>()
+                    .remove::<TileMapSpawned>();
+            }
+        }
+    }
+}
@@ -0,0 +1,996 @@
+use std::{io, str::FromStr};
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use bevy_ecs_tilemap::prelude::TileFlip;
+use roxmltree::{Document, Node};
+use serde::Deserialize;
+
+use super::{
+    LayerContent, MapLayer, MapObjectDef, MapProperties, MapTile, MapTileset, PropertyValue,
+    TileMap, invalid_data,
+};
+
+// Flags Tiled packs into the top bits of a tile's global id
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const GID_MASK: u32 = 0x0fff_ffff; // also drops the hexagonal rotation flag
+
+/// Loads Tiled maps saved as XML (`.tmx`) or JSON (`.tmj`), with embedded or
+/// external (`.tsx`, `.tsj`) tilesets. Only finite orthogonal maps with CSV,
+/// XML or uncompressed Base64 layer data are supported.
+#[derive(Default)]
+pub struct TiledLoader;
+
+impl AssetLoader for TiledLoader {
+    type Asset = TileMap;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> io::Result<TileMap> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = utf8(&bytes)?;
+        let map = if is_json(load_context.asset_path()) {
+            parse_tmj(text)?
+        } else {
+            parse_tmx(text)?
+        };
+
+        let mut tilesets = Vec::with_capacity(map.tilesets.len());
+        for tileset in map.tilesets {
+            // Image paths are relative to the file that names them
+            let (tileset, base) = match tileset {
+                TilesetRef::Embedded(tileset) => (tileset, load_context.asset_path().clone()),
+                TilesetRef::External(source) => {
+                    let path = resolve(load_context.asset_path(), &source)?;
+                    let bytes = load_context
+                        .read_asset_bytes(path.clone())
+                        .await
+                        .map_err(io::Error::other)?;
+                    let text = utf8(&bytes)?;
+                    let tileset = if is_json(&path) {
+                        tmj_tileset(serde_json::from_str(text)?)?
+                    } else {
+                        tsx_tileset(Document::parse(text).map_err(xml_error)?.root_element())?
+                    };
+                    (tileset, path)
+                }
+            };
+            if tileset.margin > 0f32 {
+                warn!("Tileset margins aren't supported; tiles from {base} may be offset");
+            }
+            tilesets.push(MapTileset {
+                image: load_context.load(resolve(&base, &tileset.image)?),
+                tile_size: tileset.tile_size,
+                spacing: tileset.spacing,
+                tile_properties: tileset.tile_properties,
+            });
+        }
+
+        Ok(TileMap {
+            tilesets,
+            layers: map.layers,
+            properties: map.properties,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx", "tmj"]
+    }
+}
+
+/// A map with its layers decoded and its tilesets still to be loaded
+struct ParsedMap {
+    tilesets: Vec<TilesetRef>,
+    layers: Vec<MapLayer>,
+    properties: MapProperties,
+}
+
+enum TilesetRef {
+    Embedded(TilesetDef),
+    /// Path of a `.tsx` or `.tsj` file, relative to the map
+    External(String),
+}
+
+struct TilesetDef {
+    tile_size: Vec2,
+    spacing: f32,
+    margin: f32,
+    /// Path relative to the file the tileset was read from
+    image: String,
+    tile_properties: HashMap<u32, MapProperties>,
+}
+
+/// What layers need to know about the map they're on
+struct MapInfo {
+    size: UVec2,
+    tile_size: Vec2,
+    /// First global id of each tileset, in ascending order
+    first_gids: Vec<u32>,
+}
+
+impl MapInfo {
+    fn pixel_height(&self) -> f32 {
+        self.size.y as f32 * self.tile_size.y
+    }
+
+    /// Decodes a layer's global ids, listed from the top row down, into tiles
+    /// with rows running south to north
+    fn tiles(&self, gids: &[u32], size: UVec2) -> io::Result<Vec<Option<MapTile>>> {
+        if gids.len() != (size.x * size.y) as usize {
+            return Err(invalid_data(format!(
+                "layer has {} tiles, expected {}x{}",
+                gids.len(),
+                size.x,
+                size.y
+            )));
+        }
+        Ok(gids
+            .chunks_exact(size.x as usize)
+            .rev()
+            .flatten()
+            .map(|gid| self.tile(*gid))
+            .collect())
+    }
+
+    fn tile(&self, gid: u32) -> Option<MapTile> {
+        let id = gid & GID_MASK;
+        if id == 0 {
+            return None;
+        }
+        let tileset = self.first_gids.iter().rposition(|first| *first <= id)?;
+        Some(MapTile {
+            tileset,
+            id: id - self.first_gids[tileset],
+            flip: TileFlip {
+                x: gid & FLIPPED_HORIZONTALLY != 0,
+                y: gid & FLIPPED_VERTICALLY != 0,
+                d: gid & FLIPPED_DIAGONALLY != 0,
+            },
+        })
+    }
+
+    /// An object from its Tiled position, which is the top-left corner, or the
+    /// bottom-left for tile objects, with y down
+    fn object(
+        &self,
+        name: &str,
+        class: &str,
+        position: Vec2,
+        size: Vec2,
+        is_tile: bool,
+        properties: MapProperties,
+    ) -> MapObjectDef {
+        let top = if is_tile {
+            position.y - size.y
+        } else {
+            position.y
+        };
+        MapObjectDef {
+            name: name.to_owned(),
+            class: class.to_owned(),
+            center: Vec2::new(
+                position.x + size.x / 2f32,
+                self.pixel_height() - top - size.y / 2f32,
+            ),
+            size,
+            properties,
+        }
+    }
+}
+
+/// Offset, opacity and visibility a group passes down to its layers
+struct GroupState {
+    offset: Vec2,
+    opacity: f32,
+    visible: bool,
+}
+
+impl Default for GroupState {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            opacity: 1f32,
+            visible: true,
+        }
+    }
+}
+
+fn utf8(bytes: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|error| invalid_data(error.to_string()))
+}
+
+fn is_json(path: &AssetPath) -> bool {
+    path.path()
+        .extension()
+        .is_some_and(|extension| extension == "tmj" || extension == "tsj" || extension == "json")
+}
+
+fn resolve(base: &AssetPath, relative: &str) -> io::Result<AssetPath<'static>> {
+    base.resolve_embed(relative)
+        .map_err(|error| invalid_data(error.to_string()))
+}
+
+fn check_map(orientation: &str, infinite: bool) -> io::Result<()> {
+    if orientation != "orthogonal" {
+        return Err(invalid_data(format!(
+            "{orientation} maps aren't supported, only orthogonal ones"
+        )));
+    }
+    if infinite {
+        return Err(invalid_data("infinite maps aren't supported"));
+    }
+    Ok(())
+}
+
+/// Global ids from uncompressed Base64 layer data
+fn base64_gids(text: &str) -> io::Result<Vec<u32>> {
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return Err(invalid_data("layer data isn't valid Base64")),
+        };
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+        .collect())
+}
+
+fn unsupported_compression(compression: &str) -> io::Error {
+    invalid_data(format!(
+        "{compression}-compressed layer data isn't supported; save layers as CSV"
+    ))
+}
+
+// TMX (XML)
+
+fn xml_error(error: roxmltree::Error) -> io::Error {
+    invalid_data(error.to_string())
+}
+
+fn attribute<T: FromStr>(node: Node, name: &str) -> io::Result<T> {
+    let element = node.tag_name().name();
+    node.attribute(name)
+        .ok_or_else(|| invalid_data(format!("<{element}> is missing `{name}`")))?
+        .parse()
+        .map_err(|_| invalid_data(format!("<{element}> has an invalid `{name}`")))
+}
+
+fn attribute_or<T: FromStr>(node: Node, name: &str, default: T) -> io::Result<T> {
+    match node.attribute(name) {
+        Some(_) => attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn parse_tmx(text: &str) -> io::Result<ParsedMap> {
+    let document = Document::parse(text).map_err(xml_error)?;
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err(invalid_data("not a Tiled map"));
+    }
+    check_map(
+        map.attribute("orientation").unwrap_or("orthogonal"),
+        map.attribute("infinite") == Some("1"),
+    )?;
+
+    let mut first_gids = Vec::new();
+    let mut tilesets = Vec::new();
+    for tileset in map.children().filter(|node| node.has_tag_name("tileset")) {
+        first_gids.push(attribute(tileset, "firstgid")?);
+        tilesets.push(match tileset.attribute("source") {
+            Some(source) => TilesetRef::External(source.to_owned()),
+            None => TilesetRef::Embedded(tsx_tileset(tileset)?),
+        });
+    }
+    let info = MapInfo {
+        size: UVec2::new(attribute(map, "width")?, attribute(map, "height")?),
+        tile_size: Vec2::new(attribute(map, "tilewidth")?, attribute(map, "tileheight")?),
+        first_gids,
+    };
+
+    let mut layers = Vec::new();
+    tmx_layers(map, &info, &GroupState::default(), &mut layers)?;
+    Ok(ParsedMap {
+        tilesets,
+        layers,
+        properties: tmx_properties(map),
+    })
+}
+
+/// Adds the layers under a map or group, flattening nested groups
+fn tmx_layers(
+    parent: Node,
+    info: &MapInfo,
+    group: &GroupState,
+    layers: &mut Vec<MapLayer>,
+) -> io::Result<()> {
+    for node in parent.children().filter(Node::is_element) {
+        let kind = node.tag_name().name();
+        if !matches!(kind, "layer" | "objectgroup" | "group") {
+            continue;
+        }
+        let state = GroupState {
+            offset: group.offset
+                + Vec2::new(
+                    attribute_or(node, "offsetx", 0f32)?,
+                    -attribute_or(node, "offsety", 0f32)?,
+                ),
+            opacity: group.opacity * attribute_or(node, "opacity", 1f32)?,
+            visible: group.visible && attribute_or(node, "visible", 1u8)? != 0,
+        };
+        let content = match kind {
+            "group" => {
+                tmx_layers(node, info, &state, layers)?;
+                continue;
+            }
+            "layer" => {
+                let size = UVec2::new(attribute(node, "width")?, attribute(node, "height")?);
+                LayerContent::Tiles {
+                    size,
+                    grid_size: info.tile_size,
+                    tiles: info.tiles(&tmx_gids(node)?, size)?,
+                }
+            }
+            _ => LayerContent::Objects(
+                node.children()
+                    .filter(|child| child.has_tag_name("object"))
+                    .map(|object| tmx_object(object, info))
+                    .collect::<io::Result<_>>()?,
+            ),
+        };
+        layers.push(MapLayer {
+            name: node.attribute("name").unwrap_or_default().to_owned(),
+            offset: state.offset,
+            opacity: state.opacity,
+            visible: state.visible,
+            properties: tmx_properties(node),
+            content,
+        });
+    }
+    Ok(())
+}
+
+fn tmx_gids(layer: Node) -> io::Result<Vec<u32>> {
+    let data = child(layer, "data").ok_or_else(|| invalid_data("tile layer has no <data>"))?;
+    if let Some(compression) = data.attribute("compression") {
+        return Err(unsupported_compression(compression));
+    }
+    match data.attribute("encoding") {
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(|gid| {
+                gid.trim()
+                    .parse()
+                    .map_err(|_| invalid_data(format!("invalid tile id `{}`", gid.trim())))
+            })
+            .collect(),
+        Some("base64") => base64_gids(data.text().unwrap_or_default()),
+        Some(encoding) => Err(invalid_data(format!("unknown layer encoding `{encoding}`"))),
+        None => data
+            .children()
+            .filter(|tile| tile.has_tag_name("tile"))
+            .map(|tile| attribute_or(tile, "gid", 0u32))
+            .collect(),
+    }
+}
+
+fn tmx_object(object: Node, info: &MapInfo) -> io::Result<MapObjectDef> {
+    // Tiled 1.9 renamed an object's `type` to `class`
+    let class = object
+        .attribute("class")
+        .or_else(|| object.attribute("type"))
+        .unwrap_or_default();
+    Ok(info.object(
+        object.attribute("name").unwrap_or_default(),
+        class,
+        Vec2::new(
+            attribute_or(object, "x", 0f32)?,
+            attribute_or(object, "y", 0f32)?,
+        ),
+        Vec2::new(
+            attribute_or(object, "width", 0f32)?,
+            attribute_or(object, "height", 0f32)?,
+        ),
+        object.attribute("gid").is_some(),
+        tmx_properties(object),
+    ))
+}
+
+fn tmx_properties(node: Node) -> MapProperties {
+    let properties = child(node, "properties")
+        .into_iter()
+        .flat_map(|properties| properties.children())
+        .filter(|property| property.has_tag_name("property"))
+        .filter_map(|property| {
+            let name = property.attribute("name")?;
+            // Multi-line strings keep their value in the element's text
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default();
+            let value =
+                PropertyValue::parse(property.attribute("type").unwrap_or("string"), value)?;
+            Some((name.to_owned(), value))
+        })
+        .collect();
+    MapProperties(properties)
+}
+
+/// A `<tileset>` element, embedded in a map or at the root of a `.tsx` file
+fn tsx_tileset(tileset: Node) -> io::Result<TilesetDef> {
+    let image = child(tileset, "image")
+        .and_then(|image| image.attribute("source"))
+        .ok_or_else(|| invalid_data("tilesets of separate images aren't supported"))?;
+    let mut tile_properties = HashMap::default();
+    for tile in tileset.children().filter(|node| node.has_tag_name("tile")) {
+        let properties = tmx_properties(tile);
+        if !properties.0.is_empty() {
+            tile_properties.insert(attribute(tile, "id")?, properties);
+        }
+    }
+    Ok(TilesetDef {
+        tile_size: Vec2::new(
+            attribute(tileset, "tilewidth")?,
+            attribute(tileset, "tileheight")?,
+        ),
+        spacing: attribute_or(tileset, "spacing", 0f32)?,
+        margin: attribute_or(tileset, "margin", 0f32)?,
+        image: image.to_owned(),
+        tile_properties,
+    })
+}
+
+// TMJ (JSON)
+
+#[derive(Deserialize)]
+struct TmjMap {
+    width: u32,
+    height: u32,
+    tilewidth: f32,
+    tileheight: f32,
+    orientation: Option<String>,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    layers: Vec<TmjLayer>,
+    #[serde(default)]
+    tilesets: Vec<TmjTileset>,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+#[derive(Deserialize)]
+struct TmjLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
+    data: Option<TmjData>,
+    compression: Option<String>,
+    #[serde(default)]
+    objects: Vec<TmjObject>,
+    /// Children of a group
+    #[serde(default)]
+    layers: Vec<TmjLayer>,
+    #[serde(default)]
+    offsetx: f32,
+    #[serde(default)]
+    offsety: f32,
+    #[serde(default = "default_opacity")]
+    opacity: f32,
+    #[serde(default = "default_visible")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+fn default_opacity() -> f32 {
+    1f32
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Layer data is an array of global ids, or a Base64 string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TmjData {
+    Gids(Vec<u32>),
+    Base64(String),
+}
+
+#[derive(Deserialize)]
+struct TmjObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+/// A tileset entry in a map, or the contents of a `.tsj` file
+#[derive(Deserialize)]
+struct TmjTileset {
+    firstgid: Option<u32>,
+    source: Option<String>,
+    #[serde(default)]
+    tilewidth: f32,
+    #[serde(default)]
+    tileheight: f32,
+    #[serde(default)]
+    spacing: f32,
+    #[serde(default)]
+    margin: f32,
+    image: Option<String>,
+    #[serde(default)]
+    tiles: Vec<TmjTile>,
+}
+
+#[derive(Deserialize)]
+struct TmjTile {
+    id: u32,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+#[derive(Deserialize)]
+struct TmjProperty {
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    value: serde_json::Value,
+}
+
+fn parse_tmj(text: &str) -> io::Result<ParsedMap> {
+    let map: TmjMap = serde_json::from_str(text)?;
+    check_map(
+        map.orientation.as_deref().unwrap_or("orthogonal"),
+        map.infinite,
+    )?;
+    let info = MapInfo {
+        size: UVec2::new(map.width, map.height),
+        tile_size: Vec2::new(map.tilewidth, map.tileheight),
+        first_gids: map
+            .tilesets
+            .iter()
+            .map(|tileset| tileset.firstgid.unwrap_or(1))
+            .collect(),
+    };
+
+    let mut layers = Vec::new();
+    tmj_layers(&map.layers, &info, &GroupState::default(), &mut layers)?;
+    let tilesets = map
+        .tilesets
+        .into_iter()
+        .map(|tileset| match tileset.source {
+            Some(source) => Ok(TilesetRef::External(source)),
+            None => tmj_tileset(tileset).map(TilesetRef::Embedded),
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(ParsedMap {
+        tilesets,
+        layers,
+        properties: tmj_properties(&map.properties),
+    })
+}
+
+/// Adds layers, flattening nested groups
+fn tmj_layers(
+    source: &[TmjLayer],
+    info: &MapInfo,
+    group: &GroupState,
+    layers: &mut Vec<MapLayer>,
+) -> io::Result<()> {
+    for layer in source {
+        let state = GroupState {
+            offset: group.offset + Vec2::new(layer.offsetx, -layer.offsety),
+            opacity: group.opacity * layer.opacity,
+            visible: group.visible && layer.visible,
+        };
+        let content = match layer.kind.as_str() {
+            "group" => {
+                tmj_layers(&layer.layers, info, &state, layers)?;
+                continue;
+            }
+            "tilelayer" => {
+                if let Some(compression) = layer.compression.as_deref()
+                    && !compression.is_empty()
+                {
+                    return Err(unsupported_compression(compression));
+                }
+                let gids = match &layer.data {
+                    Some(TmjData::Gids(gids)) => gids.clone(),
+                    Some(TmjData::Base64(text)) => base64_gids(text)?,
+                    None => return Err(invalid_data("tile layer has no data")),
+                };
+                let size = UVec2::new(layer.width, layer.height);
+                LayerContent::Tiles {
+                    size,
+                    grid_size: info.tile_size,
+                    tiles: info.tiles(&gids, size)?,
+                }
+            }
+            "objectgroup" => LayerContent::Objects(
+                layer
+                    .objects
+                    .iter()
+                    .map(|object| {
+                        let class = if object.class.is_empty() {
+                            &object.kind
+                        } else {
+                            &object.class
+                        };
+                        info.object(
+                            &object.name,
+                            class,
+                            Vec2::new(object.x, object.y),
+                            Vec2::new(object.width, object.height),
+                            object.gid.is_some(),
+                            tmj_properties(&object.properties),
+                        )
+                    })
+                    .collect(),
+            ),
+            // Image layers
+            _ => continue,
+        };
+        layers.push(MapLayer {
+            name: layer.name.clone(),
+            offset: state.offset,
+            opacity: state.opacity,
+            visible: state.visible,
+            properties: tmj_properties(&layer.properties),
+            content,
+        });
+    }
+    Ok(())
+}
+
+fn tmj_properties(properties: &[TmjProperty]) -> MapProperties {
+    MapProperties(
+        properties
+            .iter()
+            .filter_map(|property| {
+                let value = PropertyValue::from_json(&property.kind, &property.value)?;
+                Some((property.name.clone(), value))
+            })
+            .collect(),
+    )
+}
+
+fn tmj_tileset(tileset: TmjTileset) -> io::Result<TilesetDef> {
+    let image = tileset
+        .image
+        .ok_or_else(|| invalid_data("tilesets of separate images aren't supported"))?;
+    Ok(TilesetDef {
+        tile_size: Vec2::new(tileset.tilewidth, tileset.tileheight),
+        spacing: tileset.spacing,
+        margin: tileset.margin,
+        image,
+        tile_properties: tileset
+            .tiles
+            .iter()
+            .map(|tile| (tile.id, tmj_properties(&tile.properties)))
+            .filter(|(_, properties)| !properties.0.is_empty())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile_ids(layer: &MapLayer) -> Vec<Option<(usize, u32)>> {
+        let LayerContent::Tiles { tiles, .. } = &layer.content else {
+            panic!("{} isn't a tile layer", layer.name);
+        };
+        tiles
+            .iter()
+            .map(|tile| tile.map(|tile| (tile.tileset, tile.id)))
+            .collect()
+    }
+
+    fn objects(layer: &MapLayer) -> &[MapObjectDef] {
+        let LayerContent::Objects(objects) = &layer.content else {
+            panic!("{} isn't an object layer", layer.name);
+        };
+        objects
+    }
+
+    #[test]
+    fn base64_gids_are_little_endian_and_ignore_whitespace() {
+        let gids = base64_gids("AQAAAAIA\n  AAADAACA").unwrap();
+        assert_eq!(gids, vec![1, 2, 0x8000_0003]);
+    }
+
+    #[test]
+    fn base64_gids_stop_at_padding() {
+        let gids = base64_gids("AAAAAAEAAAACAABAAQAAIA==").unwrap();
+        assert_eq!(gids, vec![0, 1, 0x4000_0002, 0x2000_0001]);
+    }
+
+    #[test]
+    fn base64_gids_reject_other_characters() {
+        assert!(base64_gids("AQAA*AAA").is_err());
+    }
+
+    const TMX: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" infinite="0">
+  <properties>
+    <property name="tint" type="color" value="#80ff0000"/>
+    <property name="title" value="Test"/>
+  </properties>
+  <tileset firstgid="1" tilewidth="16" tileheight="16" spacing="1">
+    <image source="ground.png" width="64" height="64"/>
+    <tile id="2">
+      <properties>
+        <property name="blocked" type="bool" value="true"/>
+      </properties>
+    </tile>
+  </tileset>
+  <tileset firstgid="5" source="props.tsx"/>
+  <layer name="ground" width="2" height="2">
+    <data encoding="csv">
+1,2,
+5,2147483654
+</data>
+  </layer>
+  <group name="decor" offsetx="4" offsety="8" opacity="0.5">
+    <layer name="flips" width="2" height="2" opacity="0.5" visible="0">
+      <data encoding="base64">AAAAAAEAAAACAABAAQAAIA==</data>
+    </layer>
+  </group>
+  <objectgroup name="spawns">
+    <object name="base" type="Spawn" x="0" y="0" width="16" height="8"/>
+    <object name="tree" class="Prop" gid="5" x="16" y="32" width="16" height="16"/>
+  </objectgroup>
+</map>
+"##;
+
+    #[test]
+    fn tmx_tilesets_and_map_properties() {
+        let map = parse_tmx(TMX).unwrap();
+        let [TilesetRef::Embedded(ground), TilesetRef::External(props)] = &map.tilesets[..] else {
+            panic!("expected an embedded and an external tileset");
+        };
+        assert_eq!(ground.image, "ground.png");
+        assert_eq!(ground.tile_size, Vec2::splat(16f32));
+        assert_eq!(ground.spacing, 1f32);
+        assert!(ground.tile_properties[&2].flag("blocked"));
+        assert_eq!(props, "props.tsx");
+
+        // Tiled writes colors as #AARRGGBB
+        let tint = map.properties.color("tint").unwrap().to_srgba();
+        assert_eq!(tint, Srgba::hex("ff000080").unwrap());
+        assert_eq!(
+            map.properties.0["title"],
+            PropertyValue::String("Test".to_owned())
+        );
+    }
+
+    #[test]
+    fn tmx_rows_run_south_to_north() {
+        let map = parse_tmx(TMX).unwrap();
+        // The bottom row is listed last in the file but comes first here,
+        // and global ids are split across the two tilesets
+        assert_eq!(
+            tile_ids(&map.layers[0]),
+            vec![Some((1, 0)), Some((1, 1)), Some((0, 0)), Some((0, 1))]
+        );
+        let LayerContent::Tiles { tiles, .. } = &map.layers[0].content else {
+            unreachable!();
+        };
+        assert!(tiles[1].unwrap().flip.x);
+    }
+
+    #[test]
+    fn tmx_flip_flags_are_masked_off_the_id() {
+        let map = parse_tmx(TMX).unwrap();
+        let LayerContent::Tiles { tiles, .. } = &map.layers[1].content else {
+            panic!("expected a tile layer");
+        };
+        let flipped_y = tiles[0].unwrap();
+        assert_eq!(
+            (flipped_y.id, flipped_y.flip.y, flipped_y.flip.d),
+            (1, true, false)
+        );
+        let flipped_d = tiles[1].unwrap();
+        assert_eq!(
+            (flipped_d.id, flipped_d.flip.y, flipped_d.flip.d),
+            (0, false, true)
+        );
+        assert!(tiles[2].is_none());
+    }
+
+    #[test]
+    fn tmx_groups_pass_down_offset_opacity_and_visibility() {
+        let map = parse_tmx(TMX).unwrap();
+        let flips = &map.layers[1];
+        assert_eq!(flips.name, "flips");
+        assert_eq!(flips.offset, Vec2::new(4f32, -8f32));
+        assert_eq!(flips.opacity, 0.25);
+        assert!(!flips.visible);
+    }
+
+    #[test]
+    fn tmx_objects_are_centered_with_y_up() {
+        let map = parse_tmx(TMX).unwrap();
+        let [base, tree] = objects(&map.layers[2]) else {
+            panic!("expected two objects");
+        };
+        // Rectangles are placed by their top-left corner
+        assert_eq!(base.class, "Spawn");
+        assert_eq!(base.center, Vec2::new(8f32, 28f32));
+        // Tile objects by their bottom-left corner
+        assert_eq!(tree.class, "Prop");
+        assert_eq!(tree.center, Vec2::new(24f32, 8f32));
+    }
+
+    #[test]
+    fn tmx_rejects_what_it_cannot_load() {
+        let infinite = TMX.replace(r#"infinite="0""#, r#"infinite="1""#);
+        assert!(parse_tmx(&infinite).is_err());
+        let isometric = TMX.replace("orthogonal", "isometric");
+        assert!(parse_tmx(&isometric).is_err());
+        let compressed = TMX.replace(
+            r#"encoding="base64""#,
+            r#"encoding="base64" compression="zlib""#,
+        );
+        assert!(parse_tmx(&compressed).is_err());
+        let short = TMX.replace("5,2147483654", "5");
+        assert!(parse_tmx(&short).is_err());
+    }
+
+    const TMJ: &str = r##"{
+  "orientation": "orthogonal",
+  "width": 2,
+  "height": 2,
+  "tilewidth": 16,
+  "tileheight": 16,
+  "infinite": false,
+  "properties": [{ "name": "tint", "type": "color", "value": "#80ff0000" }],
+  "tilesets": [
+    {
+      "firstgid": 1,
+      "tilewidth": 16,
+      "tileheight": 16,
+      "image": "ground.png",
+      "tiles": [{ "id": 2, "properties": [{ "name": "blocked", "type": "bool", "value": true }] }]
+    },
+    { "firstgid": 5, "source": "props.tsj" }
+  ],
+  "layers": [
+    { "type": "tilelayer", "name": "ground", "width": 2, "height": 2, "data": [1, 2, 5, 2147483654] },
+    {
+      "type": "group",
+      "name": "decor",
+      "offsetx": 4,
+      "offsety": 8,
+      "opacity": 0.5,
+      "layers": [
+        {
+          "type": "tilelayer",
+          "name": "flips",
+          "width": 2,
+          "height": 2,
+          "encoding": "base64",
+          "data": "AAAAAAEAAAACAABAAQAAIA==",
+          "opacity": 0.5,
+          "visible": false
+        }
+      ]
+    },
+    { "type": "imagelayer", "name": "backdrop", "image": "sky.png" },
+    {
+      "type": "objectgroup",
+      "name": "spawns",
+      "objects": [
+        { "name": "base", "type": "Spawn", "x": 0, "y": 0, "width": 16, "height": 8 },
+        { "name": "tree", "class": "Prop", "gid": 5, "x": 16, "y": 32, "width": 16, "height": 16 }
+      ]
+    }
+  ]
+}"##;
+
+    #[test]
+    fn tmj_matches_tmx() {
+        let map = parse_tmj(TMJ).unwrap();
+        let [TilesetRef::Embedded(ground), TilesetRef::External(props)] = &map.tilesets[..] else {
+            panic!("expected an embedded and an external tileset");
+        };
+        assert_eq!(ground.image, "ground.png");
+        assert!(ground.tile_properties[&2].flag("blocked"));
+        assert_eq!(props, "props.tsj");
+        let tint = map.properties.color("tint").unwrap().to_srgba();
+        assert_eq!(tint, Srgba::hex("ff000080").unwrap());
+
+        // The image layer is skipped
+        let names: Vec<&str> = map.layers.iter().map(|layer| layer.name.as_str()).collect();
+        assert_eq!(names, ["ground", "flips", "spawns"]);
+
+        assert_eq!(
+            tile_ids(&map.layers[0]),
+            vec![Some((1, 0)), Some((1, 1)), Some((0, 0)), Some((0, 1))]
+        );
+        assert_eq!(
+            tile_ids(&map.layers[1]),
+            vec![Some((0, 1)), Some((0, 0)), None, Some((0, 0))]
+        );
+
+        let flips = &map.layers[1];
+        assert_eq!(flips.offset, Vec2::new(4f32, -8f32));
+        assert_eq!(flips.opacity, 0.25);
+        assert!(!flips.visible);
+
+        let [base, tree] = objects(&map.layers[2]) else {
+            panic!("expected two objects");
+        };
+        assert_eq!(
+            (base.class.as_str(), base.center),
+            ("Spawn", Vec2::new(8f32, 28f32))
+        );
+        assert_eq!(
+            (tree.class.as_str(), tree.center),
+            ("Prop", Vec2::new(24f32, 8f32))
+        );
+    }
+
+    #[test]
+    fn tmj_rejects_compressed_layers() {
+        let compressed = TMJ.replace(
+            r#""encoding": "base64","#,
+            r#""encoding": "base64", "compression": "zstd","#,
+        );
+        assert!(parse_tmj(&compressed).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_ecs_tilemap::prelude::*;
+
+mod autotile;
+mod camera;
+mod chunks;
+mod grid;
+mod growth;
+mod map_assets;
+mod picking;
+mod routes;
+
+use self::autotile::{
+    Autotiler, TileGrid, build_autotile_atlas, refresh_dirty_tiles, switch_autotile_rules,
+};
+use self::camera::{MapCamera, control_map_camera};
+use self::chunks::{LoadedChunks, stream_chunks};
+use self::grid::{GridShape, switch_grid_shape};
+use self::growth::{
+    TileGrowth, collect_blocked_tiles, grow_tiles, place_colony, refresh_growth_tiles,
+};
+use self::map_assets::{MapAssetsPlugin, MapObject, MapProperties, TileMapInstance};
+use self::picking::{TileCursor, TilesSelected, draw_tile_highlights, pick_tiles};
+use self::routes::{
+    TileNav, WalkingRoute, block_authored_tiles, draw_walking_route, request_walking_route,
+};
+use super::{AppState, Experiment};
+use crate::fog_of_war::{FogOfWar, FogPlane, FogRevealer};
+use crate::terrain::{TerrainSettings, TerrainWorld, tiles::TileKind};
+
+// Map constants; the map covers the same ground as the 3D terrain world, and
+// is streamed in square chunks around the camera
+const MAP_TILES: UVec2 = UVec2::new(384, 384);
+const TILE_SIZE: TilemapTileSize = TilemapTileSize { x: 16f32, y: 16f32 };
+const CHUNK_TILES: u32 = 32; // must divide the map size
+
+// Fog of war constants
+const FOG_CELL_SIZE: f32 = 8f32; // half a tile, so the reveal edge steps finely
+const SCOUT_SIGHT_RADIUS: f32 = 160f32;
+const FOG_TILE_INDEX: u32 = 0; // any opaque tile; the overlay tints it black
+
+// Authored mission layout, laid over the generated terrain
+const BEACHHEAD_MAP: &str = "maps/beachhead.tmx";
+const BEACHHEAD_ORIGIN: UVec2 = UVec2::new(168, 176); // tile under the map's bottom-left corner
+const MAP_OVERLAY_Z: f32 = 1.5; // over terrain transitions, under the fog
+const MAP_POINT_RADIUS: f32 = 6f32; // how point objects are drawn
+
+/// Follows the cursor, clearing the fog around it
+#[derive(Component)]
+pub struct Scout;
+
+pub struct EcsTilemapPoc;
+
+impl Experiment for EcsTilemapPoc {
+    fn name(&self) -> &'static str {
+        "ECS Tilemap POC"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{e0c6}"
+    }
+
+    fn app_state(&self) -> super::AppState {
+        AppState::EcsTilemap
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        app.add_plugins((TilemapPlugin, MapAssetsPlugin))
+            .add_event::<TilesSelected>()
+            .add_systems(OnEnter(AppState::EcsTilemap), startup)
+            .add_systems(
+                Update,
+                (
+                    (control_map_camera, move_scout).chain(),
+                    toggle_fog_of_war,
+                    (setup_map_objects, draw_map_objects, show_authored_maps),
+                    (
+                        pick_tiles,
+                        report_selection,
+                        draw_tile_highlights,
+                        request_walking_route,
+                        draw_walking_route,
+                        paint_tiles,
+                        place_colony,
+                        grow_tiles,
+                        switch_autotile_rules,
+                        switch_grid_shape,
+                        build_autotile_atlas,
+                        stream_chunks,
+                        refresh_dirty_tiles,
+                        refresh_growth_tiles,
+                    )
+                        .chain(),
+                )
+                    .run_if(in_state(AppState::EcsTilemap)),
+            )
+            .add_systems(
+                PostUpdate,
+                (collect_blocked_tiles, block_authored_tiles)
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(in_state(AppState::EcsTilemap)),
+            )
+            .add_systems(OnExit(AppState::EcsTilemap), cleanup)
+    }
+}
+
+/// Terrain XZ position at the center of a tile, with the map north up
+fn tile_world_position(terrain_rect: Rect, pos: UVec2) -> Vec2 {
+    let cell = terrain_rect.size() / MAP_TILES.as_vec2();
+    Vec2::new(
+        terrain_rect.min.x + (pos.x as f32 + 0.5) * cell.x,
+        terrain_rect.max.y - (pos.y as f32 + 0.5) * cell.y,
+    )
+}
+
+fn startup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    settings: Res<TerrainSettings>,
+) {
+    commands.spawn((Camera2d, MapCamera));
+
+    // Same generation pipeline as the 3D terrain, sampled once per tile
+    info!("Generating terrain for the tilemap...");
+    let terrain = TerrainWorld::generate(&settings);
+    let terrain_rect = settings.world_rect();
+
+    let grid = TileGrid::from_fn(MAP_TILES, |pos| {
+        terrain.tile_kind_at(tile_world_position(terrain_rect, pos))
+    });
+    commands.insert_resource(TileNav::new(&terrain, terrain_rect, &grid));
+    commands.spawn(WalkingRoute);
+    commands.insert_resource(terrain);
+    commands.insert_resource(grid);
+
+    // Chunks stream in once the transition atlas is built from this texture
+    let texture_handle: Handle<Image> = asset_server.load("textures_16x16.png");
+    commands.insert_resource(Autotiler::new(texture_handle));
+    commands.insert_resource(LoadedChunks::default());
+    commands.insert_resource(TileGrowth::default());
+    commands.insert_resource(TileCursor::default());
+    commands.insert_resource(GridShape::default());
+
+    // Authored maps are laid out in square tiles
+    let beachhead = GridShape::Square.map_rect().min + BEACHHEAD_ORIGIN.as_vec2() * TILE_SIZE.x;
+    commands.spawn((
+        Name::new("Beachhead"),
+        TileMapInstance(asset_server.load(BEACHHEAD_MAP)),
+        Transform::from_translation(beachhead.extend(MAP_OVERLAY_Z)),
+    ));
+
+    // One fog over every grid shape's area, so switching keeps what's explored
+    let map = GridShape::ALL
+        .iter()
+        .map(|shape| shape.map_rect())
+        .reduce(|a, b| a.union(b))
+        .unwrap_or_default();
+    commands.insert_resource(FogOfWar::new(
+        FogPlane::Xy,
+        map.min,
+        map.size(),
+        FOG_CELL_SIZE,
+        &mut images,
+    ));
+    commands.spawn((
+        Scout,
+        FogRevealer {
+            radius: SCOUT_SIGHT_RADIUS,
+        },
+        Transform::default(),
+        GlobalTransform::default(),
+    ));
+
+    info!("ECS tilemap loaded! Controls:");
+    info!("  Mouse - Scout the map through the fog of war");
+    info!("  MMB drag / window edges - Pan, Scroll - Zoom");
+    info!("  F - Toggle fog of war");
+    info!("  LMB - Select a tile, drag to select a rectangle");
+    info!("  Shift + LMB drag - Select every tile within a distance");
+    info!("  Select one tile, then hover - Show the walking route (square grid)");
+    info!("  Ctrl + LMB - Paint ground, 1-9 - Pick deep water through snow");
+    info!("  RMB - Seed a crimson colony");
+    info!("  B - Switch autotile rules (blob 47 / Wang 2-corner)");
+    info!("  G - Switch grid shape (square / hex rows / hex columns / iso diamond)");
+    info!("  ESC - Return to launcher");
+}
+
+fn move_scout(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut scout: Single<&mut Transform, With<Scout>>,
+) {
+    let (camera, camera_transform) = *camera;
+    if let Some(position) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    {
+        scout.translation = position.extend(0f32);
+    }
+}
+
+/// Ctrl + left mouse paints the selected ground kind; 1-9 select it
+fn paint_tiles(
+    input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor: Res<TileCursor>,
+    mut grid: ResMut<TileGrid>,
+    mut nav: ResMut<TileNav>,
+    mut selected: Local<usize>,
+) {
+    const KEYS: [KeyCode; 9] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    if let Some(layer) = KEYS.iter().position(|key| input.just_pressed(*key)) {
+        *selected = layer;
+        info!("Painting {:?}", TileKind::ALL[layer]);
+    }
+
+    let ctrl = input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    let kind = TileKind::ALL[*selected];
+    if let Some(pos) = cursor.hovered.map(UVec2::from)
+        && grid.kind(pos) != kind
+    {
+        grid.set(pos, kind);
+        nav.set_kind(pos, kind);
+    }
+}
+
+/// Logs what a selection covers
+fn report_selection(
+    mut selections: EventReader<TilesSelected>,
+    grid: Res<TileGrid>,
+    growth: Res<TileGrowth>,
+) {
+    for selection in selections.read() {
+        if selection.tiles.is_empty() {
+            continue;
+        }
+        let mut counts = [0; TileKind::ALL.len()];
+        let mut overgrown = 0;
+        for pos in &selection.tiles {
+            counts[grid.kind(pos.into()).layer()] += 1;
+            overgrown += usize::from(growth.age(pos.into()).is_some());
+        }
+        let kinds: Vec<String> = TileKind::ALL
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(kind, count)| format!("{count} {kind:?}"))
+            .collect();
+        info!(
+            "Selected {} tiles ({}), {overgrown} overgrown",
+            selection.tiles.len(),
+            kinds.join(", ")
+        );
+    }
+}
+
+/// Map objects with a `reveal_radius` property clear the fog around them
+fn setup_map_objects(
+    mut commands: Commands,
+    objects: Query<(Entity, &Name, &MapObject, &MapProperties), Added<MapObject>>,
+) {
+    for (entity, name, object, properties) in &objects {
+        info!(
+            "Map object {}/{name} ({}): {properties}",
+            object.layer, object.class
+        );
+        if let Some(radius) = properties.float("reveal_radius") {
+            commands.entity(entity).insert(FogRevealer { radius });
+        }
+    }
+}
+
+/// Outlines map objects in their `color` property
+fn draw_map_objects(
+    mut gizmos: Gizmos,
+    objects: Query<(
+        &GlobalTransform,
+        &MapObject,
+        &MapProperties,
+        &InheritedVisibility,
+    )>,
+) {
+    for (transform, object, properties, visibility) in &objects {
+        if !visibility.get() {
+            continue;
+        }
+        let color = properties.color("color").unwrap_or(Color::WHITE);
+        let position = transform.translation().xy();
+        if object.size == Vec2::ZERO {
+            gizmos.circle_2d(position, MAP_POINT_RADIUS, color);
+        } else {
+            gizmos.rect_2d(position, object.size, color);
+        }
+    }
+}
+
+/// Authored maps only line up with the square grid, so they hide on others
+fn show_authored_maps(
+    shape: Res<GridShape>,
+    mut instances: Query<&mut Visibility, With<TileMapInstance>>,
+) {
+    if !shape.is_changed() {
+        return;
+    }
+    for mut visibility in &mut instances {
+        *visibility = if *shape == GridShape::Square {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn toggle_fog_of_war(mut fog: ResMut<FogOfWar>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::KeyF) {
+        let enabled = !fog.enabled();
+        fog.set_enabled(enabled);
+        info!("Fog of war {}", if enabled { "on" } else { "off" });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup(
+    mut commands: Commands,
+    entities: Query<
+        Entity,
+        (
+            Or<(
+                With<TileStorage>,
+                With<TilemapId>,
+                With<TileMapInstance>,
+                With<Scout>,
+                With<WalkingRoute>,
+                With<Camera2d>,
+            )>,
+            // Children go with their parents
+            Without<ChildOf>,
+        ),
+    >,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<FogOfWar>();
+    commands.remove_resource::<TerrainWorld>();
+    commands.remove_resource::<TileGrid>();
+    commands.remove_resource::<Autotiler>();
+    commands.remove_resource::<LoadedChunks>();
+    commands.remove_resource::<TileGrowth>();
+    commands.remove_resource::<TileCursor>();
+    commands.remove_resource::<GridShape>();
+    commands.remove_resource::<TileNav>();
+}
@@ -0,0 +1,247 @@
+use bevy::{platform::collections::HashSet, prelude::*, window::PrimaryWindow};
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{camera::MapCamera, grid::GridShape};
+
+// Highlight colors
+const HOVER_COLOR: Color = Color::srgb(1f32, 1f32, 1f32);
+const SELECTION_COLOR: Color = Color::srgb(1f32, 0.85, 0.2);
+const LINE_COLOR: Color = Color::srgb(0.3, 0.9, 1f32);
+const RING_COLOR: Color = Color::srgb(0.9, 0.4, 1f32);
+
+/// A tilemap whose tiles can be picked; `origin` is where its first tile sits
+/// in map tile coordinates, so tiles from separately spawned chunks pick as
+/// one map
+#[derive(Component)]
+pub struct PickableTilemap {
+    pub origin: UVec2,
+}
+
+/// Sent when a click or drag selects tiles, in map tile coordinates
+#[derive(Event)]
+pub struct TilesSelected {
+    pub tiles: HashSet<TilePos>,
+}
+
+/// The tile under the cursor and the current selection
+#[derive(Resource, Default)]
+pub struct TileCursor {
+    pub hovered: Option<TilePos>,
+    /// Tile the left button went down on, while dragging
+    drag_from: Option<TilePos>,
+    selected: HashSet<TilePos>,
+}
+
+/// Everything needed to map between a tilemap's tiles and the world
+type TilemapGeometry<'a> = (
+    &'a PickableTilemap,
+    &'a GlobalTransform,
+    &'a TilemapSize,
+    &'a TilemapGridSize,
+    &'a TilemapTileSize,
+    &'a TilemapType,
+    &'a TilemapAnchor,
+);
+
+/// Tile under a world position on one tilemap, in map coordinates; goes
+/// through the tilemap's transform, so scaled or rotated maps pick too
+fn pick_tile(
+    (pickable, transform, size, grid_size, tile_size, map_type, anchor): TilemapGeometry,
+    world: Vec2,
+) -> Option<TilePos> {
+    let local = transform
+        .affine()
+        .inverse()
+        .transform_point3(world.extend(0f32))
+        .xy();
+    let pos = TilePos::from_world_pos(&local, size, grid_size, tile_size, map_type, anchor)?;
+    Some((pickable.origin + UVec2::from(pos)).into())
+}
+
+/// World-space outline of a tile, as a closed line strip, shaped for the
+/// tilemap's type
+fn tile_outline(
+    (_, transform, size, grid_size, tile_size, map_type, anchor): TilemapGeometry,
+    pos: TilePos,
+) -> Vec<Vec2> {
+    let center = pos.center_in_world(size, grid_size, tile_size, map_type, anchor);
+    let half = Vec2::new(grid_size.x, grid_size.y) / 2f32;
+    let corners: Vec<Vec2> = match map_type {
+        TilemapType::Square => vec![
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ],
+        TilemapType::Isometric(_) => vec![
+            Vec2::new(0f32, -half.y),
+            Vec2::new(half.x, 0f32),
+            Vec2::new(0f32, half.y),
+            Vec2::new(-half.x, 0f32),
+        ],
+        // Hexes fill the grid box, with rows (or columns) three quarters of
+        // it apart
+        TilemapType::Hexagon(
+            HexCoordSystem::Row | HexCoordSystem::RowEven | HexCoordSystem::RowOdd,
+        ) => vec![
+            Vec2::new(0f32, -half.y),
+            Vec2::new(half.x, -half.y / 2f32),
+            Vec2::new(half.x, half.y / 2f32),
+            Vec2::new(0f32, half.y),
+            Vec2::new(-half.x, half.y / 2f32),
+            Vec2::new(-half.x, -half.y / 2f32),
+        ],
+        TilemapType::Hexagon(_) => vec![
+            Vec2::new(-half.x / 2f32, -half.y),
+            Vec2::new(half.x / 2f32, -half.y),
+            Vec2::new(half.x, 0f32),
+            Vec2::new(half.x / 2f32, half.y),
+            Vec2::new(-half.x / 2f32, half.y),
+            Vec2::new(-half.x, 0f32),
+        ],
+    };
+    corners
+        .iter()
+        .chain(corners.first())
+        .map(|corner| {
+            transform
+                .transform_point((center + *corner).extend(0f32))
+                .xy()
+        })
+        .collect()
+}
+
+/// Every tile in the rectangle spanned by two tiles, in tile coordinates
+fn tiles_between(a: TilePos, b: TilePos) -> HashSet<TilePos> {
+    let (columns, rows) = (a.x.min(b.x)..=a.x.max(b.x), a.y.min(b.y)..=a.y.max(b.y));
+    rows.flat_map(|y| columns.clone().map(move |x| TilePos { x, y }))
+        .collect()
+}
+
+/// Tracks the tile under the cursor; left click selects a tile and dragging
+/// selects the rectangle between where it started and the cursor, or with
+/// shift held every tile as close to the start as the cursor is. Ctrl is
+/// left to painting.
+#[allow(clippy::too_many_arguments)]
+pub fn pick_tiles(
+    input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    shape: Res<GridShape>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MapCamera>>,
+    tilemaps: Query<TilemapGeometry>,
+    mut cursor: ResMut<TileCursor>,
+    mut selections: EventWriter<TilesSelected>,
+) {
+    // The viewport mapping covers the camera's pan and zoom
+    let (camera, camera_transform) = *camera;
+    let world = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position).ok());
+    cursor.hovered = world.and_then(|world| {
+        tilemaps
+            .iter()
+            .find_map(|geometry| pick_tile(geometry, world))
+    });
+
+    let ctrl = input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if mouse.just_pressed(MouseButton::Left) && !ctrl {
+        cursor.drag_from = cursor.hovered;
+        cursor.selected.clear();
+    }
+    let Some(from) = cursor.drag_from else {
+        return;
+    };
+    // Off the map the selection keeps the last tile the cursor was over
+    if let Some(to) = cursor.hovered {
+        let shift = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        cursor.selected = if shift {
+            let (center, edge) = (UVec2::from(from), UVec2::from(to));
+            shape
+                .area(center, shape.distance(center, edge))
+                .into_iter()
+                .map(TilePos::from)
+                .collect()
+        } else {
+            tiles_between(from, to)
+        };
+    }
+    if mouse.just_released(MouseButton::Left) {
+        cursor.drag_from = None;
+        selections.write(TilesSelected {
+            tiles: cursor.selected.clone(),
+        });
+    }
+}
+
+/// Line and ring measured from a single selected tile to the hovered one
+#[derive(Default)]
+pub struct Measure {
+    /// Selected tile, hovered tile and shape the sets were worked out for
+    key: Option<(TilePos, TilePos, GridShape)>,
+    line: HashSet<TilePos>,
+    ring: HashSet<TilePos>,
+}
+
+/// Outlines the hovered tile and the selection on loaded tilemaps. With one
+/// tile selected, the line from it to the hovered tile and the ring through
+/// the hovered tile show how the grid measures distance.
+pub fn draw_tile_highlights(
+    mut gizmos: Gizmos,
+    cursor: Res<TileCursor>,
+    shape: Res<GridShape>,
+    tilemaps: Query<TilemapGeometry>,
+    mut measure: Local<Measure>,
+) {
+    let key = match (cursor.selected.iter().next(), cursor.hovered) {
+        (Some(from), Some(to)) if cursor.selected.len() == 1 && cursor.drag_from.is_none() => {
+            Some((*from, to, *shape))
+        }
+        _ => None,
+    };
+    if measure.key != key {
+        measure.key = key;
+        let (line, ring) = key.map_or_else(Default::default, |(from, to, shape)| {
+            let (from, to) = (UVec2::from(from), UVec2::from(to));
+            (
+                shape.line(from, to),
+                shape.ring(from, shape.distance(from, to)),
+            )
+        });
+        measure.line = line.into_iter().map(TilePos::from).collect();
+        measure.ring = ring.into_iter().map(TilePos::from).collect();
+    }
+
+    let layers = [
+        (&cursor.selected, SELECTION_COLOR),
+        (&measure.ring, RING_COLOR),
+        (&measure.line, LINE_COLOR),
+    ];
+    for geometry in &tilemaps {
+        let (pickable, _, size, ..) = geometry;
+        // Walks the tilemap rather than the tiles, which can be far more
+        for (tiles, color) in layers {
+            if tiles.is_empty() {
+                continue;
+            }
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let local = TilePos { x, y };
+                    let map = TilePos::from(pickable.origin + UVec2::from(local));
+                    if tiles.contains(&map) {
+                        gizmos.linestrip_2d(tile_outline(geometry, local), color);
+                    }
+                }
+            }
+        }
+        let Some(hovered) = cursor.hovered.map(UVec2::from) else {
+            continue;
+        };
+        if hovered.cmpge(pickable.origin).all() {
+            let local = TilePos::from(hovered - pickable.origin);
+            if local.within_map_bounds(size) {
+                gizmos.linestrip_2d(tile_outline(geometry, local), HOVER_COLOR);
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{
+    MAP_TILES, TILE_SIZE,
+    autotile::TileGrid,
+    grid::GridShape,
+    map_assets::MapProperties,
+    picking::{PickableTilemap, TileCursor, TilesSelected},
+};
+use crate::navigation::{NavGrid, NavPath, PathRequest, SlopeCost};
+use crate::terrain::{TerrainWorld, tiles::TileKind};
+
+// Walking costs; nothing climbs past a 45° slope
+const SLOPE_COST: SlopeCost = SlopeCost {
+    max_slope: 1f32,
+    penalty: 4f32,
+};
+const ROUTE_COLOR: Color = Color::srgb(0.3, 1f32, 0.4);
+
+/// Walking costs over the map, one nav cell per tile; routes run from a
+/// single selected tile to the hovered one
+#[derive(Resource)]
+pub struct TileNav {
+    grid: Arc<NavGrid>,
+    /// Costs from the terrain and authored maps alone, to restore painted
+    /// tiles from
+    ground: NavGrid,
+    /// Tile a route starts from, while exactly one is selected
+    start: Option<UVec2>,
+    /// Ends of the route last searched for
+    requested: Option<(UVec2, UVec2)>,
+}
+
+impl TileNav {
+    /// Nav cells line up with tiles, whose rows count down the terrain's Z
+    pub fn new(terrain: &TerrainWorld, terrain_rect: Rect, tiles: &TileGrid) -> Self {
+        let cell = terrain_rect.size() / MAP_TILES.as_vec2();
+        let ground = NavGrid::from_heightfield(
+            &terrain.heightfield,
+            MAP_TILES,
+            Vec2::new(terrain_rect.min.x, terrain_rect.max.y),
+            Vec2::new(cell.x, -cell.y),
+            SLOPE_COST,
+        );
+        let mut nav = Self {
+            grid: Arc::new(ground.clone()),
+            ground,
+            start: None,
+            requested: None,
+        };
+        for y in 0..MAP_TILES.y {
+            for x in 0..MAP_TILES.x {
+                let pos = UVec2::new(x, y);
+                nav.set_kind(pos, tiles.kind(pos));
+            }
+        }
+        nav
+    }
+
+    /// Water and rock can't be walked; other ground costs what its slope does
+    pub fn set_kind(&mut self, pos: UVec2, kind: TileKind) {
+        let cost = match kind {
+            TileKind::DeepWater | TileKind::ShallowWater | TileKind::Rock => None,
+            _ => self.ground.cost(pos),
+        };
+        Arc::make_mut(&mut self.grid).set_cost(pos, cost);
+        self.requested = None;
+    }
+}
+
+/// Holds the walking route while it's searched for, and once it's found
+#[derive(Component)]
+pub struct WalkingRoute;
+
+/// Blocks tiles that authored maps' tilesets mark `blocked`; runs after
+/// transforms propagate so the maps' placement is known
+#[allow(clippy::type_complexity)]
+pub fn block_authored_tiles(
+    layers: Query<
+        (&TileStorage, &TilemapGridSize, &GlobalTransform),
+        (Added<TileStorage>, Without<PickableTilemap>),
+    >,
+    tiles: Query<&MapProperties>,
+    mut nav: ResMut<TileNav>,
+) {
+    for (storage, grid_size, transform) in &layers {
+        // Only layers on the map's own square tiles line up with nav cells
+        if grid_size.x != TILE_SIZE.x || grid_size.y != TILE_SIZE.y {
+            continue;
+        }
+        let Some(offset) = GridShape::Square.tile_at(transform.translation().xy()) else {
+            continue;
+        };
+        let blocked = |tile| {
+            tiles
+                .get(tile)
+                .is_ok_and(|properties| properties.flag("blocked"))
+        };
+        nav.ground.block_tiles(storage, offset, blocked);
+        Arc::make_mut(&mut nav.grid).block_tiles(storage, offset, blocked);
+        nav.requested = None;
+    }
+}
+
+/// Searches for a walking route whenever its ends or the ground change;
+/// routes follow square tiles, so other grid shapes show none
+pub fn request_walking_route(
+    mut commands: Commands,
+    mut selections: EventReader<TilesSelected>,
+    cursor: Res<TileCursor>,
+    shape: Res<GridShape>,
+    mut nav: ResMut<TileNav>,
+    route: Single<Entity, With<WalkingRoute>>,
+) {
+    for selection in selections.read() {
+        let mut tiles = selection.tiles.iter();
+        nav.start = match (tiles.next(), tiles.next()) {
+            (Some(pos), None) => Some(pos.into()),
+            _ => None,
+        };
+    }
+    let ends = nav
+        .start
+        .zip(cursor.hovered.map(UVec2::from))
+        .filter(|_| *shape == GridShape::Square);
+    if ends == nav.requested {
+        return;
+    }
+    nav.requested = ends;
+    let mut route = commands.entity(*route);
+    route.remove::<(PathRequest, NavPath)>();
+    if let Some((start, goal)) = ends {
+        route.insert(PathRequest::new(&nav.grid, start, goal));
+    }
+}
+
+/// Draws the walking route, logging what it costs once it's found
+pub fn draw_walking_route(
+    mut gizmos: Gizmos,
+    nav: Res<TileNav>,
+    shape: Res<GridShape>,
+    route: Single<Option<Ref<NavPath>>, With<WalkingRoute>>,
+) {
+    let (Some(path), Some((start, _))) = (route.as_ref(), nav.requested) else {
+        return;
+    };
+    if path.is_changed() {
+        info!(
+            "Walking route of {} tiles costs {:.1} flat tiles",
+            path.cells.len(),
+            path.cost
+        );
+    }
+    let points = std::iter::once(start)
+        .chain(path.cells.iter().copied())
+        .map(|pos| shape.tile_center(pos));
+    gizmos.linestrip_2d(points, ROUTE_COLOR);
+}
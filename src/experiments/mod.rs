@@ -1,11 +1,17 @@
 pub mod crimson_sprawl;
 pub mod ecs_tilemap;
+pub mod projectile_arc;
+pub mod rugged_pathfinding;
+pub mod skirmish;
+pub mod strike_targeting;
 pub mod terrain_proc_gen;
 
 use bevy::prelude::*;
 
 use self::{
     crimson_sprawl::CrimsonSprawlExperiment, ecs_tilemap::EcsTilemapPoc,
+    projectile_arc::ProjectileArcExperiment, rugged_pathfinding::RuggedPathfindingExperiment,
+    skirmish::SkirmishExperiment, strike_targeting::StrikeTargetingExperiment,
     terrain_proc_gen::TerrainProcGenExperiment,
 };
 
@@ -32,6 +38,10 @@ pub enum AppState {
     CrimsonSprawl,
     TerrainProcGen,
     EcsTilemap,
+    StrikeTargeting,
+    Skirmish,
+    RuggedPathfinding,
+    ProjectileArc,
 }
 
 /// Registry of all available experiments
@@ -40,6 +50,10 @@ pub fn all_experiments() -> Vec<Box<dyn Experiment>> {
         Box::new(CrimsonSprawlExperiment),
         Box::new(TerrainProcGenExperiment),
         Box::new(EcsTilemapPoc),
+        Box::new(StrikeTargetingExperiment),
+        Box::new(SkirmishExperiment),
+        Box::new(RuggedPathfindingExperiment),
+        Box::new(ProjectileArcExperiment),
         // Add new experiments here
     ]
 }
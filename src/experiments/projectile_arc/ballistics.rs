@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::terrain::TerrainWorld;
+
+// Ballistics constants, in world units; the terrain is kilometres wide, so
+// gravity is scaled up to keep flights short
+const GRAVITY: f32 = 30f32;
+const DRAG: f32 = 0.0005; // quadratic drag per unit of speed
+const MAX_FLIGHT_SECS: f32 = 30f32; // shells still flying after this are lost
+
+/// A shell in flight, stepped on the fixed timestep
+#[derive(Component)]
+pub struct Shell {
+    pub velocity: Vec3,
+    /// Seconds since it was fired
+    pub flight: f32,
+    /// Where the arc predicted it would land when it was fired
+    pub predicted: Option<Vec3>,
+}
+
+/// A shell hit the ground
+#[derive(Event)]
+pub struct ShellImpact {
+    pub point: Vec3,
+    pub flight: f32,
+    pub predicted: Option<Vec3>,
+}
+
+/// Advances a shell one step: gravity, and drag against its speed squared
+pub fn step(position: Vec3, velocity: Vec3, dt: f32) -> (Vec3, Vec3) {
+    let acceleration = Vec3::NEG_Y * GRAVITY - DRAG * velocity.length() * velocity;
+    let velocity = velocity + acceleration * dt;
+    (position + velocity * dt, velocity)
+}
+
+/// Where the straight line of one step first meets the ground
+fn ground_hit(terrain: &TerrainWorld, from: Vec3, to: Vec3) -> Option<Vec3> {
+    let (direction, length) = Dir3::new_and_length(to - from).ok()?;
+    terrain.raycast(Ray3d::new(from, direction), length)
+}
+
+/// Whether a point is still over the generated terrain
+fn over_terrain(terrain: &TerrainWorld, point: Vec3) -> bool {
+    terrain.heightfield.nearest(point.xz()).is_some()
+}
+
+/// A predicted flight, stepped exactly like a real shell
+pub struct Trajectory {
+    /// From the muzzle to the impact, or to where the flight was given up
+    pub points: Vec<Vec3>,
+    pub impact: Option<Vec3>,
+    pub flight: f32,
+}
+
+impl Trajectory {
+    pub fn predict(terrain: &TerrainWorld, muzzle: Vec3, velocity: Vec3, dt: f32) -> Self {
+        let mut points = vec![muzzle];
+        let (mut position, mut velocity) = (muzzle, velocity);
+        let mut flight = 0f32;
+        while flight < MAX_FLIGHT_SECS && over_terrain(terrain, position) {
+            let (next, next_velocity) = step(position, velocity, dt);
+            flight += dt;
+            if let Some(hit) = ground_hit(terrain, position, next) {
+                points.push(hit);
+                return Self {
+                    points,
+                    impact: Some(hit),
+                    flight,
+                };
+            }
+            points.push(next);
+            (position, velocity) = (next, next_velocity);
+        }
+        Self {
+            points,
+            impact: None,
+            flight,
+        }
+    }
+}
+
+/// Steps shells along their arcs and reports where they land
+pub fn fly_shells(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<TerrainWorld>,
+    mut shells: Query<(Entity, &mut Shell, &mut Transform)>,
+    mut impacts: EventWriter<ShellImpact>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut shell, mut transform) in &mut shells {
+        let (next, velocity) = step(transform.translation, shell.velocity, dt);
+        shell.flight += dt;
+        if let Some(point) = ground_hit(&terrain, transform.translation, next) {
+            impacts.write(ShellImpact {
+                point,
+                flight: shell.flight,
+                predicted: shell.predicted,
+            });
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if shell.flight >= MAX_FLIGHT_SECS || !over_terrain(&terrain, next) {
+            info!("Shell lost after {:.1}s", shell.flight);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        shell.velocity = velocity;
+        transform.translation = next;
+    }
+}
@@ -0,0 +1,499 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{platform::collections::HashSet, prelude::*, window::PrimaryWindow};
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
+use rand::Rng;
+
+mod ballistics;
+
+use self::ballistics::{Shell, ShellImpact, Trajectory, fly_shells};
+use super::{AppState, Experiment};
+use crate::growth::{CrimsonColony, FULL_HEALTH, GrowthPatch, growth_color};
+use crate::orbital_strike::{OrbitalStrike, ScorchMark};
+use crate::terrain::{
+    GenerateTerrain, TerrainMesh, TerrainStore, TerrainWorld,
+    biome::Biome,
+    edit::{Brush, RefreshTerrainChunks, TerrainEdits},
+};
+
+// Launcher constants
+const MUZZLE_HEIGHT: f32 = 4f32; // above the ground under the launcher
+const BARREL_LENGTH: f32 = 8f32;
+const DEFAULT_ELEVATION: f32 = 35f32; // degrees
+const MIN_ELEVATION: f32 = 5f32;
+const MAX_ELEVATION: f32 = 85f32;
+const ELEVATION_RATE: f32 = 20f32; // degrees per second
+const DEFAULT_SPEED: f32 = 120f32; // world units per second
+const MIN_SPEED: f32 = 40f32;
+const MAX_SPEED: f32 = 250f32;
+const SPEED_RATE: f32 = 40f32; // world units per second, per second
+const SHELL_RADIUS: f32 = 1f32;
+
+// Blast constants
+const BLAST_RADIUS: f32 = 12f32;
+const BLAST_STRENGTH: f32 = 1.2; // destroys young growth, wounds mature growth
+const CRATER_DEPTH: f32 = 3f32;
+
+// Growth constants
+const GROWTH_RADIUS: f32 = 30f32;
+const SEEDED_PATCH_SPACING: f32 = 4f32; // coarser than growth::PATCH_SPACING so the seeded field stays cheap
+const PATCH_SIZE: f32 = 3.5;
+const PATCH_HEIGHT: f32 = 0.4;
+
+// Camera and view constants
+const CAMERA_DISTANCE: f32 = 250f32;
+const MAX_RAY_DISTANCE: f32 = 5000f32;
+const ARC_COLOR: Color = Color::srgb(1f32, 0.9, 0.3);
+const MARKER_COLOR: Color = Color::srgb(1f32, 0.3, 0.2);
+const BARREL_COLOR: Color = Color::WHITE;
+const MARKER_HEIGHT: f32 = 0.5; // above the ground
+
+/// Aim of the launcher; the heading follows the cursor
+#[derive(Component)]
+pub struct Launcher {
+    /// Direction on the XZ plane
+    heading: Vec2,
+    /// Degrees above the horizon
+    elevation: f32,
+    speed: f32,
+    /// Predicted flight for the current aim
+    trajectory: Option<Trajectory>,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self {
+            heading: Vec2::NEG_Y,
+            elevation: DEFAULT_ELEVATION,
+            speed: DEFAULT_SPEED,
+            trajectory: None,
+        }
+    }
+}
+
+impl Launcher {
+    fn muzzle_velocity(&self) -> Vec3 {
+        let (rise, run) = self.elevation.to_radians().sin_cos();
+        Vec3::new(self.heading.x * run, rise, self.heading.y * run) * self.speed
+    }
+}
+
+/// Ground point under the cursor, if any
+#[derive(Resource, Default)]
+pub struct ArcCursor(Option<Vec3>);
+
+/// Flight time of the last shell to land, and how far it landed from where
+/// its arc said it would
+#[derive(Resource, Default)]
+pub struct ImpactReport(Option<(f32, Option<f32>)>);
+
+#[derive(Component)]
+pub struct ProjectileHud;
+
+#[derive(Component)]
+pub struct ArcCamera;
+
+pub struct ProjectileArcExperiment;
+
+impl Experiment for ProjectileArcExperiment {
+    fn name(&self) -> &'static str {
+        "Projectile Arc & Impact Markers"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{f135}" // Font Awesome rocket icon
+    }
+
+    fn app_state(&self) -> AppState {
+        AppState::ProjectileArc
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        // The terrain experiments may have added the camera plugin already
+        if !app.is_plugin_added::<PanOrbitCameraPlugin>() {
+            app.add_plugins(PanOrbitCameraPlugin);
+        }
+        app.add_event::<ShellImpact>()
+            .add_systems(OnEnter(AppState::ProjectileArc), setup_projectile_arc)
+            .add_systems(
+                Update,
+                (
+                    spawn_launcher.run_if(resource_added::<TerrainWorld>),
+                    (
+                        aim_launcher,
+                        fire_shell,
+                        seed_growth,
+                        blast_terrain,
+                        draw_trajectory,
+                        update_projectile_hud,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<TerrainWorld>),
+                )
+                    .run_if(in_state(AppState::ProjectileArc)),
+            )
+            .add_systems(
+                FixedUpdate,
+                fly_shells
+                    .run_if(in_state(AppState::ProjectileArc).and(resource_exists::<TerrainWorld>)),
+            )
+            .add_systems(OnExit(AppState::ProjectileArc), cleanup_projectile_arc)
+    }
+}
+
+fn setup_projectile_arc(mut commands: Commands) {
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(200f32, 400f32, 100f32).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0f32, CAMERA_DISTANCE, CAMERA_DISTANCE).looking_at(Vec3::ZERO, Vec3::Y),
+        PanOrbitCamera {
+            // The left button fires
+            button_orbit: MouseButton::Right,
+            button_pan: MouseButton::Middle,
+            ..default()
+        },
+        ArcCamera,
+    ));
+    commands.spawn((
+        ProjectileHud,
+        Text::default(),
+        TextFont {
+            font_size: 18f32,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12f32),
+            left: Val::Px(12f32),
+            ..default()
+        },
+    ));
+    commands.insert_resource(ArcCursor::default());
+    commands.insert_resource(ImpactReport::default());
+    commands.queue(GenerateTerrain);
+
+    info!("Projectile arc loaded! Controls:");
+    info!("  Mouse - Turn the launcher toward the cursor");
+    info!("  W/S - Raise/lower the barrel");
+    info!("  A/D - Less/more muzzle speed");
+    info!("  LMB - Fire");
+    info!("  G - Seed growth under the cursor");
+    info!("  RMB drag - Orbit, MMB drag - Pan, Scroll - Zoom");
+    info!("  ESC - Return to launcher");
+}
+
+/// Puts the launcher on the ground in the middle of the map once the terrain
+/// is generated
+fn spawn_launcher(
+    mut commands: Commands,
+    terrain: Res<TerrainWorld>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let ground = terrain.surface_height_at(Vec2::ZERO);
+    commands.spawn((
+        Launcher::default(),
+        Mesh3d(meshes.add(Cylinder::new(3f32, MUZZLE_HEIGHT))),
+        MeshMaterial3d(materials.add(Color::srgb(0.4, 0.4, 0.45))),
+        Transform::from_xyz(0f32, ground + MUZZLE_HEIGHT / 2f32, 0f32),
+    ));
+}
+
+/// Muzzle position of a launcher standing at `transform`
+fn muzzle(transform: &Transform) -> Vec3 {
+    transform.translation + Vec3::Y * MUZZLE_HEIGHT / 2f32
+}
+
+/// Finds the ground under the cursor, turns the launcher toward it, applies
+/// W/S/A/D, and predicts the flight for the new aim
+#[allow(clippy::too_many_arguments)]
+fn aim_launcher(
+    time: Res<Time>,
+    fixed: Res<Time<Fixed>>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut cursor: ResMut<ArcCursor>,
+    terrain: Res<TerrainWorld>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<ArcCamera>>,
+    launcher: Option<Single<(&mut Launcher, &Transform)>>,
+) {
+    let (camera, camera_transform) = *camera;
+    cursor.0 = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world(camera_transform, position).ok())
+        .and_then(|ray| terrain.raycast(ray, MAX_RAY_DISTANCE));
+    let Some(launcher) = launcher else {
+        return;
+    };
+    let (mut launcher, transform) = launcher.into_inner();
+
+    if let Some(target) = cursor.0
+        && let Some(heading) = (target - transform.translation).xz().try_normalize()
+    {
+        launcher.heading = heading;
+    }
+    let dt = time.delta_secs();
+    if input.pressed(KeyCode::KeyW) {
+        launcher.elevation += ELEVATION_RATE * dt;
+    }
+    if input.pressed(KeyCode::KeyS) {
+        launcher.elevation -= ELEVATION_RATE * dt;
+    }
+    if input.pressed(KeyCode::KeyD) {
+        launcher.speed += SPEED_RATE * dt;
+    }
+    if input.pressed(KeyCode::KeyA) {
+        launcher.speed -= SPEED_RATE * dt;
+    }
+    launcher.elevation = launcher.elevation.clamp(MIN_ELEVATION, MAX_ELEVATION);
+    launcher.speed = launcher.speed.clamp(MIN_SPEED, MAX_SPEED);
+
+    // Stepped exactly as shells are, so the arc is where they'll go
+    launcher.trajectory = Some(Trajectory::predict(
+        &terrain,
+        muzzle(transform),
+        launcher.muzzle_velocity(),
+        fixed.timestep().as_secs_f32(),
+    ));
+}
+
+/// Left click fires a shell along the current arc
+fn fire_shell(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    launcher: Option<Single<(&Launcher, &Transform)>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(launcher) = launcher else {
+        return;
+    };
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (launcher, transform) = *launcher;
+    commands.spawn((
+        Shell {
+            velocity: launcher.muzzle_velocity(),
+            flight: 0f32,
+            predicted: launcher
+                .trajectory
+                .as_ref()
+                .and_then(|trajectory| trajectory.impact),
+        },
+        Mesh3d(meshes.add(Sphere::new(SHELL_RADIUS))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: ARC_COLOR,
+            emissive: LinearRgba::from(ARC_COLOR) * 4f32,
+            ..default()
+        })),
+        Transform::from_translation(muzzle(transform)),
+    ));
+}
+
+/// G seeds a finished patch of growth under the cursor to shoot at
+fn seed_growth(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    cursor: Res<ArcCursor>,
+    terrain: Res<TerrainWorld>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(center) = cursor.0.filter(|_| input.just_pressed(KeyCode::KeyG)) else {
+        return;
+    };
+    let colony = commands
+        .spawn((
+            CrimsonColony {
+                radius: GROWTH_RADIUS,
+                expansion_complete: true,
+                ..default()
+            },
+            Transform::from_translation(center),
+        ))
+        .id();
+
+    let mesh = meshes.add(Cuboid::new(PATCH_SIZE, PATCH_HEIGHT, PATCH_SIZE));
+    let mut rng = rand::thread_rng();
+    let reach = (GROWTH_RADIUS / SEEDED_PATCH_SPACING) as i32;
+    let mut patches = 0;
+    for z in -reach..=reach {
+        for x in -reach..=reach {
+            let offset = Vec2::new(x as f32, z as f32) * SEEDED_PATCH_SPACING;
+            if offset.length() > GROWTH_RADIUS {
+                continue;
+            }
+            let position = center.xz() + offset;
+            if terrain.water_depth_at(position) > 0f32 {
+                continue;
+            }
+            let age = rng.gen_range(0f32..=1f32);
+            commands.spawn((
+                GrowthPatch {
+                    _colony_entity: colony,
+                    age,
+                    maturation_rate: 0f32,
+                    health: FULL_HEALTH,
+                },
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(materials.add(growth_color(age))),
+                Transform::from_xyz(position.x, terrain.height_at(position), position.y),
+            ));
+            patches += 1;
+        }
+    }
+    info!("Seeded {patches} growth patches at {}", center.xz());
+}
+
+/// Digs and scorches a crater where each shell landed, strikes the growth
+/// around it, and reports how far off the prediction was
+fn blast_terrain(
+    mut commands: Commands,
+    mut impacts: EventReader<ShellImpact>,
+    mut terrain: ResMut<TerrainWorld>,
+    mut edits: ResMut<TerrainEdits>,
+    mut strikes: EventWriter<OrbitalStrike>,
+    mut patches: Query<&mut Transform, With<GrowthPatch>>,
+    mut report: ResMut<ImpactReport>,
+) {
+    for impact in impacts.read() {
+        let center = impact.point.xz();
+        let mut touched = HashSet::new();
+        for brush in [Brush::Lower, Brush::Paint(Biome::Badlands)] {
+            touched.extend(terrain.apply_brush(
+                &mut edits,
+                brush,
+                center,
+                BLAST_RADIUS,
+                CRATER_DEPTH,
+            ));
+        }
+        if !touched.is_empty() {
+            commands.queue(RefreshTerrainChunks(touched));
+        }
+        // Growth left in the crater settles onto its floor
+        for mut transform in &mut patches {
+            let position = transform.translation.xz();
+            if position.distance(center) < BLAST_RADIUS {
+                transform.translation.y = terrain.height_at(position);
+            }
+        }
+        strikes.write(OrbitalStrike {
+            center: impact.point.with_y(terrain.height_at(center)),
+            radius: BLAST_RADIUS,
+            strength: BLAST_STRENGTH,
+        });
+
+        let error = impact
+            .predicted
+            .map(|predicted| predicted.distance(impact.point));
+        match error {
+            Some(error) => info!(
+                "Impact at {center} after {:.2}s, {error:.2} units from the predicted point",
+                impact.flight
+            ),
+            None => info!(
+                "Impact at {center} after {:.2}s, unpredicted",
+                impact.flight
+            ),
+        }
+        report.0 = Some((impact.flight, error));
+    }
+}
+
+/// Draws the predicted arc, the barrel, and the impact marker with the blast
+/// radius around it
+fn draw_trajectory(mut gizmos: Gizmos, launcher: Option<Single<(&Launcher, &Transform)>>) {
+    let Some(launcher) = launcher else {
+        return;
+    };
+    let (launcher, transform) = *launcher;
+    let muzzle = muzzle(transform);
+    gizmos.line(
+        muzzle,
+        muzzle + launcher.muzzle_velocity().normalize() * BARREL_LENGTH,
+        BARREL_COLOR,
+    );
+    let Some(trajectory) = &launcher.trajectory else {
+        return;
+    };
+    gizmos.linestrip(trajectory.points.iter().copied(), ARC_COLOR);
+    if let Some(impact) = trajectory.impact {
+        let flat = Quat::from_rotation_x(FRAC_PI_2);
+        let marker = impact + Vec3::Y * MARKER_HEIGHT;
+        gizmos.circle(Isometry3d::new(marker, flat), BLAST_RADIUS, MARKER_COLOR);
+        gizmos.cross(
+            Isometry3d::new(marker, flat),
+            BLAST_RADIUS / 4f32,
+            MARKER_COLOR,
+        );
+    }
+}
+
+fn update_projectile_hud(
+    report: Res<ImpactReport>,
+    launcher: Option<Single<&Launcher>>,
+    mut hud: Single<&mut Text, With<ProjectileHud>>,
+) {
+    let Some(launcher) = launcher else {
+        hud.0 = "Generating terrain...".to_string();
+        return;
+    };
+    let arc = match launcher.trajectory.as_ref() {
+        Some(Trajectory {
+            impact: Some(_),
+            flight,
+            ..
+        }) => format!("lands in {flight:.1}s"),
+        _ => "leaves the map".to_string(),
+    };
+    let last = match report.0 {
+        Some((flight, Some(error))) => {
+            format!("Last shell: {flight:.2}s flight, {error:.2} off the prediction")
+        }
+        Some((flight, None)) => format!("Last shell: {flight:.2}s flight, unpredicted"),
+        None => "No shells landed yet".to_string(),
+    };
+    hud.0 = format!(
+        "Elevation {:.0}°, speed {:.0}, {arc}\n{last}",
+        launcher.elevation, launcher.speed
+    );
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_projectile_arc(
+    mut commands: Commands,
+    mut terrain_store: ResMut<TerrainStore>,
+    mut edits: ResMut<TerrainEdits>,
+    entities: Query<
+        Entity,
+        Or<(
+            With<TerrainMesh>,
+            With<Launcher>,
+            With<Shell>,
+            With<CrimsonColony>,
+            With<GrowthPatch>,
+            With<ScorchMark>,
+            With<ProjectileHud>,
+            With<Camera3d>,
+            With<DirectionalLight>,
+        )>,
+    >,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    // Craters only last for the session
+    terrain_store.0.clear();
+    edits.clear();
+    commands.remove_resource::<TerrainWorld>();
+    commands.remove_resource::<ArcCursor>();
+    commands.remove_resource::<ImpactReport>();
+}
@@ -0,0 +1,436 @@
+use std::sync::Arc;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
+
+mod views;
+
+use self::views::{DebugViews, draw_cost_field, draw_explored, draw_route, toggle_views};
+use super::{AppState, Experiment};
+use crate::growth::growth_color;
+use crate::navigation::{NavGrid, NavPath, PathRequest, SlopeCost};
+use crate::terrain::{GenerateTerrain, TerrainMesh, TerrainSettings, TerrainStore, TerrainWorld};
+
+// Navigation constants; one nav cell spans about two heightfield samples
+const NAV_CELL_SIZE: f32 = 10f32;
+const SLOPE_COST: SlopeCost = SlopeCost {
+    max_slope: 0.8, // about 39°
+    penalty: 6f32,
+};
+const GROWTH_COST: f32 = 25f32; // growth multiplies a cell's cost by this
+
+// Unit constants
+const UNIT_SPEED: f32 = 40f32; // world units per second on flat ground
+const UNIT_RADIUS: f32 = 3f32;
+const UNIT_COLOR: Color = Color::srgb(0.2, 0.5, 1f32);
+
+// Growth constants
+const GROWTH_RADIUS: f32 = 40f32;
+const GROWTH_TILE_HEIGHT: f32 = 0.5;
+
+// Camera and cursor constants
+const CAMERA_DISTANCE: f32 = 400f32;
+const MAX_RAY_DISTANCE: f32 = 5000f32;
+const CURSOR_COLOR: Color = Color::WHITE;
+
+/// The nav grid over the terrain, and the costs before any growth was seeded
+#[derive(Resource)]
+pub struct RuggedNav {
+    grid: Arc<NavGrid>,
+    ground: NavGrid,
+}
+
+impl RuggedNav {
+    /// Water is impassable, and slopes cost more the steeper they get
+    fn new(terrain: &TerrainWorld, settings: &TerrainSettings) -> Self {
+        let rect = settings.world_rect();
+        let size = (rect.size() / NAV_CELL_SIZE).as_uvec2();
+        let mut ground = NavGrid::from_heightfield(
+            &terrain.heightfield,
+            size,
+            rect.min,
+            Vec2::splat(NAV_CELL_SIZE),
+            SLOPE_COST,
+        );
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let cell = UVec2::new(x, y);
+                if terrain.water_depth_at(ground.cell_center(cell)) > 0f32 {
+                    ground.set_cost(cell, None);
+                }
+            }
+        }
+        Self {
+            grid: Arc::new(ground.clone()),
+            ground,
+        }
+    }
+
+    /// Makes every cell within a growth patch expensive to cross; patches
+    /// reaching past the map edge cover the cells on it
+    fn add_growth(&mut self, center: Vec2, radius: f32) -> Vec<UVec2> {
+        let (a, b) = (
+            self.ground.nearest_cell(center - radius),
+            self.ground.nearest_cell(center + radius),
+        );
+        let (min, max) = (a.min(b), a.max(b));
+        let mut covered = Vec::new();
+        let grid = Arc::make_mut(&mut self.grid);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = UVec2::new(x, y);
+                if grid.cell_center(cell).distance(center) > radius {
+                    continue;
+                }
+                let cost = self.ground.cost(cell).map(|cost| cost * GROWTH_COST);
+                grid.set_cost(cell, cost);
+                covered.push(cell);
+            }
+        }
+        covered
+    }
+}
+
+/// Walks the route it's given cell by cell, slower on steep ground
+#[derive(Component, Default)]
+pub struct Walker {
+    goal: Option<UVec2>,
+    /// Index of the route cell it's heading for
+    step: usize,
+    /// Seconds since startup when the current route was asked for
+    requested_at: f32,
+}
+
+/// One cell of a growth patch
+#[derive(Component)]
+pub struct Growth;
+
+/// Ground point under the cursor, if any
+#[derive(Resource, Default)]
+pub struct TerrainCursor(Option<Vec3>);
+
+#[derive(Component)]
+pub struct RuggedHud;
+
+#[derive(Component)]
+pub struct RuggedCamera;
+
+pub struct RuggedPathfindingExperiment;
+
+impl Experiment for RuggedPathfindingExperiment {
+    fn name(&self) -> &'static str {
+        "Rugged Terrain Pathfinding"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{f018}" // Font Awesome road icon
+    }
+
+    fn app_state(&self) -> AppState {
+        AppState::RuggedPathfinding
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        // The terrain experiment may have added the camera plugin already
+        if !app.is_plugin_added::<PanOrbitCameraPlugin>() {
+            app.add_plugins(PanOrbitCameraPlugin);
+        }
+        app.add_systems(
+            OnEnter(AppState::RuggedPathfinding),
+            setup_rugged_pathfinding,
+        )
+        .add_systems(
+            Update,
+            (
+                build_nav_grid.run_if(not(resource_exists::<RuggedNav>)),
+                (
+                    aim_cursor,
+                    command_unit,
+                    walk_unit,
+                    toggle_views,
+                    (
+                        draw_route,
+                        draw_cost_field,
+                        draw_explored,
+                        update_rugged_hud,
+                    ),
+                )
+                    .chain()
+                    .run_if(resource_exists::<RuggedNav>),
+            )
+                .run_if(in_state(AppState::RuggedPathfinding)),
+        )
+        .add_systems(
+            OnExit(AppState::RuggedPathfinding),
+            cleanup_rugged_pathfinding,
+        )
+    }
+}
+
+fn setup_rugged_pathfinding(mut commands: Commands) {
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(200f32, 400f32, 100f32).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0f32, CAMERA_DISTANCE, CAMERA_DISTANCE).looking_at(Vec3::ZERO, Vec3::Y),
+        PanOrbitCamera {
+            // The left button sends the unit
+            button_orbit: MouseButton::Right,
+            button_pan: MouseButton::Middle,
+            ..default()
+        },
+        RuggedCamera,
+    ));
+    commands.spawn((
+        RuggedHud,
+        Text::default(),
+        TextFont {
+            font_size: 18f32,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12f32),
+            left: Val::Px(12f32),
+            ..default()
+        },
+    ));
+    commands.insert_resource(TerrainCursor::default());
+    commands.insert_resource(DebugViews::default());
+    commands.queue(GenerateTerrain);
+
+    info!("Rugged terrain pathfinding loaded! Controls:");
+    info!("  LMB - Send the unit to the ground under the cursor");
+    info!("  G - Seed growth under the cursor");
+    info!("  1 - Toggle the route, 2 - the cost field, 3 - explored cells");
+    info!("  RMB drag - Orbit, MMB drag - Pan, Scroll - Zoom");
+    info!("  ESC - Return to launcher");
+}
+
+/// Builds the nav grid once the terrain is generated, and puts the unit on
+/// the open cell nearest the middle
+fn build_nav_grid(
+    mut commands: Commands,
+    terrain: Option<Res<TerrainWorld>>,
+    settings: Res<TerrainSettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(terrain) = terrain else {
+        return;
+    };
+    let nav = RuggedNav::new(&terrain, &settings);
+    let start = nav
+        .ground
+        .cell_at(Vec2::ZERO)
+        .and_then(|middle| {
+            let size = nav.ground.size();
+            (0..size.y)
+                .flat_map(|y| (0..size.x).map(move |x| UVec2::new(x, y)))
+                .filter(|cell| nav.ground.cost(*cell).is_some())
+                .min_by_key(|cell| cell.as_ivec2().distance_squared(middle.as_ivec2()))
+        })
+        .map_or(Vec2::ZERO, |cell| nav.ground.cell_center(cell));
+
+    commands.spawn((
+        Walker::default(),
+        Mesh3d(meshes.add(Sphere::new(UNIT_RADIUS))),
+        MeshMaterial3d(materials.add(UNIT_COLOR)),
+        Transform::from_translation(on_ground(&terrain, start)),
+    ));
+    commands.insert_resource(nav);
+}
+
+/// Where the unit stands at a world XZ position
+fn on_ground(terrain: &TerrainWorld, position: Vec2) -> Vec3 {
+    Vec3::new(
+        position.x,
+        terrain.surface_height_at(position) + UNIT_RADIUS,
+        position.y,
+    )
+}
+
+/// Finds the ground under the cursor and marks it
+fn aim_cursor(
+    mut cursor: ResMut<TerrainCursor>,
+    terrain: Res<TerrainWorld>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<RuggedCamera>>,
+    mut gizmos: Gizmos,
+) {
+    let (camera, camera_transform) = *camera;
+    cursor.0 = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world(camera_transform, position).ok())
+        .and_then(|ray| terrain.raycast(ray, MAX_RAY_DISTANCE));
+    if let Some(target) = cursor.0 {
+        gizmos.circle(
+            Isometry3d::new(
+                target + Vec3::Y * 0.5,
+                Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+            ),
+            NAV_CELL_SIZE / 2f32,
+            CURSOR_COLOR,
+        );
+    }
+}
+
+/// Left click sends the unit to the cursor; G seeds growth there, rerouting
+/// the unit if it's under way
+#[allow(clippy::too_many_arguments)]
+fn command_unit(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor: Res<TerrainCursor>,
+    terrain: Res<TerrainWorld>,
+    mut nav: ResMut<RuggedNav>,
+    unit: Single<(Entity, &mut Walker, &Transform)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(target) = cursor.0 else {
+        return;
+    };
+    let (entity, mut walker, transform) = unit.into_inner();
+    let mut reroute = false;
+
+    if input.just_pressed(KeyCode::KeyG) {
+        let covered = nav.add_growth(target.xz(), GROWTH_RADIUS);
+        let tile = meshes.add(Cuboid::new(
+            NAV_CELL_SIZE,
+            GROWTH_TILE_HEIGHT,
+            NAV_CELL_SIZE,
+        ));
+        let material = materials.add(growth_color(0.5));
+        for cell in &covered {
+            let center = nav.grid.cell_center(*cell);
+            commands.spawn((
+                Growth,
+                Mesh3d(tile.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(center.x, terrain.surface_height_at(center), center.y),
+            ));
+        }
+        info!("Growth seeded over {} cells", covered.len());
+        reroute = walker.goal.is_some();
+    }
+    // Clicks off the grid are ignored so the current goal and route stay paired
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some(goal) = nav.grid.cell_at(target.xz())
+    {
+        walker.goal = Some(goal);
+        reroute = true;
+    }
+
+    let (Some(from), Some(goal)) = (nav.grid.cell_at(transform.translation.xz()), walker.goal)
+    else {
+        return;
+    };
+    if reroute {
+        // The old route is kept until the new one is found
+        walker.requested_at = time.elapsed_secs();
+        commands
+            .entity(entity)
+            .insert(PathRequest::new(&nav.grid, from, goal));
+    }
+}
+
+/// Moves the unit along its route, slowed by each cell's cost
+fn walk_unit(
+    time: Res<Time>,
+    terrain: Res<TerrainWorld>,
+    nav: Res<RuggedNav>,
+    unit: Single<(&mut Walker, &mut Transform, Option<Ref<NavPath>>)>,
+) {
+    let (mut walker, mut transform, path) = unit.into_inner();
+    let Some(path) = path else {
+        return;
+    };
+    if path.is_changed() {
+        walker.step = 0;
+        info!(
+            "Route of {} cells costs {:.1} flat cells, found in {:.0}ms after exploring {} cells",
+            path.cells.len(),
+            path.cost,
+            (time.elapsed_secs() - walker.requested_at) * 1000f32,
+            path.explored.len()
+        );
+    }
+    let Some(cell) = path.cells.get(walker.step) else {
+        return;
+    };
+
+    let position = transform.translation.xz();
+    let next = nav.grid.cell_center(*cell);
+    let speed = UNIT_SPEED / nav.grid.cost(*cell).unwrap_or(1f32);
+    let reach = speed * time.delta_secs();
+    let moved = if position.distance(next) <= reach {
+        walker.step += 1;
+        if walker.step == path.cells.len() {
+            info!("Unit arrived");
+        }
+        next
+    } else {
+        position + (next - position).normalize() * reach
+    };
+    transform.translation = on_ground(&terrain, moved);
+}
+
+fn update_rugged_hud(
+    views: Res<DebugViews>,
+    unit: Single<(&Walker, Option<&NavPath>, Has<PathRequest>)>,
+    mut hud: Single<&mut Text, With<RuggedHud>>,
+) {
+    let (walker, path, searching) = *unit;
+    let route = match (path, walker.goal) {
+        _ if searching => "Searching...".to_string(),
+        (Some(path), Some(_)) => format!(
+            "Route: {} cells, cost {:.1}, {} cells explored",
+            path.cells.len(),
+            path.cost,
+            path.explored.len()
+        ),
+        (None, Some(_)) => "No route to the goal".to_string(),
+        (_, None) => "Click the ground to send the unit".to_string(),
+    };
+    let toggle = |on: bool| if on { "on" } else { "off" };
+    hud.0 = format!(
+        "{route}\nRoute view {}, cost field {}, explored cells {}",
+        toggle(views.route),
+        toggle(views.costs),
+        toggle(views.explored)
+    );
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_rugged_pathfinding(
+    mut commands: Commands,
+    mut terrain_store: ResMut<TerrainStore>,
+    entities: Query<
+        Entity,
+        Or<(
+            With<TerrainMesh>,
+            With<Walker>,
+            With<Growth>,
+            With<RuggedHud>,
+            With<Camera3d>,
+            With<DirectionalLight>,
+        )>,
+    >,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    terrain_store.0.clear();
+    commands.remove_resource::<TerrainWorld>();
+    commands.remove_resource::<RuggedNav>();
+    commands.remove_resource::<TerrainCursor>();
+    commands.remove_resource::<DebugViews>();
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use super::{GROWTH_COST, NAV_CELL_SIZE, RuggedNav, SLOPE_COST, TerrainCursor, Walker};
+use crate::navigation::NavPath;
+use crate::terrain::TerrainWorld;
+
+// Debug views float this far over the ground so it doesn't hide them
+const VIEW_LIFT: f32 = 1f32;
+
+// The cost field is drawn for this many cells either side of the cursor
+const COST_VIEW_CELLS: i32 = 24;
+
+// Debug colors
+const ROUTE_COLOR: Color = Color::srgb(1f32, 0.9, 0.2);
+const FLAT_COLOR: Color = Color::srgb(0.2, 0.9, 0.3);
+const STEEP_COLOR: Color = Color::srgb(1f32, 0.5, 0.1);
+const GROWTH_CELL_COLOR: Color = Color::srgb(0.9, 0.2, 0.9);
+const BLOCKED_COLOR: Color = Color::srgb(0.8, 0.1, 0.1);
+const EXPLORED_COLOR: Color = Color::srgba(0.3, 0.8, 1f32, 0.5);
+
+/// Which debug views are drawn
+#[derive(Resource)]
+pub struct DebugViews {
+    pub route: bool,
+    pub costs: bool,
+    pub explored: bool,
+}
+
+impl Default for DebugViews {
+    fn default() -> Self {
+        Self {
+            route: true,
+            costs: false,
+            explored: false,
+        }
+    }
+}
+
+/// 1-3 toggle the route, the cost field and the explored cells
+pub fn toggle_views(mut views: ResMut<DebugViews>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::Digit1) {
+        views.route = !views.route;
+    }
+    if input.just_pressed(KeyCode::Digit2) {
+        views.costs = !views.costs;
+    }
+    if input.just_pressed(KeyCode::Digit3) {
+        views.explored = !views.explored;
+    }
+}
+
+/// Point just over the ground at a cell's center
+fn over_cell(terrain: &TerrainWorld, nav: &RuggedNav, cell: UVec2) -> Vec3 {
+    let center = nav.grid.cell_center(cell);
+    Vec3::new(
+        center.x,
+        terrain.surface_height_at(center) + VIEW_LIFT,
+        center.y,
+    )
+}
+
+/// The rest of the unit's route, from where it stands
+pub fn draw_route(
+    mut gizmos: Gizmos,
+    views: Res<DebugViews>,
+    terrain: Res<TerrainWorld>,
+    nav: Res<RuggedNav>,
+    unit: Single<(&Walker, &Transform, Option<&NavPath>)>,
+) {
+    let (walker, transform, path) = *unit;
+    let Some(path) = path.filter(|_| views.route) else {
+        return;
+    };
+    let remaining = path.cells.iter().skip(walker.step);
+    let points = std::iter::once(transform.translation)
+        .chain(remaining.map(|cell| over_cell(&terrain, &nav, *cell)));
+    gizmos.linestrip(points, ROUTE_COLOR);
+}
+
+/// Outlines the cells around the cursor, or the unit, colored by what they
+/// cost to cross: green to orange with slope, magenta under growth, red where
+/// blocked
+pub fn draw_cost_field(
+    mut gizmos: Gizmos,
+    views: Res<DebugViews>,
+    terrain: Res<TerrainWorld>,
+    nav: Res<RuggedNav>,
+    cursor: Res<TerrainCursor>,
+    unit: Single<&Transform, With<Walker>>,
+) {
+    if !views.costs {
+        return;
+    }
+    let around = cursor.0.unwrap_or(unit.translation).xz();
+    let Some(middle) = nav.grid.cell_at(around) else {
+        return;
+    };
+    let steepest = 1f32 + SLOPE_COST.penalty * SLOPE_COST.max_slope;
+    for y in -COST_VIEW_CELLS..=COST_VIEW_CELLS {
+        for x in -COST_VIEW_CELLS..=COST_VIEW_CELLS {
+            let cell = middle.as_ivec2() + IVec2::new(x, y);
+            if cell.cmplt(IVec2::ZERO).any() || cell.cmpge(nav.grid.size().as_ivec2()).any() {
+                continue;
+            }
+            let cell = cell.as_uvec2();
+            let center = over_cell(&terrain, &nav, cell);
+            let color = match nav.grid.cost(cell) {
+                None => BLOCKED_COLOR,
+                Some(cost) if cost >= GROWTH_COST => GROWTH_CELL_COLOR,
+                Some(cost) => FLAT_COLOR.mix(
+                    &STEEP_COLOR,
+                    ((cost - 1f32) / (steepest - 1f32)).clamp(0f32, 1f32),
+                ),
+            };
+            gizmos.rect(
+                Isometry3d::new(center, Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                Vec2::splat(NAV_CELL_SIZE * 0.8),
+                color,
+            );
+        }
+    }
+}
+
+/// Marks every cell the last route search expanded
+pub fn draw_explored(
+    mut gizmos: Gizmos,
+    views: Res<DebugViews>,
+    terrain: Res<TerrainWorld>,
+    nav: Res<RuggedNav>,
+    path: Single<Option<&NavPath>, With<Walker>>,
+) {
+    let Some(path) = (*path).filter(|_| views.explored) else {
+        return;
+    };
+    for cell in &path.explored {
+        let point = over_cell(&terrain, &nav, *cell);
+        gizmos.line(
+            point,
+            point + Vec3::Y * NAV_CELL_SIZE / 2f32,
+            EXPLORED_COLOR,
+        );
+    }
+}
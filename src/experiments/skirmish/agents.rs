@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    Player,
+    arena::Arena,
+    combat::{Combatant, ShotFired, Team, fire_shot},
+};
+use crate::navigation::{FlowField, FlowFieldRequest};
+
+// Agent balance constants
+pub const AGENT_HEALTH: f32 = 40f32;
+const AGENT_SPEED: f32 = 90f32; // world units per second
+const AGENT_RANGE: f32 = 320f32; // agents close in until the player is this near
+const AGENT_DAMAGE: f32 = 10f32;
+const AGENT_COOLDOWN_SECS: f32 = 1.1;
+const AGENT_REACTION_SECS: f32 = 0.6; // from first seeing the player to the first shot
+const AGENT_SPREAD: f32 = 0.15; // radians either side of the aim
+
+// Debug colors
+const PATH_COLOR: Color = Color::srgba(1f32, 0.6, 0.2, 0.35);
+const SIGHT_COLOR: Color = Color::srgba(1f32, 0.2, 0.2, 0.5);
+
+/// AI-driven combatant: closes in along the flow field toward the player
+/// until it can see them within range, then stands and shoots
+#[derive(Component)]
+pub struct Agent {
+    /// Cell it's heading for, next on the way to the player
+    next: Option<UVec2>,
+    cooldown: Timer,
+    sees_player: bool,
+}
+
+impl Default for Agent {
+    fn default() -> Self {
+        Self {
+            next: None,
+            cooldown: Timer::from_seconds(AGENT_COOLDOWN_SECS, TimerMode::Once),
+            sees_player: false,
+        }
+    }
+}
+
+/// Rebuilds the flow field toward the player whenever they reach a new cell;
+/// every agent steers by the same field
+#[allow(clippy::type_complexity)]
+pub fn request_flow_field(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    player: Option<
+        Single<(Entity, &Transform, Option<&FlowField>), (With<Player>, Without<FlowFieldRequest>)>,
+    >,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let (entity, transform, field) = *player;
+    let Some(cell) = arena.grid.cell_at(transform.translation.xy()) else {
+        return;
+    };
+    if field.is_none_or(|field| field.target() != cell) {
+        commands
+            .entity(entity)
+            .insert(FlowFieldRequest::new(&arena.grid, cell));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn run_agents(
+    mut commands: Commands,
+    time: Res<Time>,
+    arena: Res<Arena>,
+    player: Option<Single<(&Transform, Option<&FlowField>), (With<Player>, Without<Agent>)>>,
+    mut agents: Query<(&mut Agent, &Combatant, &mut Transform)>,
+    mut shots_fired: EventWriter<ShotFired>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let (player, field) = *player;
+    let target = player.translation.xy();
+    let mut rng = rand::thread_rng();
+    for (mut agent, combatant, mut transform) in &mut agents {
+        let position = transform.translation.xy();
+        agent.cooldown.tick(time.delta());
+
+        let sees_player = arena.grid.line_of_sight(position, target);
+        if sees_player && !agent.sees_player {
+            // Spotting the player takes a moment before the first shot
+            let wait = agent.cooldown.remaining_secs().max(AGENT_REACTION_SECS);
+            agent.cooldown = Timer::from_seconds(wait, TimerMode::Once);
+        }
+        agent.sees_player = sees_player;
+
+        if sees_player && position.distance(target) <= AGENT_RANGE {
+            agent.next = None;
+            if agent.cooldown.finished() {
+                let aim =
+                    (target - position).to_angle() + rng.gen_range(-AGENT_SPREAD..=AGENT_SPREAD);
+                fire_shot(
+                    &mut commands,
+                    &mut shots_fired,
+                    Team::Agents,
+                    position,
+                    combatant.radius,
+                    Vec2::from_angle(aim),
+                    AGENT_DAMAGE,
+                );
+                agent.cooldown = Timer::from_seconds(AGENT_COOLDOWN_SECS, TimerMode::Once);
+            }
+            continue;
+        }
+
+        // Close in on the player, one flow field step at a time; the last
+        // step before the player's cell is the player themselves
+        agent.next = field
+            .zip(arena.grid.cell_at(position))
+            .and_then(|(field, cell)| field.next(cell));
+        let Some(next) = agent.next.map(|cell| {
+            if field.is_some_and(|field| field.target() == cell) {
+                target
+            } else {
+                arena.grid.cell_center(cell)
+            }
+        }) else {
+            continue;
+        };
+        let step = (next - position).clamp_length_max(AGENT_SPEED * time.delta_secs());
+        // Slide along walls when cutting a corner
+        let mut moved = position;
+        for axis_step in [Vec2::new(step.x, 0f32), Vec2::new(0f32, step.y)] {
+            if !arena
+                .grid
+                .blocks_circle(moved + axis_step, combatant.radius)
+            {
+                moved += axis_step;
+            }
+        }
+        transform.translation = moved.extend(transform.translation.z);
+    }
+}
+
+/// Faintly draws the route each agent's flow field steps take, and its line
+/// of sight while it has one
+#[allow(clippy::type_complexity)]
+pub fn draw_agent_intent(
+    mut gizmos: Gizmos,
+    arena: Res<Arena>,
+    agents: Query<(&Agent, &Transform)>,
+    player: Option<Single<(&Transform, Option<&FlowField>), (With<Player>, Without<Agent>)>>,
+) {
+    let field = player.as_ref().and_then(|player| player.1);
+    for (agent, transform) in &agents {
+        let position = transform.translation.xy();
+        if let (Some(field), Some(next)) = (field, agent.next) {
+            let route = std::iter::successors(Some(next), |cell| field.next(*cell))
+                .map(|cell| arena.grid.cell_center(cell));
+            gizmos.linestrip_2d(std::iter::once(position).chain(route), PATH_COLOR);
+        }
+        if let Some(player) = &player
+            && agent.sees_player
+        {
+            gizmos.line_2d(position, player.0.translation.xy(), SIGHT_COLOR);
+        }
+    }
+}
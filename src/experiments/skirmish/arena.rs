@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::navigation::NavGrid;
+
+/// World size of one arena cell
+pub const CELL_SIZE: f32 = 24f32;
+
+/// Arena layout, top row first: `#` wall, `P` player start, `A` agent start
+const LAYOUT: [&str; 20] = [
+    "################################",
+    "#..............................#",
+    "#..P...........#...............#",
+    "#..............#.......A.......#",
+    "#.....####.....#...............#",
+    "#.....#........#.....#####.....#",
+    "#.....#..............#.........#",
+    "#.....#..............#.....A...#",
+    "#..........#####.....#.........#",
+    "#..............................#",
+    "#..............................#",
+    "#.........#####.......####.....#",
+    "#.....A.......#................#",
+    "#.............#.......#........#",
+    "#...#####.....#.......#....A...#",
+    "#.............#.......#........#",
+    "#.....................#........#",
+    "#..........#######.............#",
+    "#..............................#",
+    "################################",
+];
+
+/// Flat arena on a grid of open and wall cells, centered on the origin with
+/// cell (0, 0) at the bottom left
+#[derive(Resource)]
+pub struct Arena {
+    /// Walls are the blocked cells; shared with pathfinding tasks
+    pub grid: Arc<NavGrid>,
+    extent: Vec2,
+    pub player_start: UVec2,
+    pub agent_starts: Vec<UVec2>,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        let size = UVec2::new(LAYOUT[0].len() as u32, LAYOUT.len() as u32);
+        let extent = size.as_vec2() * CELL_SIZE;
+        let mut grid = NavGrid::new(size, -extent / 2f32, Vec2::splat(CELL_SIZE));
+        let mut player_start = UVec2::ZERO;
+        let mut agent_starts = Vec::new();
+        for (row, line) in LAYOUT.iter().enumerate() {
+            let y = size.y - 1 - row as u32;
+            for (x, cell) in line.chars().enumerate() {
+                let pos = UVec2::new(x as u32, y);
+                match cell {
+                    '#' => grid.set_cost(pos, None),
+                    'P' => player_start = pos,
+                    'A' => agent_starts.push(pos),
+                    _ => {}
+                }
+            }
+        }
+        Self {
+            grid: Arc::new(grid),
+            extent,
+            player_start,
+            agent_starts,
+        }
+    }
+}
+
+impl Arena {
+    /// World size of the whole arena
+    pub fn extent(&self) -> Vec2 {
+        self.extent
+    }
+}
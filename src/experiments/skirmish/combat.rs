@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+
+use super::arena::Arena;
+
+// Shot constants
+const SHOT_SPEED: f32 = 480f32; // world units per second
+const SHOT_RANGE: f32 = 600f32; // shots fizzle after this far
+const SHOT_SIZE: f32 = 4f32;
+
+// Feedback constants
+const HIT_FLASH_SECS: f32 = 0.12;
+const HEALTH_BAR_WIDTH: f32 = 20f32;
+const HEALTH_BAR_OFFSET: f32 = 14f32; // above the unit's center
+const HEALTH_COLOR: Color = Color::srgb(0.2, 0.9, 0.3);
+const LOST_HEALTH_COLOR: Color = Color::srgb(0.5, 0.1, 0.1);
+
+/// Side a combatant or shot is on; shots only hit the other side
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Team {
+    Player,
+    Agents,
+}
+
+impl Team {
+    pub fn color(self) -> Color {
+        match self {
+            Team::Player => Color::srgb(0.3, 0.6, 1f32),
+            Team::Agents => Color::srgb(1f32, 0.4, 0.2),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Round body shots collide with
+#[derive(Component)]
+pub struct Combatant {
+    pub radius: f32,
+}
+
+/// Briefly whitens a combatant that was hit
+#[derive(Component)]
+pub struct HitFlash(Timer);
+
+#[derive(Component)]
+pub struct Shot {
+    team: Team,
+    damage: f32,
+    velocity: Vec2,
+    travelled: f32,
+}
+
+/// A shot was fired
+#[derive(Event)]
+pub struct ShotFired {
+    pub team: Team,
+}
+
+/// A shot reached a combatant of the other team
+#[derive(Event)]
+pub struct Hit {
+    pub target: Entity,
+    pub team: Team,
+    pub damage: f32,
+}
+
+/// A combatant ran out of health; it's despawned after this is sent
+#[derive(Event)]
+pub struct Killed {
+    pub team: Team,
+}
+
+/// Shoots from a combatant's edge toward `direction`
+pub fn fire_shot(
+    commands: &mut Commands,
+    shots_fired: &mut EventWriter<ShotFired>,
+    team: Team,
+    from: Vec2,
+    radius: f32,
+    direction: Vec2,
+    damage: f32,
+) {
+    let direction = direction.normalize_or(Vec2::X);
+    commands.spawn((
+        Shot {
+            team,
+            damage,
+            velocity: direction * SHOT_SPEED,
+            travelled: 0f32,
+        },
+        Sprite::from_color(team.color().lighter(0.2), Vec2::splat(SHOT_SIZE)),
+        Transform::from_translation((from + direction * radius).extend(2f32)),
+    ));
+    shots_fired.write(ShotFired { team });
+}
+
+/// Closest distance from a point to the segment between `from` and `to`
+fn distance_to_segment(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let along = to - from;
+    let t =
+        ((point - from).dot(along) / along.length_squared().max(f32::EPSILON)).clamp(0f32, 1f32);
+    point.distance(from + along * t)
+}
+
+/// Moves shots and registers what they hit along the way this frame, so fast
+/// shots can't pass through thin targets
+pub fn move_shots(
+    mut commands: Commands,
+    time: Res<Time>,
+    arena: Res<Arena>,
+    mut shots: Query<(Entity, &mut Shot, &mut Transform)>,
+    combatants: Query<(Entity, &Team, &Combatant, &Transform), Without<Shot>>,
+    mut hits: EventWriter<Hit>,
+) {
+    for (entity, mut shot, mut transform) in &mut shots {
+        let from = transform.translation.xy();
+        let to = from + shot.velocity * time.delta_secs();
+        let target = combatants
+            .iter()
+            .filter(|(_, team, ..)| **team != shot.team)
+            .map(|(target, _, combatant, target_transform)| {
+                let center = target_transform.translation.xy();
+                let reach = combatant.radius + SHOT_SIZE / 2f32;
+                (
+                    target,
+                    center,
+                    distance_to_segment(center, from, to) <= reach,
+                )
+            })
+            .filter(|(.., hit)| *hit)
+            .min_by(|(_, a, _), (_, b, _)| from.distance(*a).total_cmp(&from.distance(*b)));
+
+        if let Some((target, center, _)) = target
+            && arena.grid.line_of_sight(from, center)
+        {
+            hits.write(Hit {
+                target,
+                team: shot.team,
+                damage: shot.damage,
+            });
+            commands.entity(entity).despawn();
+            continue;
+        }
+        shot.travelled += from.distance(to);
+        if !arena.grid.line_of_sight(from, to) || shot.travelled > SHOT_RANGE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation = to.extend(transform.translation.z);
+    }
+}
+
+/// Applies damage from hits and removes combatants with no health left
+pub fn apply_hits(
+    mut commands: Commands,
+    mut hits: EventReader<Hit>,
+    mut targets: Query<(&Team, &mut Health)>,
+    mut kills: EventWriter<Killed>,
+) {
+    for hit in hits.read() {
+        let Ok((team, mut health)) = targets.get_mut(hit.target) else {
+            // Already killed by an earlier hit this frame
+            continue;
+        };
+        if health.current <= 0f32 {
+            continue;
+        }
+        health.current -= hit.damage;
+        if health.current <= 0f32 {
+            kills.write(Killed { team: *team });
+            commands.entity(hit.target).despawn();
+        } else {
+            commands
+                .entity(hit.target)
+                .insert(HitFlash(Timer::from_seconds(
+                    HIT_FLASH_SECS,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+pub fn flash_hit_combatants(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashing: Query<(Entity, &Team, &mut HitFlash, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, team, mut flash, material) in &mut flashing {
+        flash.0.tick(time.delta());
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        if flash.0.finished() {
+            material.color = team.color();
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            material.color = Color::WHITE;
+        }
+    }
+}
+
+pub fn draw_health_bars(mut gizmos: Gizmos, combatants: Query<(&Health, &Transform)>) {
+    for (health, transform) in &combatants {
+        let left =
+            transform.translation.xy() + Vec2::new(-HEALTH_BAR_WIDTH / 2f32, HEALTH_BAR_OFFSET);
+        let right = left + Vec2::X * HEALTH_BAR_WIDTH;
+        let split = left.lerp(right, (health.current / health.max).clamp(0f32, 1f32));
+        gizmos.line_2d(left, split, HEALTH_COLOR);
+        gizmos.line_2d(split, right, LOST_HEALTH_COLOR);
+    }
+}
@@ -0,0 +1,425 @@
+mod agents;
+mod arena;
+mod combat;
+
+use bevy::{prelude::*, render::camera::ScalingMode, window::PrimaryWindow};
+
+use self::{
+    agents::{AGENT_HEALTH, Agent, draw_agent_intent, request_flow_field, run_agents},
+    arena::{Arena, CELL_SIZE},
+    combat::{
+        Combatant, Health, Hit, Killed, Shot, ShotFired, Team, apply_hits, draw_health_bars,
+        fire_shot, flash_hit_combatants, move_shots,
+    },
+};
+use super::{AppState, Experiment};
+
+// Player constants
+const PLAYER_HEALTH: f32 = 100f32;
+const PLAYER_SPEED: f32 = 150f32; // world units per second
+const PLAYER_DAMAGE: f32 = 20f32;
+const PLAYER_COOLDOWN_SECS: f32 = 0.25;
+
+// Arena look
+const COMBATANT_RADIUS: f32 = 8f32;
+const FLOOR_COLOR: Color = Color::srgb(0.16, 0.17, 0.15);
+const WALL_COLOR: Color = Color::srgb(0.45, 0.45, 0.5);
+const VIEW_MARGIN: f32 = 32f32;
+
+// The next fight starts this long after one ends
+const RESTART_SECS: f32 = 3f32;
+
+/// The avatar the player drives
+#[derive(Component)]
+pub struct Player {
+    cooldown: Timer,
+}
+
+/// Arena floor and walls
+#[derive(Component)]
+pub struct ArenaTile;
+
+#[derive(Component)]
+pub struct SkirmishHud;
+
+/// Shots fired and hits landed by one team
+#[derive(Default, Clone, Copy)]
+struct Accuracy {
+    shots: u32,
+    hits: u32,
+}
+
+impl Accuracy {
+    fn rate(&self) -> f32 {
+        if self.shots == 0 {
+            0f32
+        } else {
+            self.hits as f32 / self.shots as f32 * 100f32
+        }
+    }
+}
+
+/// The current fight and the figures fights are judged by
+#[derive(Resource, Default)]
+pub struct SkirmishMetrics {
+    /// Seconds the current fight has run
+    elapsed: f32,
+    player: Accuracy,
+    agents: Accuracy,
+    /// Set once the fight is decided, counting down to the next one
+    restart: Option<Timer>,
+    /// Durations of finished fights, and whether the player won each
+    fights: Vec<(f32, bool)>,
+    total_player: Accuracy,
+    total_agents: Accuracy,
+}
+
+impl SkirmishMetrics {
+    fn average_duration(&self) -> f32 {
+        let total: f32 = self.fights.iter().map(|(duration, _)| duration).sum();
+        total / self.fights.len().max(1) as f32
+    }
+
+    fn finish(&mut self, player_won: bool) {
+        self.fights.push((self.elapsed, player_won));
+        self.restart = Some(Timer::from_seconds(RESTART_SECS, TimerMode::Once));
+        self.total_player.shots += self.player.shots;
+        self.total_player.hits += self.player.hits;
+        self.total_agents.shots += self.agents.shots;
+        self.total_agents.hits += self.agents.hits;
+
+        info!(
+            "Fight {} in {:.1}s: player hit {}/{} ({:.0}%), agents hit {}/{} ({:.0}%)",
+            if player_won { "won" } else { "lost" },
+            self.elapsed,
+            self.player.hits,
+            self.player.shots,
+            self.player.rate(),
+            self.agents.hits,
+            self.agents.shots,
+            self.agents.rate()
+        );
+        let wins = self.fights.iter().filter(|(_, won)| *won).count();
+        info!(
+            "Metrics over {} fights ({wins} won): average duration {:.1}s, player hit rate {:.0}%, agent hit rate {:.0}%",
+            self.fights.len(),
+            self.average_duration(),
+            self.total_player.rate(),
+            self.total_agents.rate()
+        );
+    }
+}
+
+pub struct SkirmishExperiment;
+
+impl Experiment for SkirmishExperiment {
+    fn name(&self) -> &'static str {
+        "Simple Skirmish"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{f132}"
+    }
+
+    fn app_state(&self) -> AppState {
+        AppState::Skirmish
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        app.add_event::<ShotFired>()
+            .add_event::<Hit>()
+            .add_event::<Killed>()
+            .add_systems(OnEnter(AppState::Skirmish), setup_skirmish)
+            .add_systems(
+                Update,
+                (
+                    (drive_player, request_flow_field, run_agents),
+                    move_shots,
+                    apply_hits,
+                    (record_metrics, flash_hit_combatants),
+                    restart_fight,
+                    (draw_health_bars, draw_agent_intent, update_skirmish_hud),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Skirmish)),
+            )
+            .add_systems(OnExit(AppState::Skirmish), cleanup_skirmish)
+    }
+}
+
+fn setup_skirmish(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let arena = Arena::default();
+    let view = arena.extent() + 2f32 * VIEW_MARGIN;
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: view.x,
+                min_height: view.y,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+    commands.spawn((
+        ArenaTile,
+        Sprite::from_color(FLOOR_COLOR, arena.extent()),
+        Transform::default(),
+    ));
+    for wall in arena.grid.blocked_cells() {
+        commands.spawn((
+            ArenaTile,
+            Sprite::from_color(WALL_COLOR, Vec2::splat(CELL_SIZE)),
+            Transform::from_translation(arena.grid.cell_center(wall).extend(1f32)),
+        ));
+    }
+    commands.spawn((
+        SkirmishHud,
+        Text::default(),
+        TextFont {
+            font_size: 18f32,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12f32),
+            left: Val::Px(12f32),
+            ..default()
+        },
+    ));
+    spawn_combatants(&mut commands, &arena, &mut meshes, &mut materials);
+    commands.insert_resource(arena);
+    commands.insert_resource(SkirmishMetrics::default());
+
+    info!("Skirmish loaded! Controls:");
+    info!("  WASD - Move");
+    info!("  Mouse - Aim");
+    info!("  LMB (hold) - Fire");
+    info!("  R - Restart the fight");
+    info!("  ESC - Return to launcher");
+}
+
+fn spawn_combatants(
+    commands: &mut Commands,
+    arena: &Arena,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let body = meshes.add(Circle::new(COMBATANT_RADIUS));
+    // Each combatant gets its own material so it can flash on its own
+    let mut combatant = |team: Team, cell: UVec2, max_health: f32| {
+        (
+            team,
+            Combatant {
+                radius: COMBATANT_RADIUS,
+            },
+            Health::new(max_health),
+            Mesh2d(body.clone()),
+            MeshMaterial2d(materials.add(team.color())),
+            Transform::from_translation(arena.grid.cell_center(cell).extend(3f32)),
+        )
+    };
+    commands.spawn((
+        combatant(Team::Player, arena.player_start, PLAYER_HEALTH),
+        Player {
+            cooldown: Timer::from_seconds(PLAYER_COOLDOWN_SECS, TimerMode::Once),
+        },
+    ));
+    for cell in &arena.agent_starts {
+        commands.spawn((
+            combatant(Team::Agents, *cell, AGENT_HEALTH),
+            Agent::default(),
+        ));
+    }
+}
+
+/// WASD moves the player, sliding along walls; holding the left button fires
+/// toward the cursor
+#[allow(clippy::too_many_arguments)]
+fn drive_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    arena: Res<Arena>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    player: Option<Single<(&mut Player, &Combatant, &mut Transform)>>,
+    mut shots_fired: EventWriter<ShotFired>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let (mut player, combatant, mut transform) = player.into_inner();
+    player.cooldown.tick(time.delta());
+
+    let mut direction = Vec2::ZERO;
+    for (key, step) in [
+        (KeyCode::KeyW, Vec2::Y),
+        (KeyCode::KeyS, Vec2::NEG_Y),
+        (KeyCode::KeyA, Vec2::NEG_X),
+        (KeyCode::KeyD, Vec2::X),
+    ] {
+        if input.pressed(key) {
+            direction += step;
+        }
+    }
+    let step = direction.normalize_or_zero() * PLAYER_SPEED * time.delta_secs();
+    let mut position = transform.translation.xy();
+    // One axis at a time, so pressing into a wall slides along it
+    for axis_step in [Vec2::new(step.x, 0f32), Vec2::new(0f32, step.y)] {
+        if !arena
+            .grid
+            .blocks_circle(position + axis_step, combatant.radius)
+        {
+            position += axis_step;
+        }
+    }
+    transform.translation = position.extend(transform.translation.z);
+
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+    if mouse.pressed(MouseButton::Left) && player.cooldown.finished() {
+        fire_shot(
+            &mut commands,
+            &mut shots_fired,
+            Team::Player,
+            position,
+            combatant.radius,
+            cursor - position,
+            PLAYER_DAMAGE,
+        );
+        player.cooldown.reset();
+    }
+}
+
+/// Counts shots and hits, and ends the fight once a side is wiped out
+fn record_metrics(
+    time: Res<Time>,
+    mut shots_fired: EventReader<ShotFired>,
+    mut hits: EventReader<Hit>,
+    mut kills: EventReader<Killed>,
+    agents: Query<(), With<Agent>>,
+    mut metrics: ResMut<SkirmishMetrics>,
+) {
+    // Shots still in flight when the fight ends don't count
+    if metrics.restart.is_some() {
+        shots_fired.clear();
+        hits.clear();
+        kills.clear();
+        return;
+    }
+    metrics.elapsed += time.delta_secs();
+    for shot in shots_fired.read() {
+        match shot.team {
+            Team::Player => metrics.player.shots += 1,
+            Team::Agents => metrics.agents.shots += 1,
+        }
+    }
+    for hit in hits.read() {
+        match hit.team {
+            Team::Player => metrics.player.hits += 1,
+            Team::Agents => metrics.agents.hits += 1,
+        }
+    }
+    let mut player_killed = false;
+    let mut agents_killed = 0;
+    for kill in kills.read() {
+        match kill.team {
+            Team::Player => player_killed = true,
+            Team::Agents => agents_killed += 1,
+        }
+    }
+    // Killed combatants were despawned before this runs
+    if player_killed {
+        metrics.finish(false);
+    } else if agents_killed > 0 && agents.is_empty() {
+        metrics.finish(true);
+    }
+}
+
+/// Starts a fresh fight on R, or a few seconds after the last one ended
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn restart_fight(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    arena: Res<Arena>,
+    mut metrics: ResMut<SkirmishMetrics>,
+    entities: Query<Entity, Or<(With<Team>, With<Shot>)>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let due = metrics
+        .restart
+        .as_mut()
+        .is_some_and(|timer| timer.tick(time.delta()).finished());
+    if !due && !input.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    for entity in &entities {
+        commands.entity(entity).despawn();
+    }
+    spawn_combatants(&mut commands, &arena, &mut meshes, &mut materials);
+    metrics.elapsed = 0f32;
+    metrics.player = Accuracy::default();
+    metrics.agents = Accuracy::default();
+    metrics.restart = None;
+    info!("Fight started");
+}
+
+fn update_skirmish_hud(
+    metrics: Res<SkirmishMetrics>,
+    player: Query<&Health, With<Player>>,
+    agents: Query<(), With<Agent>>,
+    mut hud: Single<&mut Text, With<SkirmishHud>>,
+) {
+    let health = player.iter().next().map_or(0f32, |health| health.current);
+    let status = match (&metrics.restart, metrics.fights.last()) {
+        (Some(timer), Some((_, won))) => format!(
+            "{}! Next fight in {:.0}s",
+            if *won { "Won" } else { "Lost" },
+            timer.remaining_secs().ceil()
+        ),
+        _ => format!("{:.1}s", metrics.elapsed),
+    };
+    hud.0 = format!(
+        "{status}\nHealth {health:.0}, {} agents left\n\
+         Hits: player {:.0}%, agents {:.0}%\n\
+         Average fight {:.1}s over {} fights",
+        agents.iter().count(),
+        metrics.player.rate(),
+        metrics.agents.rate(),
+        metrics.average_duration(),
+        metrics.fights.len()
+    );
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_skirmish(
+    mut commands: Commands,
+    entities: Query<
+        Entity,
+        Or<(
+            With<ArenaTile>,
+            With<Team>,
+            With<Shot>,
+            With<SkirmishHud>,
+            With<Camera2d>,
+        )>,
+    >,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<Arena>();
+    commands.remove_resource::<SkirmishMetrics>();
+}
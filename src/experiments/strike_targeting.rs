@@ -0,0 +1,391 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use bevy::window::PrimaryWindow;
+
+use super::{AppState, Experiment};
+
+// Map constants
+const STRIKE_MAP: &str = "strike-map.png";
+const MAP_SIZE: f32 = 1024f32; // world units; the texture's size in pixels
+const VIEW_MARGIN: f32 = 64f32; // kept around the map at any window shape
+
+// Strike window, as in the story
+const STRIKE_WINDOW_SECS: f32 = 30f32;
+
+// Crosshair constants, in map pixels
+const MIN_STRIKE_RADIUS: f32 = 24f32;
+const MAX_STRIKE_RADIUS: f32 = 320f32;
+const DEFAULT_STRIKE_RADIUS: f32 = 96f32;
+const GRAB_TOLERANCE: f32 = 12f32; // how close to the ring a press starts a resize
+const CLICK_TOLERANCE: f32 = 4f32; // movement still counted as a click, not a drag
+
+// Atmosphere constants; the beam weakens with the extra air it crosses when
+// it drifts off vertical, like sunlight near the horizon
+const PLATFORM_ALTITUDE: f32 = 640f32; // map pixels above the ground
+const ATMOSPHERE_DENSITY: f32 = 2f32; // attenuation per extra atmosphere crossed
+
+// The platform's ground track sweeps across the map during the window
+const NADIR_START: Vec2 = Vec2::new(-320f32, -240f32);
+const NADIR_END: Vec2 = Vec2::new(320f32, 240f32);
+
+// Preview constants
+const PREVIEW_RINGS: usize = 6; // concentric rings shading the impact area
+const IMPACT_FLASH_SECS: f32 = 1.5;
+const CROSSHAIR_COLOR: Color = Color::srgb(1f32, 1f32, 0.85);
+const NADIR_COLOR: Color = Color::srgb(0.4, 0.8, 1f32);
+const NADIR_MARKER_SIZE: f32 = 10f32;
+const WEAK_COLOR: Color = Color::srgb(1f32, 0.2, 0.1);
+const STRONG_COLOR: Color = Color::srgb(0.2, 1f32, 0.3);
+
+/// The static tactical map
+#[derive(Component)]
+pub struct StrikeMap;
+
+/// Countdown and strike readout
+#[derive(Component)]
+pub struct StrikeHud;
+
+/// A fired strike
+struct StrikeResult {
+    center: Vec2,
+    radius: f32,
+    strength: f32,
+    /// Seconds into the window
+    fired_at: f32,
+    flash: Timer,
+}
+
+/// Targeting state for one strike window, with the metrics it's judged by
+#[derive(Resource)]
+pub struct StrikeTargeting {
+    window: Timer,
+    target: Option<Vec2>,
+    radius: f32,
+    /// Where the left button went down, and whether it grabbed the ring
+    press: Option<(Vec2, bool)>,
+    resizes: u32,
+    placements: u32,
+    /// Seconds into the window the target was first placed
+    first_placed_at: Option<f32>,
+    result: Option<StrikeResult>,
+    /// The window ran out with no target placed
+    missed: bool,
+}
+
+impl Default for StrikeTargeting {
+    fn default() -> Self {
+        Self {
+            window: Timer::from_seconds(STRIKE_WINDOW_SECS, TimerMode::Once),
+            target: None,
+            radius: DEFAULT_STRIKE_RADIUS,
+            press: None,
+            resizes: 0,
+            placements: 0,
+            first_placed_at: None,
+            result: None,
+            missed: false,
+        }
+    }
+}
+
+impl StrikeTargeting {
+    fn is_over(&self) -> bool {
+        self.result.is_some() || self.missed
+    }
+
+    /// Ground point directly under the platform
+    fn nadir(&self) -> Vec2 {
+        NADIR_START.lerp(NADIR_END, self.window.fraction())
+    }
+
+    /// Share of the beam's energy reaching `point` through the atmosphere
+    fn strength_at(&self, point: Vec2) -> f32 {
+        let drift = point.distance(self.nadir()) / PLATFORM_ALTITUDE;
+        // Air crossed relative to a vertical shot is the secant of the angle
+        // off vertical
+        let extra_air = (1f32 + drift * drift).sqrt() - 1f32;
+        (-ATMOSPHERE_DENSITY * extra_air).exp()
+    }
+
+    fn fire(&mut self) {
+        let Some(center) = self.target else {
+            return;
+        };
+        let result = StrikeResult {
+            center,
+            radius: self.radius,
+            strength: self.strength_at(center),
+            fired_at: self.window.elapsed_secs(),
+            flash: Timer::from_seconds(IMPACT_FLASH_SECS, TimerMode::Once),
+        };
+        info!(
+            "Strike fired at ({:.0}, {:.0}), radius {:.0}, strength {:.0}%",
+            result.center.x,
+            result.center.y,
+            result.radius,
+            result.strength * 100f32
+        );
+        info!(
+            "Metrics: time to target {:.1}s (first placed at {}), {} placements, {} resizes",
+            result.fired_at,
+            self.first_placed_at
+                .map_or("never".to_owned(), |at| format!("{at:.1}s")),
+            self.placements,
+            self.resizes
+        );
+        self.result = Some(result);
+    }
+}
+
+pub struct StrikeTargetingExperiment;
+
+impl Experiment for StrikeTargetingExperiment {
+    fn name(&self) -> &'static str {
+        "Strike-Radius Targeting"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{f05b}"
+    }
+
+    fn app_state(&self) -> AppState {
+        AppState::StrikeTargeting
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        app.add_systems(OnEnter(AppState::StrikeTargeting), setup_strike_targeting)
+            .add_systems(
+                Update,
+                (
+                    (aim_strike, run_strike_window, restart_strike).chain(),
+                    (draw_strike_preview, update_strike_hud),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::StrikeTargeting)),
+            )
+            .add_systems(OnExit(AppState::StrikeTargeting), cleanup_strike_targeting)
+    }
+}
+
+fn setup_strike_targeting(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // The whole map stays in view whatever the window's shape
+    commands.spawn((
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: MAP_SIZE + 2f32 * VIEW_MARGIN,
+                min_height: MAP_SIZE + 2f32 * VIEW_MARGIN,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+    commands.spawn((
+        StrikeMap,
+        Sprite {
+            image: asset_server.load(STRIKE_MAP),
+            custom_size: Some(Vec2::splat(MAP_SIZE)),
+            ..default()
+        },
+    ));
+    commands.spawn((
+        StrikeHud,
+        Text::default(),
+        TextFont {
+            font_size: 20f32,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12f32),
+            left: Val::Px(12f32),
+            ..default()
+        },
+    ));
+    commands.insert_resource(StrikeTargeting::default());
+
+    info!("Strike targeting loaded! Controls:");
+    info!("  LMB - Place the target");
+    info!("  LMB drag on the ring - Resize the strike");
+    info!("  Space / RMB - Fire");
+    info!("  R - Restart the window");
+    info!("  ESC - Return to launcher");
+}
+
+fn cursor_position(
+    window: &Window,
+    (camera, camera_transform): (&Camera, &GlobalTransform),
+) -> Option<Vec2> {
+    window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+}
+
+/// Clicking places the target; pressing on the ring and dragging resizes it
+fn aim_strike(
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut targeting: ResMut<StrikeTargeting>,
+) {
+    if targeting.is_over() {
+        return;
+    }
+    let Some(cursor) = cursor_position(&window, *camera) else {
+        return;
+    };
+    let cursor = cursor.clamp(Vec2::splat(-MAP_SIZE / 2f32), Vec2::splat(MAP_SIZE / 2f32));
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let on_ring = targeting.target.is_some_and(|target| {
+            (target.distance(cursor) - targeting.radius).abs() <= GRAB_TOLERANCE
+        });
+        targeting.press = Some((cursor, on_ring));
+    }
+    let Some((pressed_at, on_ring)) = targeting.press else {
+        return;
+    };
+    if on_ring && let Some(target) = targeting.target {
+        targeting.radius = target
+            .distance(cursor)
+            .clamp(MIN_STRIKE_RADIUS, MAX_STRIKE_RADIUS);
+    }
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    targeting.press = None;
+    if on_ring {
+        targeting.resizes += 1;
+        info!("Strike radius {:.0}", targeting.radius);
+    } else if pressed_at.distance(cursor) <= CLICK_TOLERANCE {
+        targeting.target = Some(cursor);
+        targeting.placements += 1;
+        if targeting.first_placed_at.is_none() {
+            targeting.first_placed_at = Some(targeting.window.elapsed_secs());
+        }
+    }
+}
+
+/// Counts the window down and fires on command, or when time runs out
+fn run_strike_window(
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut targeting: ResMut<StrikeTargeting>,
+) {
+    if let Some(result) = &mut targeting.result {
+        result.flash.tick(time.delta());
+    }
+    if targeting.is_over() {
+        return;
+    }
+    targeting.window.tick(time.delta());
+    let fire = input.just_pressed(KeyCode::Space) || mouse.just_pressed(MouseButton::Right);
+    if fire || targeting.window.finished() {
+        targeting.fire();
+    }
+    if targeting.window.finished() && targeting.result.is_none() {
+        targeting.missed = true;
+        info!(
+            "Strike window missed: no target placed in {STRIKE_WINDOW_SECS:.0}s ({} resizes)",
+            targeting.resizes
+        );
+    }
+}
+
+/// R starts a fresh window
+fn restart_strike(input: Res<ButtonInput<KeyCode>>, mut targeting: ResMut<StrikeTargeting>) {
+    if input.just_pressed(KeyCode::KeyR) {
+        *targeting = StrikeTargeting::default();
+        info!("Strike window restarted");
+    }
+}
+
+/// Draws the platform's ground track and the crosshair, shading the impact
+/// area by the strength the beam would arrive with; after firing, a flash
+fn draw_strike_preview(
+    mut gizmos: Gizmos,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    targeting: Res<StrikeTargeting>,
+) {
+    if let Some(result) = &targeting.result {
+        let since = result.flash.fraction();
+        let color = Color::WHITE.with_alpha(result.strength * (1f32 - since));
+        gizmos.circle_2d(result.center, result.radius * since.sqrt(), color);
+        gizmos.circle_2d(
+            result.center,
+            result.radius,
+            WEAK_COLOR.mix(&STRONG_COLOR, result.strength),
+        );
+        return;
+    }
+
+    let nadir = targeting.nadir();
+    gizmos.cross_2d(nadir, NADIR_MARKER_SIZE, NADIR_COLOR);
+
+    // Before the target is placed the crosshair follows the cursor
+    let Some(center) = targeting
+        .target
+        .or_else(|| cursor_position(&window, *camera))
+    else {
+        return;
+    };
+    gizmos.line_2d(nadir, center, NADIR_COLOR.with_alpha(0.4));
+
+    let radius = targeting.radius;
+    let strength = targeting.strength_at(center);
+    let color = WEAK_COLOR.mix(&STRONG_COLOR, strength);
+    gizmos.circle_2d(center, radius, color);
+    for ring in 1..PREVIEW_RINGS {
+        let ring_radius = radius * ring as f32 / PREVIEW_RINGS as f32;
+        gizmos.circle_2d(center, ring_radius, color.with_alpha(0.25 * strength));
+    }
+    gizmos.line_2d(
+        center - Vec2::X * radius * 1.2,
+        center + Vec2::X * radius * 1.2,
+        CROSSHAIR_COLOR,
+    );
+    gizmos.line_2d(
+        center - Vec2::Y * radius * 1.2,
+        center + Vec2::Y * radius * 1.2,
+        CROSSHAIR_COLOR,
+    );
+}
+
+fn update_strike_hud(targeting: Res<StrikeTargeting>, mut hud: Single<&mut Text, With<StrikeHud>>) {
+    hud.0 = if let Some(result) = &targeting.result {
+        format!(
+            "Strike fired at {:.1}s: radius {:.0}, strength {:.0}%\n\
+             {} placements, {} resizes. R to retry",
+            result.fired_at,
+            result.radius,
+            result.strength * 100f32,
+            targeting.placements,
+            targeting.resizes
+        )
+    } else if targeting.missed {
+        "Strike window missed. R to retry".to_owned()
+    } else {
+        let strength = targeting
+            .target
+            .map_or("place a target".to_owned(), |target| {
+                format!("strength {:.0}%", targeting.strength_at(target) * 100f32)
+            });
+        format!(
+            "{:.1}s left: radius {:.0}, {strength}",
+            targeting.window.remaining_secs(),
+            targeting.radius
+        )
+    };
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_strike_targeting(
+    mut commands: Commands,
+    entities: Query<Entity, Or<(With<StrikeMap>, With<StrikeHud>, With<Camera2d>)>>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<StrikeTargeting>();
+}
@@ -0,0 +1,206 @@
+use bevy::{input::mouse::MouseWheel, prelude::*, window::PrimaryWindow};
+use bevy_panorbit_camera::PanOrbitCamera;
+
+use super::ShipCamera;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::terrain::{RegenerateTerrain, TerrainSettings};
+use crate::terrain::{
+    TerrainWorld,
+    biome::Biome,
+    edit::{Brush, RefreshTerrainChunks, TerrainEdits},
+};
+
+// Brush constants
+const DEFAULT_RADIUS: f32 = 25f32;
+const MIN_RADIUS: f32 = 5f32;
+const MAX_RADIUS: f32 = 200f32;
+const RADIUS_STEP: f32 = 1.15f32; // per scroll notch
+const DEFAULT_STRENGTH: f32 = 15f32; // world units per second for raise/lower
+const STRENGTH_STEP: f32 = 1.5f32;
+const MAX_RAY_DISTANCE: f32 = 3000f32;
+
+// Edits are saved per seed, relative to the working directory
+#[cfg(not(target_arch = "wasm32"))]
+const EDITS_DIR: &str = "saves";
+
+/// Sculpt and paint state; the camera stops orbiting while editing so the
+/// mouse drives the brush
+#[derive(Resource)]
+pub struct TerrainEditor {
+    active: bool,
+    brush: Brush,
+    radius: f32,
+    strength: f32,
+    /// Terrain point under the cursor, if any
+    target: Option<Vec3>,
+}
+
+impl Default for TerrainEditor {
+    fn default() -> Self {
+        Self {
+            active: false,
+            brush: Brush::Raise,
+            radius: DEFAULT_RADIUS,
+            strength: DEFAULT_STRENGTH,
+            target: None,
+        }
+    }
+}
+
+/// Tab toggles the editor, 1-5 pick raise/lower/smooth/flatten/paint (5 again
+/// cycles the painted biome), scroll resizes, `,`/`.` change strength
+pub fn control_editor(
+    mut editor: ResMut<TerrainEditor>,
+    mut orbit: Single<&mut PanOrbitCamera, With<ShipCamera>>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+) {
+    if input.just_pressed(KeyCode::Tab) {
+        editor.active = !editor.active;
+        orbit.enabled = !editor.active;
+        if editor.active {
+            info!(
+                "Editor on: {:?} brush, radius {:.0}",
+                editor.brush, editor.radius
+            );
+        } else {
+            info!("Editor off");
+        }
+    }
+    if !editor.active {
+        wheel.clear();
+        return;
+    }
+
+    let brush = if input.just_pressed(KeyCode::Digit1) {
+        Some(Brush::Raise)
+    } else if input.just_pressed(KeyCode::Digit2) {
+        Some(Brush::Lower)
+    } else if input.just_pressed(KeyCode::Digit3) {
+        Some(Brush::Smooth)
+    } else if input.just_pressed(KeyCode::Digit4) {
+        Some(Brush::Flatten(0f32))
+    } else if input.just_pressed(KeyCode::Digit5) {
+        Some(Brush::Paint(match editor.brush {
+            Brush::Paint(biome) => {
+                let index = Biome::ALL.iter().position(|other| *other == biome);
+                Biome::ALL[index.map_or(0, |index| (index + 1) % Biome::ALL.len())]
+            }
+            _ => Biome::default(),
+        }))
+    } else {
+        None
+    };
+    if let Some(brush) = brush {
+        editor.brush = brush;
+        info!("Brush: {brush:?}");
+    }
+
+    for event in wheel.read() {
+        editor.radius = if event.y > 0f32 {
+            editor.radius * RADIUS_STEP
+        } else {
+            editor.radius / RADIUS_STEP
+        }
+        .clamp(MIN_RADIUS, MAX_RADIUS);
+    }
+    if input.just_pressed(KeyCode::Period) {
+        editor.strength *= STRENGTH_STEP;
+        info!("Brush strength {:.1}", editor.strength);
+    } else if input.just_pressed(KeyCode::Comma) {
+        editor.strength /= STRENGTH_STEP;
+        info!("Brush strength {:.1}", editor.strength);
+    }
+}
+
+/// Finds the terrain under the cursor and outlines the brush there
+pub fn aim_brush(
+    mut editor: ResMut<TerrainEditor>,
+    terrain: Option<Res<TerrainWorld>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<ShipCamera>>,
+    mut gizmos: Gizmos,
+) {
+    let (camera, camera_transform) = *camera;
+    editor.target = terrain.filter(|_| editor.active).and_then(|terrain| {
+        window
+            .cursor_position()
+            .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
+            .and_then(|ray| terrain.raycast(ray, MAX_RAY_DISTANCE))
+    });
+
+    if let Some(target) = editor.target {
+        gizmos.circle(
+            Isometry3d::new(
+                target + Vec3::Y * 0.5,
+                Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+            ),
+            editor.radius,
+            Color::WHITE,
+        );
+    }
+}
+
+/// Applies the brush under the cursor while the left mouse button is held
+pub fn sculpt_terrain(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut editor: ResMut<TerrainEditor>,
+    mut edits: ResMut<TerrainEdits>,
+    terrain: Option<ResMut<TerrainWorld>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+) {
+    let (Some(mut terrain), Some(target)) = (terrain, editor.target) else {
+        return;
+    };
+    if !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    // Flatten levels out to whatever was under the cursor when the stroke began
+    if mouse.just_pressed(MouseButton::Left)
+        && let Brush::Flatten(_) = editor.brush
+    {
+        editor.brush = Brush::Flatten(target.y);
+    }
+
+    let touched = terrain.apply_brush(
+        &mut edits,
+        editor.brush,
+        target.xz(),
+        editor.radius,
+        editor.strength * time.delta_secs(),
+    );
+    if !touched.is_empty() {
+        commands.queue(RefreshTerrainChunks(touched));
+    }
+}
+
+/// F6 saves the edits for the current seed, F7 loads them and regenerates
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_load_edits(
+    mut commands: Commands,
+    mut edits: ResMut<TerrainEdits>,
+    mut settings: ResMut<TerrainSettings>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    let path = std::path::PathBuf::from(EDITS_DIR).join(format!("terrain_{}.edits", settings.seed));
+    if input.just_pressed(KeyCode::F6) {
+        match edits.save(&path, settings.seed) {
+            Ok(()) => info!("Saved terrain edits to {}", path.display()),
+            Err(err) => error!("Failed to save terrain edits to {}: {err}", path.display()),
+        }
+    } else if input.just_pressed(KeyCode::F7) {
+        match TerrainEdits::load(&path) {
+            Ok((seed, loaded)) => {
+                info!("Loaded terrain edits from {}", path.display());
+                settings.seed = seed;
+                *edits = loaded;
+                commands.queue(RegenerateTerrain);
+            }
+            Err(err) => error!(
+                "Failed to load terrain edits from {}: {err}",
+                path.display()
+            ),
+        }
+    }
+}
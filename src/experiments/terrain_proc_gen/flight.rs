@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+use super::{Ship, ShipCamera};
+use crate::terrain::TerrainWorld;
+
+// Flight model constants, all per second so behavior is frame-rate independent
+const ACCELERATION: f32 = 60f32;
+const BOOST_MULTIPLIER: f32 = 2.5f32; // scales thrust, and so top speed
+const MAX_SPEED: f32 = 60f32; // top speed under thrust, without boost
+const DRAG: f32 = ACCELERATION / MAX_SPEED; // exponential decay rate of horizontal velocity
+
+// Altitude hold constants
+const DEFAULT_HOVER_HEIGHT: f32 = 20f32;
+const MIN_HOVER_HEIGHT: f32 = 5f32;
+const MAX_HOVER_HEIGHT: f32 = 150f32;
+const CLIMB_RATE: f32 = 25f32;
+const ALTITUDE_STIFFNESS: f32 = 6f32; // spring pulling toward the hover height
+const ALTITUDE_DAMPING: f32 = 4f32; // under 2√stiffness, so the spring overshoots a little
+const SHIP_RADIUS: f32 = 2f32;
+
+// Banking constants
+const MAX_BANK: f32 = 0.6f32; // radians
+const BANK_PER_TURN_RATE: f32 = 0.4f32; // radians of bank per radian/second of turn
+const BANK_RESPONSE: f32 = 5f32;
+const HEADING_RESPONSE: f32 = 6f32;
+const MIN_HEADING_SPEED: f32 = 1f32;
+
+/// Flight state for the player's ship
+#[derive(Component)]
+pub struct ShipFlight {
+    pub velocity: Vec3,
+    pub heading: f32,
+    pub bank: f32,
+    pub hover_height: f32,
+}
+
+impl Default for ShipFlight {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            heading: 0f32,
+            bank: 0f32,
+            hover_height: DEFAULT_HOVER_HEIGHT,
+        }
+    }
+}
+
+/// Offset from the hover height and vertical speed after `dt` seconds of the
+/// altitude spring, solved exactly so climbs overshoot and settle the same at
+/// any frame rate; the spring is underdamped
+fn settle_spring(offset: f32, speed: f32, dt: f32) -> (f32, f32) {
+    let decay_rate = ALTITUDE_DAMPING / 2f32;
+    let frequency = (ALTITUDE_STIFFNESS - decay_rate * decay_rate).sqrt();
+    let (sin, cos) = (frequency * dt).sin_cos();
+    let decay = (-decay_rate * dt).exp();
+    (
+        decay * (offset * cos + (speed + decay_rate * offset) / frequency * sin),
+        decay
+            * (speed * cos - (decay_rate * speed + ALTITUDE_STIFFNESS * offset) / frequency * sin),
+    )
+}
+
+/// Camera-relative thrust from WASD, with Shift to boost and R/F to climb/descend
+pub fn fly_ship(
+    time: Res<Time>,
+    inputs: Res<ButtonInput<KeyCode>>,
+    terrain: Option<Res<TerrainWorld>>,
+    camera: Single<&Transform, (With<ShipCamera>, Without<Ship>)>,
+    ship: Single<(&mut Transform, &mut ShipFlight), With<Ship>>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0f32 {
+        return;
+    }
+    let (mut transform, mut flight) = ship.into_inner();
+
+    // Thrust along the camera's heading, flattened onto the ground plane
+    let forward = Vec3::new(camera.forward().x, 0f32, camera.forward().z).normalize_or_zero();
+    let right = Vec3::new(camera.right().x, 0f32, camera.right().z).normalize_or_zero();
+    let mut thrust = Vec3::ZERO;
+    if inputs.pressed(KeyCode::KeyW) {
+        thrust += forward;
+    }
+    if inputs.pressed(KeyCode::KeyS) {
+        thrust -= forward;
+    }
+    if inputs.pressed(KeyCode::KeyA) {
+        thrust -= right;
+    }
+    if inputs.pressed(KeyCode::KeyD) {
+        thrust += right;
+    }
+    let boosting = inputs.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let acceleration = if boosting {
+        ACCELERATION * BOOST_MULTIPLIER
+    } else {
+        ACCELERATION
+    };
+
+    // Thrust against drag, integrated exactly so speed and distance covered
+    // don't depend on the frame rate; velocity eases toward the top speed
+    let top_velocity = thrust.normalize_or_zero() * acceleration / DRAG;
+    let decay = (-DRAG * dt).exp();
+    let start = Vec3::new(flight.velocity.x, 0f32, flight.velocity.z);
+    let horizontal = top_velocity + (start - top_velocity) * decay;
+    let travelled = top_velocity * dt + (start - top_velocity) * (1f32 - decay) / DRAG;
+
+    if inputs.pressed(KeyCode::KeyR) {
+        flight.hover_height += CLIMB_RATE * dt;
+    }
+    if inputs.pressed(KeyCode::KeyF) {
+        flight.hover_height -= CLIMB_RATE * dt;
+    }
+    flight.hover_height = flight
+        .hover_height
+        .clamp(MIN_HOVER_HEIGHT, MAX_HOVER_HEIGHT);
+
+    // Spring toward the hover height above whatever is below: ground or water
+    transform.translation += travelled;
+    let surface = terrain
+        .as_ref()
+        .map(|terrain| terrain.surface_height_at(transform.translation.xz()));
+    let vertical = match surface {
+        Some(surface) => {
+            let target = surface + flight.hover_height;
+            let (offset, vertical) =
+                settle_spring(transform.translation.y - target, flight.velocity.y, dt);
+            transform.translation.y = target + offset;
+            vertical
+        }
+        None => {
+            transform.translation.y += flight.velocity.y * dt;
+            flight.velocity.y
+        }
+    };
+    flight.velocity = Vec3::new(horizontal.x, vertical, horizontal.z);
+
+    // Never sink into the terrain: slide along it instead
+    if let (Some(terrain), Some(surface)) = (terrain.as_ref(), surface) {
+        let floor = surface + SHIP_RADIUS;
+        if transform.translation.y < floor {
+            transform.translation.y = floor;
+            let normal = terrain.normal_at(transform.translation.xz());
+            let into_ground = flight.velocity.dot(normal);
+            if into_ground < 0f32 {
+                flight.velocity -= normal * into_ground;
+            }
+        }
+    }
+
+    // Turn toward the direction of travel and bank into the turn
+    let previous_heading = flight.heading;
+    if horizontal.length() > MIN_HEADING_SPEED {
+        let target = f32::atan2(-horizontal.x, -horizontal.z);
+        let delta = wrap_angle(target - flight.heading);
+        flight.heading =
+            wrap_angle(flight.heading + delta * (1f32 - (-HEADING_RESPONSE * dt).exp()));
+    }
+    let turn_rate = wrap_angle(flight.heading - previous_heading) / dt;
+    let target_bank = (turn_rate * BANK_PER_TURN_RATE).clamp(-MAX_BANK, MAX_BANK);
+    flight.bank += (target_bank - flight.bank) * (1f32 - (-BANK_RESPONSE * dt).exp());
+
+    transform.rotation = Quat::from_rotation_y(flight.heading) * Quat::from_rotation_z(flight.bank);
+}
+
+/// Wraps an angle to [-PI, PI]
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
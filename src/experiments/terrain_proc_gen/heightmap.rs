@@ -0,0 +1,94 @@
+use std::{path::Path, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::terrain::{
+    HeightSource, RegenerateTerrain, TERRAIN_HEIGHT, TerrainSettings, import::HeightMap,
+};
+
+// Set this to a heightmap or DEM path to use it instead of noise on entry
+const HEIGHTMAP_ENV: &str = "RESURGENCE_HEIGHTMAP";
+
+// Imported heights map [0, 1] onto the same range as the noise by default
+const DEFAULT_SCALE: f32 = 2f32 * TERRAIN_HEIGHT;
+const DEFAULT_OFFSET: f32 = -TERRAIN_HEIGHT;
+const SCALE_STEP: f32 = 1.25f32;
+const OFFSET_STEP: f32 = 5f32;
+
+/// Swaps the height source to the file named by `RESURGENCE_HEIGHTMAP`, if set
+pub fn load_heightmap_from_env(mut settings: ResMut<TerrainSettings>) {
+    if let Ok(path) = std::env::var(HEIGHTMAP_ENV) {
+        use_heightmap(&mut settings, Path::new(&path));
+    }
+}
+
+/// Dropping a heightmap or DEM onto the window regenerates the terrain from it
+pub fn drop_heightmap(
+    mut commands: Commands,
+    mut settings: ResMut<TerrainSettings>,
+    mut events: EventReader<FileDragAndDrop>,
+) {
+    for event in events.read() {
+        if let FileDragAndDrop::DroppedFile { path_buf, .. } = event
+            && use_heightmap(&mut settings, path_buf)
+        {
+            commands.queue(RegenerateTerrain);
+        }
+    }
+}
+
+/// `-`/`=` scale imported heights, `[`/`]` shift them, N goes back to noise
+pub fn tune_heightmap(
+    mut commands: Commands,
+    mut settings: ResMut<TerrainSettings>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    if input.just_pressed(KeyCode::KeyN) {
+        if matches!(settings.source, HeightSource::Noise) {
+            return;
+        }
+        settings.source = HeightSource::Noise;
+        info!("Regenerating terrain from noise");
+        commands.queue(RegenerateTerrain);
+        return;
+    }
+
+    let HeightSource::Imported { scale, offset, .. } = &mut settings.source else {
+        return;
+    };
+    if input.just_pressed(KeyCode::Equal) {
+        *scale *= SCALE_STEP;
+    } else if input.just_pressed(KeyCode::Minus) {
+        *scale /= SCALE_STEP;
+    } else if input.just_pressed(KeyCode::BracketRight) {
+        *offset += OFFSET_STEP;
+    } else if input.just_pressed(KeyCode::BracketLeft) {
+        *offset -= OFFSET_STEP;
+    } else {
+        return;
+    }
+    info!("Regenerating imported terrain with scale {scale:.1} and offset {offset:.1}");
+    commands.queue(RegenerateTerrain);
+}
+
+/// Loads `path` as the height source, keeping the current scale and offset when
+/// already importing. Returns whether the source changed.
+fn use_heightmap(settings: &mut TerrainSettings, path: &Path) -> bool {
+    let heights = match HeightMap::load(path) {
+        Ok(heights) => Arc::new(heights),
+        Err(err) => {
+            error!("Failed to load heightmap {}: {err}", path.display());
+            return false;
+        }
+    };
+    let (scale, offset) = match settings.source {
+        HeightSource::Imported { scale, offset, .. } => (scale, offset),
+        HeightSource::Noise => (DEFAULT_SCALE, DEFAULT_OFFSET),
+    };
+    settings.source = HeightSource::Imported {
+        heights,
+        scale,
+        offset,
+    };
+    true
+}
@@ -0,0 +1,361 @@
+use bevy::{
+    color::palettes::css::BLUE,
+    pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin},
+    prelude::*,
+};
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
+
+mod editor;
+mod flight;
+#[cfg(not(target_arch = "wasm32"))]
+mod heightmap;
+mod time_of_day;
+
+use self::{
+    editor::{TerrainEditor, aim_brush, control_editor, sculpt_terrain},
+    flight::{ShipFlight, fly_ship},
+    time_of_day::{
+        TimeOfDay, atmosphere_bundle, control_time_of_day, sun_bundle, update_sky, update_sun,
+    },
+};
+use super::{AppState, Experiment};
+use crate::fog_of_war::{FogOfWar, FogPlane, FogRevealer};
+use crate::minimap::{Minimap, MinimapMarker, SpawnMinimap};
+use crate::terrain::{
+    GenerateTerrain, RegenerateTerrain, TerrainMesh, TerrainSettings, TerrainStore, TerrainWorld,
+    biome::Biome, edit::TerrainEdits, poi::PointOfInterest,
+};
+
+// How far past a point of interest's footprint the ship still counts as over it
+const POI_REPORT_DISTANCE: f32 = 10f32;
+
+// Fog of war constants
+const FOG_CELL_SIZE: f32 = 10f32;
+const SHIP_SIGHT_RADIUS: f32 = 150f32; // operational radius revealed around the ship
+
+#[derive(Component)]
+pub struct Ship;
+
+#[derive(Component)]
+pub struct ShipCamera;
+
+pub struct TerrainProcGenExperiment;
+
+impl Experiment for TerrainProcGenExperiment {
+    fn name(&self) -> &'static str {
+        "Procedural Terrain Generation"
+    }
+
+    fn icon(&self) -> &'static str {
+        "\u{e2a6}" // Font Awesome mountain icon
+    }
+
+    fn app_state(&self) -> AppState {
+        AppState::TerrainProcGen
+    }
+
+    fn app_setup<'a>(&self, app: &'a mut App) -> &'a mut App {
+        app.add_plugins(WireframePlugin::default())
+            .insert_resource(WireframeConfig {
+                global: false, // only draw wireframes where you add `Wireframe`
+                default_color: Color::WHITE,
+            })
+            .add_plugins(PanOrbitCameraPlugin)
+            .init_resource::<TerrainEditor>()
+            .init_resource::<TimeOfDay>()
+            .add_systems(
+                OnEnter(AppState::TerrainProcGen),
+                (
+                    #[cfg(not(target_arch = "wasm32"))]
+                    heightmap::load_heightmap_from_env,
+                    setup_terrain_experiment,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_wireframe,
+                    toggle_fog_of_war,
+                    fly_ship,
+                    control_ship_camera.after(fly_ship),
+                    report_ship_biome,
+                    report_points_of_interest,
+                    (control_time_of_day, update_sun, update_sky).chain(),
+                    tune_erosion,
+                    (control_editor, aim_brush, sculpt_terrain).chain(),
+                    #[cfg(not(target_arch = "wasm32"))]
+                    editor::save_load_edits,
+                    #[cfg(not(target_arch = "wasm32"))]
+                    export_terrain,
+                    #[cfg(not(target_arch = "wasm32"))]
+                    heightmap::drop_heightmap,
+                    #[cfg(not(target_arch = "wasm32"))]
+                    heightmap::tune_heightmap,
+                )
+                    .run_if(in_state(AppState::TerrainProcGen)),
+            )
+            .add_systems(OnExit(AppState::TerrainProcGen), cleanup_terrain_experiment)
+    }
+}
+
+fn setup_terrain_experiment(
+    mut commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    settings: Res<TerrainSettings>,
+) {
+    info!("Setting up terrain generation experiment...");
+
+    // Setup light, ship, camera, fog, and terrain; the fog goes first so the
+    // terrain materials pick up its texture
+    setup_light(&mut commands);
+    setup_ship(&mut commands, meshes, materials);
+    let camera = setup_camera(&mut commands);
+    setup_fog_of_war(&mut commands, &mut images, &settings);
+    setup_terrain(&mut commands);
+    commands.queue(SpawnMinimap {
+        area: settings.world_rect(),
+        ui_camera: camera,
+    });
+
+    info!("Terrain experiment loaded! Controls:");
+    info!("  WASD - Fly ship (relative to camera)");
+    info!("  Shift - Boost");
+    info!("  R/F - Climb/descend");
+    info!("  Space - Toggle wireframe");
+    info!("  V - Toggle fog of war");
+    info!("  M - Toggle tactical map");
+    info!("  E - Toggle erosion");
+    info!("  PageUp/PageDown - More/fewer erosion droplets");
+    #[cfg(not(target_arch = "wasm32"))]
+    info!("  F5 - Export heightmap, color map, and glTF");
+    #[cfg(not(target_arch = "wasm32"))]
+    info!(
+        "  Drop a heightmap/DEM on the window - Use it as terrain (-/= scale, [/] offset, N noise)"
+    );
+    info!("  Tab - Toggle sculpt/paint editor");
+    info!("    LMB - Apply brush, Scroll - Brush size, ,/. - Brush strength");
+    info!("    1-5 - Raise/lower/smooth/flatten/paint (5 again cycles biome)");
+    #[cfg(not(target_arch = "wasm32"))]
+    info!("    F6/F7 - Save/load edits for this seed");
+    info!("  T/Y - Speed up/slow down time of day, P - Pause time");
+    info!("  Mouse - Orbit camera");
+    info!("  ESC - Return to launcher");
+}
+
+fn setup_ship(
+    commands: &mut Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let sphere_mesh = meshes.add(Sphere::new(2f32).mesh());
+    let wing_mesh = meshes.add(Cuboid::new(7f32, 0.3f32, 1.5f32));
+    let blue_material = materials.add(StandardMaterial {
+        base_color: BLUE.into(),
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(sphere_mesh),
+        MeshMaterial3d(blue_material.clone()),
+        Transform::from_xyz(0f32, 20f32, 0f32),
+        Ship,
+        ShipFlight::default(),
+        FogRevealer {
+            radius: SHIP_SIGHT_RADIUS,
+        },
+        MinimapMarker {
+            color: Color::WHITE,
+        },
+        // Wings make heading and banking readable
+        children![(
+            Mesh3d(wing_mesh),
+            MeshMaterial3d(blue_material),
+            Transform::from_xyz(0f32, 0f32, 0.5f32),
+        )],
+    ));
+}
+
+fn setup_camera(commands: &mut Commands) -> Entity {
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 20.0, 75.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+            GlobalTransform::default(),
+            PanOrbitCamera::default(),
+            atmosphere_bundle(),
+            ShipCamera,
+        ))
+        .id()
+}
+
+fn setup_light(commands: &mut Commands) {
+    // Rotated into place every frame by the time of day systems
+    commands.spawn(sun_bundle());
+}
+
+fn setup_fog_of_war(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    settings: &TerrainSettings,
+) {
+    let rect = settings.world_rect();
+    commands.insert_resource(FogOfWar::new(
+        FogPlane::Xz,
+        rect.min,
+        rect.size(),
+        FOG_CELL_SIZE,
+        images,
+    ));
+}
+
+fn setup_terrain(commands: &mut Commands) {
+    // Generate the 3x3 grid of terrain chunks
+    commands.queue(GenerateTerrain);
+}
+
+fn toggle_wireframe(
+    mut commands: Commands,
+    landscapes_wireframe: Query<Entity, (With<TerrainMesh>, With<Wireframe>)>,
+    landscapes: Query<Entity, (With<TerrainMesh>, Without<Wireframe>)>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    if input.just_pressed(KeyCode::Space) {
+        for terrain in &landscapes {
+            commands.entity(terrain).insert(Wireframe);
+        }
+        for terrain in &landscapes_wireframe {
+            commands.entity(terrain).remove::<Wireframe>();
+        }
+    }
+}
+
+fn toggle_fog_of_war(mut fog: ResMut<FogOfWar>, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::KeyV) {
+        let enabled = !fog.enabled();
+        fog.set_enabled(enabled);
+        info!("Fog of war {}", if enabled { "on" } else { "off" });
+    }
+}
+
+fn tune_erosion(
+    mut commands: Commands,
+    mut settings: ResMut<TerrainSettings>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    let defaults = TerrainSettings::default().erosion;
+    if input.just_pressed(KeyCode::KeyE) {
+        settings.erosion = match settings.erosion {
+            Some(_) => None,
+            None => defaults,
+        };
+    } else if input.just_pressed(KeyCode::PageUp) {
+        let erosion = settings
+            .erosion
+            .get_or_insert(defaults.expect("default erosion"));
+        erosion.droplets = erosion.droplets.saturating_mul(2);
+    } else if input.just_pressed(KeyCode::PageDown) {
+        let erosion = settings
+            .erosion
+            .get_or_insert(defaults.expect("default erosion"));
+        erosion.droplets /= 2;
+    } else {
+        return;
+    }
+
+    match settings.erosion {
+        Some(erosion) => info!(
+            "Regenerating terrain with {} erosion droplets",
+            erosion.droplets
+        ),
+        None => info!("Regenerating terrain without erosion"),
+    }
+    commands.queue(RegenerateTerrain);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn export_terrain(mut commands: Commands, input: Res<ButtonInput<KeyCode>>) {
+    if input.just_pressed(KeyCode::F5) {
+        commands.queue(crate::terrain::export::ExportTerrain);
+    }
+}
+
+fn control_ship_camera(
+    ship: Single<&Transform, (With<Ship>, Without<ShipCamera>)>,
+    mut orbit: Single<&mut PanOrbitCamera, With<ShipCamera>>,
+) {
+    orbit.target_focus = Vec3::new(ship.translation.x, ship.translation.y, ship.translation.z)
+}
+
+fn report_ship_biome(
+    ship: Single<&Transform, With<Ship>>,
+    terrain: Option<Res<TerrainWorld>>,
+    mut current: Local<Option<(Biome, bool)>>,
+) {
+    let Some(terrain) = terrain else {
+        return;
+    };
+    let position = ship.translation.xz();
+    let over_water = terrain.water_depth_at(position) > 0f32;
+    let reading = terrain.biome_at(position).map(|biome| (biome, over_water));
+    if reading != *current {
+        if let Some((biome, over_water)) = reading {
+            let moisture = terrain.moisture_at(position);
+            if over_water {
+                info!("Ship over water in {biome:?} (moisture {moisture:.2})");
+            } else {
+                info!("Ship entered {biome:?} (moisture {moisture:.2})");
+            }
+        }
+        *current = reading;
+    }
+}
+
+fn report_points_of_interest(
+    ship: Single<&Transform, With<Ship>>,
+    points: Query<(Entity, &PointOfInterest, &GlobalTransform)>,
+    mut current: Local<Option<Entity>>,
+) {
+    let position = ship.translation.xz();
+    let nearby = points.iter().find(|(_, point, transform)| {
+        transform.translation().xz().distance(position) < point.kind.radius() + POI_REPORT_DISTANCE
+    });
+    let entity = nearby.map(|(entity, _, _)| entity);
+    if entity != *current {
+        if let Some((_, point, _)) = nearby {
+            info!("Ship over {:?}", point.kind);
+        }
+        *current = entity;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_terrain_experiment(
+    mut commands: Commands,
+    mut terrain_store: ResMut<TerrainStore>,
+    mut edits: ResMut<TerrainEdits>,
+    mut editor: ResMut<TerrainEditor>,
+    mut clock: ResMut<TimeOfDay>,
+    entities: Query<
+        Entity,
+        Or<(
+            With<TerrainMesh>,
+            With<Ship>,
+            With<Camera3d>,
+            With<DirectionalLight>,
+            With<Minimap>,
+        )>,
+    >,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    terrain_store.0.clear();
+    edits.clear();
+    *editor = TerrainEditor::default();
+    *clock = TimeOfDay::default();
+    commands.remove_resource::<TerrainWorld>();
+    commands.remove_resource::<FogOfWar>();
+}
@@ -0,0 +1,181 @@
+use bevy::{
+    pbr::{CascadeShadowConfigBuilder, DistanceFog, FogFalloff},
+    prelude::*,
+    render::camera::ClearColorConfig,
+};
+
+use super::ShipCamera;
+
+// Clock constants, in in-game hours
+const START_HOUR: f32 = 9f32;
+const DEFAULT_TIME_SCALE: f32 = 0.1f32; // in-game hours per real second
+const MAX_TIME_SCALE: f32 = 12.8f32;
+const SUNRISE_HOUR: f32 = 6f32;
+const MAX_SUN_ELEVATION: f32 = 1.2f32; // radians, a little under 70°
+
+// Light levels; the moon takes over below the horizon so nights stay readable
+const SUN_ILLUMINANCE: f32 = 50_000f32;
+const MOON_ILLUMINANCE: f32 = light_consts::lux::CLEAR_SUNRISE;
+const DAY_AMBIENT: f32 = 400f32;
+const NIGHT_AMBIENT: f32 = 40f32;
+
+// The atmosphere is denser than projected: distant ridges wash out well inside
+// the 3x3 chunk grid
+const FOG_VISIBILITY: f32 = 1400f32;
+
+// Shadow cascades spread over roughly one and a half chunks, with a tight first
+// cascade so nearby shadows stay crisp
+const SHADOW_MAX_DISTANCE: f32 = 1500f32;
+const SHADOW_FIRST_CASCADE: f32 = 60f32;
+
+/// In-game clock driving the sun, sky, and fog
+#[derive(Resource)]
+pub struct TimeOfDay {
+    /// Hour of the day in [0, 24)
+    hour: f32,
+    /// In-game hours per real second; zero pauses the clock
+    scale: f32,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self {
+            hour: START_HOUR,
+            scale: DEFAULT_TIME_SCALE,
+        }
+    }
+}
+
+/// The directional light that plays both sun and moon
+#[derive(Component)]
+pub struct Sun;
+
+/// Sun light with cascades tuned for the large terrain chunks
+pub fn sun_bundle() -> impl Bundle {
+    (
+        DirectionalLight {
+            shadows_enabled: true,
+            illuminance: SUN_ILLUMINANCE,
+            ..default()
+        },
+        CascadeShadowConfigBuilder {
+            num_cascades: 4,
+            first_cascade_far_bound: SHADOW_FIRST_CASCADE,
+            maximum_distance: SHADOW_MAX_DISTANCE,
+            ..default()
+        }
+        .build(),
+        Transform::default(),
+        Sun,
+    )
+}
+
+/// Sky, ambient light, and fog for the ship camera; colors are filled in by
+/// [`update_sky`]
+pub fn atmosphere_bundle() -> impl Bundle {
+    (
+        AmbientLight {
+            brightness: DAY_AMBIENT,
+            ..default()
+        },
+        DistanceFog {
+            falloff: FogFalloff::from_visibility(FOG_VISIBILITY),
+            ..default()
+        },
+    )
+}
+
+/// T speeds the clock up, Y slows it down, P pauses and resumes
+pub fn control_time_of_day(
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut clock: ResMut<TimeOfDay>,
+    mut paused_scale: Local<Option<f32>>,
+) {
+    if input.just_pressed(KeyCode::KeyP) {
+        match paused_scale.take() {
+            Some(scale) => clock.scale = scale,
+            None => *paused_scale = Some(std::mem::take(&mut clock.scale)),
+        }
+        info!(
+            "Time of day {}",
+            if clock.scale > 0f32 {
+                "resumed"
+            } else {
+                "paused"
+            }
+        );
+    } else if input.just_pressed(KeyCode::KeyT) && paused_scale.is_none() {
+        clock.scale = (clock.scale * 2f32).min(MAX_TIME_SCALE);
+        info!("Time scale {:.2} hours per second", clock.scale);
+    } else if input.just_pressed(KeyCode::KeyY) && paused_scale.is_none() {
+        clock.scale /= 2f32;
+        info!("Time scale {:.2} hours per second", clock.scale);
+    }
+
+    clock.hour = (clock.hour + clock.scale * time.delta_secs()).rem_euclid(24f32);
+}
+
+/// Swings the sun across the sky and swaps to moonlight once it sets
+pub fn update_sun(
+    clock: Res<TimeOfDay>,
+    sun: Single<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+) {
+    let (mut light, mut transform) = sun.into_inner();
+    let daylight = daylight(clock.hour);
+    let direction = sun_direction(clock.hour);
+
+    if direction.y > 0f32 {
+        // Low sun is warm, high sun is white
+        let warmth = 1f32 - daylight.powf(0.5);
+        light.color = Color::srgb(1f32, 1f32 - 0.35 * warmth, 1f32 - 0.6 * warmth);
+        light.illuminance = SUN_ILLUMINANCE * daylight;
+        *transform = Transform::from_translation(direction).looking_at(Vec3::ZERO, Vec3::Y);
+    } else {
+        light.color = Color::srgb(0.6, 0.7, 1f32);
+        light.illuminance = MOON_ILLUMINANCE * (-direction.y).clamp(0.2, 1f32);
+        *transform = Transform::from_translation(-direction).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+/// Keeps the clear color, ambient light, and fog in step with the sun
+pub fn update_sky(
+    clock: Res<TimeOfDay>,
+    camera: Single<(&mut Camera, &mut AmbientLight, &mut DistanceFog), With<ShipCamera>>,
+) {
+    let (mut camera, mut ambient, mut fog) = camera.into_inner();
+    let daylight = daylight(clock.hour);
+    let sky = sky_color(daylight);
+
+    camera.clear_color = ClearColorConfig::Custom(sky);
+    ambient.color = sky;
+    ambient.brightness = NIGHT_AMBIENT + (DAY_AMBIENT - NIGHT_AMBIENT) * daylight;
+    // Haze is a little paler than the sky so silhouettes fade rather than vanish
+    fog.color = sky.mix(&Color::srgb(0.6, 0.6, 0.62), 0.3 * daylight);
+    fog.directional_light_color = Color::srgba(1f32, 0.8, 0.5, 0.5 * daylight);
+}
+
+/// Unit vector toward the sun: rises in the east (+X) at sunrise, peaks at
+/// noon, sets in the west
+fn sun_direction(hour: f32) -> Vec3 {
+    let angle = (hour - SUNRISE_HOUR) / 24f32 * std::f32::consts::TAU;
+    let elevation = angle.sin() * MAX_SUN_ELEVATION;
+    let azimuth = angle.cos();
+    Vec3::new(azimuth * elevation.cos(), elevation.sin(), -0.3).normalize()
+}
+
+/// 0 at night, 1 at noon, easing through dawn and dusk
+fn daylight(hour: f32) -> f32 {
+    sun_direction(hour).y.max(0f32).powf(0.6)
+}
+
+fn sky_color(daylight: f32) -> Color {
+    let night = Color::srgb(0.02, 0.03, 0.07);
+    let dusk = Color::srgb(0.75, 0.45, 0.3);
+    let day = Color::srgb(0.5, 0.65, 0.8);
+    if daylight < 0.3 {
+        night.mix(&dusk, daylight / 0.3)
+    } else {
+        dusk.mix(&day, ((daylight - 0.3) / 0.7).min(1f32))
+    }
+}
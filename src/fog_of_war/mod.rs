@@ -0,0 +1,344 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::VisibilitySystems,
+    },
+    transform::TransformSystem,
+};
+use bevy_ecs_tilemap::prelude::*;
+
+// Fog texture values; the terrain shader interpolates between them
+const UNEXPLORED_TEXEL: u8 = 0;
+const EXPLORED_TEXEL: u8 = 128;
+const VISIBLE_TEXEL: u8 = 255;
+
+// Tilemap overlay tint alpha per fog state
+const UNEXPLORED_ALPHA: f32 = 1f32;
+const EXPLORED_ALPHA: f32 = 0.55;
+
+/// Which world plane the fog grid covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FogPlane {
+    /// Ground plane of 3D scenes
+    Xz,
+    /// Screen plane of 2D scenes
+    Xy,
+}
+
+impl FogPlane {
+    fn project(&self, point: Vec3) -> Vec2 {
+        match self {
+            FogPlane::Xz => point.xz(),
+            FogPlane::Xy => point.xy(),
+        }
+    }
+}
+
+/// What a fog cell currently shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FogState {
+    /// Never seen; drawn dark and hides everything
+    #[default]
+    Unexplored,
+    /// Seen before but not in vision now; terrain shows, units don't
+    Explored,
+    /// Inside a revealer's radius right now
+    Visible,
+}
+
+impl FogState {
+    fn texel(&self) -> u8 {
+        match self {
+            FogState::Unexplored => UNEXPLORED_TEXEL,
+            FogState::Explored => EXPLORED_TEXEL,
+            FogState::Visible => VISIBLE_TEXEL,
+        }
+    }
+}
+
+/// Explored/visible grid over a rectangle of the world, mirrored into a
+/// texture the terrain material samples.
+///
+/// Experiments insert this on enter and remove it on exit; the fog systems
+/// only run while it exists.
+#[derive(Resource)]
+pub struct FogOfWar {
+    plane: FogPlane,
+    origin: Vec2,
+    cell_size: f32,
+    size: UVec2,
+    cells: Vec<FogState>,
+    /// Cells made visible by the last reveal, so the next one only has to
+    /// demote those
+    visible: Vec<usize>,
+    /// When false everything counts as visible and the overlay is cleared
+    enabled: bool,
+    /// Bumped whenever any cell changes, so consumers can skip unchanged frames
+    revision: u32,
+    texture: Handle<Image>,
+}
+
+impl FogOfWar {
+    /// Covers `extent` world units from `origin` on `plane` with square cells
+    pub fn new(
+        plane: FogPlane,
+        origin: Vec2,
+        extent: Vec2,
+        cell_size: f32,
+        images: &mut Assets<Image>,
+    ) -> Self {
+        let size = (extent / cell_size).ceil().as_uvec2().max(UVec2::ONE);
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[UNEXPLORED_TEXEL],
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::default(),
+        );
+        // Filtering softens the cell edges into a gradient
+        image.sampler = ImageSampler::linear();
+
+        Self {
+            plane,
+            origin,
+            cell_size,
+            size,
+            cells: vec![FogState::Unexplored; (size.x * size.y) as usize],
+            visible: Vec::new(),
+            enabled: true,
+            revision: 1,
+            texture: images.add(image),
+        }
+    }
+
+    /// Fog texture, one texel per cell
+    pub fn texture(&self) -> Handle<Image> {
+        self.texture.clone()
+    }
+
+    /// Covered rectangle as (origin x, origin y, width, height) on the fog plane
+    pub fn bounds(&self) -> Vec4 {
+        let extent = self.size.as_vec2() * self.cell_size;
+        Vec4::new(self.origin.x, self.origin.y, extent.x, extent.y)
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    /// Fog at a world position; points outside the grid are never revealed
+    pub fn state_at(&self, point: Vec3) -> FogState {
+        if !self.enabled {
+            return FogState::Visible;
+        }
+        self.cell_index(self.plane.project(point))
+            .map_or(FogState::Unexplored, |index| self.cells[index])
+    }
+
+    /// Whether a world position is inside a revealer's radius
+    pub fn is_visible(&self, point: Vec3) -> bool {
+        self.state_at(point) == FogState::Visible
+    }
+
+    fn cell_index(&self, point: Vec2) -> Option<usize> {
+        let cell = ((point - self.origin) / self.cell_size).floor();
+        if cell.x < 0f32
+            || cell.y < 0f32
+            || cell.x >= self.size.x as f32
+            || cell.y >= self.size.y as f32
+        {
+            return None;
+        }
+        Some(cell.y as usize * self.size.x as usize + cell.x as usize)
+    }
+
+    /// Demotes last frame's vision to explored and reveals circles around
+    /// each `(center, radius)`
+    fn reveal(&mut self, revealers: impl IntoIterator<Item = (Vec3, f32)>) {
+        let previous = std::mem::take(&mut self.visible);
+        for &index in &previous {
+            self.cells[index] = FogState::Explored;
+        }
+
+        for (center, radius) in revealers {
+            let center = self.plane.project(center);
+            let min = ((center - radius - self.origin) / self.cell_size)
+                .floor()
+                .max(Vec2::ZERO)
+                .as_uvec2();
+            let max = ((center + radius - self.origin) / self.cell_size)
+                .ceil()
+                .as_uvec2()
+                .min(self.size);
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    // Test the cell center so the revealed disc is symmetric
+                    let cell_center =
+                        self.origin + (Vec2::new(x as f32, y as f32) + 0.5) * self.cell_size;
+                    if cell_center.distance_squared(center) > radius * radius {
+                        continue;
+                    }
+                    let index = (y * self.size.x + x) as usize;
+                    if self.cells[index] != FogState::Visible {
+                        self.cells[index] = FogState::Visible;
+                        self.visible.push(index);
+                    }
+                }
+            }
+        }
+
+        self.visible.sort_unstable();
+        if self.visible != previous {
+            self.revision = self.revision.wrapping_add(1);
+        }
+    }
+}
+
+/// Clears fog within `radius` world units of the entity
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FogRevealer {
+    pub radius: f32,
+}
+
+/// Hides the entity while the fog at its position is thicker than it allows
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HiddenByFog {
+    /// Shown once the area has been explored; for static features
+    #[default]
+    Explored,
+    /// Shown only while in vision; for things that move or change
+    Visible,
+}
+
+/// Marks a tilemap as the fog layer drawn over another map; its tiles are
+/// tinted from the fog at each tile's center
+#[derive(Component)]
+pub struct FogTilemapOverlay;
+
+pub struct FogOfWarPlugin;
+
+impl Plugin for FogOfWarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                reveal_fog,
+                (hide_fogged_entities, update_fog_overlays, write_fog_texture),
+            )
+                .chain()
+                .after(TransformSystem::TransformPropagate)
+                .before(VisibilitySystems::VisibilityPropagate)
+                .run_if(resource_exists::<FogOfWar>),
+        );
+    }
+}
+
+fn reveal_fog(mut fog: ResMut<FogOfWar>, revealers: Query<(&GlobalTransform, &FogRevealer)>) {
+    fog.reveal(
+        revealers
+            .iter()
+            .map(|(transform, revealer)| (transform.translation(), revealer.radius)),
+    );
+}
+
+/// Re-checks every entity when the fog changes, and otherwise only the ones
+/// that moved or just became fog-hidden
+fn hide_fogged_entities(
+    fog: Res<FogOfWar>,
+    mut entities: Query<(Ref<GlobalTransform>, Ref<HiddenByFog>, &mut Visibility)>,
+    mut seen: Local<u32>,
+) {
+    let fog_changed = *seen != fog.revision || fog.is_added();
+    *seen = fog.revision;
+
+    for (transform, hidden_by, mut visibility) in &mut entities {
+        if !fog_changed && !transform.is_changed() && !hidden_by.is_added() {
+            continue;
+        }
+        let position = transform.translation();
+        let shown = match *hidden_by {
+            HiddenByFog::Explored => fog.state_at(position) != FogState::Unexplored,
+            HiddenByFog::Visible => fog.is_visible(position),
+        };
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_fog_overlays(
+    fog: Res<FogOfWar>,
+    overlays: Query<
+        (
+            &TileStorage,
+            &TilemapSize,
+            &TilemapGridSize,
+            &TilemapTileSize,
+            &TilemapType,
+            &TilemapAnchor,
+            &GlobalTransform,
+        ),
+        With<FogTilemapOverlay>,
+    >,
+    added: Query<(), Added<FogTilemapOverlay>>,
+    mut tiles: Query<&mut TileColor>,
+    mut seen: Local<u32>,
+) {
+    if *seen == fog.revision && !fog.is_added() && added.is_empty() {
+        return;
+    }
+    *seen = fog.revision;
+
+    for (storage, map_size, grid_size, tile_size, map_type, anchor, transform) in &overlays {
+        for x in 0..map_size.x {
+            for y in 0..map_size.y {
+                let tile_pos = TilePos { x, y };
+                let Some(mut color) = storage
+                    .get(&tile_pos)
+                    .and_then(|tile| tiles.get_mut(tile).ok())
+                else {
+                    continue;
+                };
+                let center =
+                    tile_pos.center_in_world(map_size, grid_size, tile_size, map_type, anchor);
+                let alpha = match fog.state_at(transform.transform_point(center.extend(0f32))) {
+                    FogState::Unexplored => UNEXPLORED_ALPHA,
+                    FogState::Explored => EXPLORED_ALPHA,
+                    FogState::Visible => 0f32,
+                };
+                *color = TileColor(Color::BLACK.with_alpha(alpha));
+            }
+        }
+    }
+}
+
+fn write_fog_texture(fog: Res<FogOfWar>, mut images: ResMut<Assets<Image>>, mut seen: Local<u32>) {
+    if *seen == fog.revision && !fog.is_added() {
+        return;
+    }
+    *seen = fog.revision;
+
+    let Some(image) = images.get_mut(&fog.texture) else {
+        return;
+    };
+    image.data = Some(if fog.enabled {
+        fog.cells.iter().map(FogState::texel).collect()
+    } else {
+        vec![VISIBLE_TEXEL; fog.cells.len()]
+    });
+}
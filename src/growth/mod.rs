@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+// Spacing constants
+pub const PATCH_SPACING: f32 = 2f32; // between neighbouring patches, in world units
+
+// Update timing constants
+pub const GROWTH_UPDATE_FREQUENCY: f32 = 0.2f32; // 5Hz
+
+// Game balance constants
+pub const DEFAULT_GROWTH_RATE: f32 = 0.5f32; // How fast spots mature (0-1 per second)
+pub const DEFAULT_RADIUS_EXPANSION_RATE: f32 = 1f32; // How fast growth spreads (units per second)
+pub const DEFAULT_INITIAL_GROWTH_AGE: f32 = 0f32; // Starting age for new growth spots
+const DEFAULT_INITIAL_RADIUS: f32 = 0f32; // Starting radius for new growth origins
+pub const DEFAULT_MAX_GROWTH_AGE: f32 = 1f32; // Maximum age (fully mature)
+pub const MAX_GROWTH_RADIUS: f32 = 120f32; // Maximum radius to prevent infinite expansion
+pub const FULL_HEALTH: f32 = 1f32; // Health of new colonies and patches
+
+// Visual aging constants (red to black interpolation)
+const GROWTH_VISUAL_AGE_THRESHOLD: f32 = 1f32; // Age when visual updates stop
+
+/// Origin of a spreading growth colony
+#[derive(Component)]
+pub struct CrimsonColony {
+    pub radius: f32,
+    pub expansion_complete: bool,
+    pub expansion_rate: f32,
+    pub maturation_rate: f32,
+    pub max_radius: f32,
+    pub health: f32, // Destroyed at 0
+}
+
+impl Default for CrimsonColony {
+    fn default() -> Self {
+        Self {
+            radius: DEFAULT_INITIAL_RADIUS,
+            expansion_complete: false,
+            expansion_rate: DEFAULT_RADIUS_EXPANSION_RATE,
+            maturation_rate: DEFAULT_GROWTH_RATE,
+            max_radius: MAX_GROWTH_RADIUS,
+            health: FULL_HEALTH,
+        }
+    }
+}
+
+/// One patch of ground a colony has spread onto
+#[derive(Component)]
+pub struct GrowthPatch {
+    pub _colony_entity: Entity,
+    pub age: f32,             // 0.0 to 1.0 (fully mature)
+    pub maturation_rate: f32, // How fast it ages per second
+    pub health: f32,          // Destroyed at 0
+}
+
+/// Growth color for an age: red when new, through brown, to black when mature
+pub fn growth_color(age: f32) -> Color {
+    // Red -> Brown -> Dark Brown -> Black
+    let age_normalized = (age / GROWTH_VISUAL_AGE_THRESHOLD).clamp(0.0, 1.0);
+
+    let (r, g, b) = if age_normalized < 0.5 {
+        // First half: Red (1,0,0) -> Brown (0.6,0.3,0.1)
+        let t = age_normalized * 2.0; // 0.0 to 1.0
+        let r = 1.0 - t * 0.4; // 1.0 -> 0.6
+        let g = t * 0.3; // 0.0 -> 0.3
+        let b = t * 0.1; // 0.0 -> 0.1
+        (r, g, b)
+    } else {
+        // Second half: Brown (0.6,0.3,0.1) -> Black (0,0,0)
+        let t = (age_normalized - 0.5) * 2.0; // 0.0 to 1.0
+        let r = 0.6 - t * 0.6; // 0.6 -> 0.0
+        let g = 0.3 - t * 0.3; // 0.3 -> 0.0
+        let b = 0.1 - t * 0.1; // 0.1 -> 0.0
+        (r, g, b)
+    };
+
+    Color::srgb(r, g, b)
+}
@@ -1,17 +1,35 @@
 use bevy::prelude::*;
 
 mod experiments;
+mod fog_of_war;
+mod growth;
 mod launcher;
+mod minimap;
+mod navigation;
+mod orbital_strike;
+mod terrain;
 
 use experiments::{AppState, all_experiments};
+use fog_of_war::FogOfWarPlugin;
 use launcher::LauncherPlugin;
+use minimap::MinimapPlugin;
+use navigation::NavigationPlugin;
+use orbital_strike::OrbitalStrikePlugin;
+use terrain::TerrainPlugin;
 
 fn main() {
     let mut app = App::new();
 
     app.add_plugins(DefaultPlugins)
         .init_state::<AppState>()
-        .add_plugins(LauncherPlugin);
+        .add_plugins((
+            LauncherPlugin,
+            TerrainPlugin,
+            FogOfWarPlugin,
+            MinimapPlugin,
+            NavigationPlugin,
+            OrbitalStrikePlugin,
+        ));
 
     // Add all experiment systems from registry
     for experiment in all_experiments() {
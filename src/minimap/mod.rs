@@ -0,0 +1,284 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    platform::collections::HashSet,
+    prelude::*,
+    render::{
+        camera::{ClearColorConfig, ScalingMode},
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    },
+    ui::UiTargetCamera,
+};
+
+// Render target resolution; sharp enough for the full-screen tactical map
+const MINIMAP_RESOLUTION: u32 = 512;
+// The minimap camera looks straight down from this height
+const MINIMAP_ALTITUDE: f32 = 1000f32;
+const MINIMAP_CLEAR_COLOR: Color = Color::srgb(0.02, 0.02, 0.03);
+
+// Layout, in logical pixels unless noted
+const MINIMAP_SIZE: f32 = 220f32;
+const MINIMAP_MARGIN: f32 = 12f32;
+const TACTICAL_SIZE_VH: f32 = 90f32; // percent of the window height
+const FRAME_BORDER: f32 = 2f32;
+const FRAME_COLOR: Color = Color::srgba(0.8, 0.8, 0.8, 0.8);
+const ICON_SIZE: f32 = 8f32;
+const ICON_NOSE_LENGTH: f32 = 6f32;
+
+/// On the minimap camera and UI root, so experiments can despawn both in cleanup
+#[derive(Component)]
+pub struct Minimap;
+
+/// Shows the entity on the minimap as a dot with a heading tick
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MinimapMarker {
+    pub color: Color,
+}
+
+/// Text line under the minimap that experiments fill in
+#[derive(Component)]
+pub struct MinimapCaption;
+
+/// Lays the root out as a corner minimap or a centered tactical map
+#[derive(Component)]
+struct MinimapRoot {
+    tactical: bool,
+}
+
+/// The image node; icons are placed relative to it
+#[derive(Component)]
+struct MinimapFrame {
+    /// World XZ rectangle the camera covers, north (-Z) up
+    area: Rect,
+}
+
+/// UI icon following a [`MinimapMarker`] entity
+#[derive(Component)]
+struct MinimapIcon(Entity);
+
+/// Spawns a top-down camera over `area` rendering into a texture, and a
+/// corner widget showing it on `ui_camera`'s view
+pub struct SpawnMinimap {
+    /// World XZ rectangle to show
+    pub area: Rect,
+    pub ui_camera: Entity,
+}
+
+impl Command for SpawnMinimap {
+    fn apply(self, world: &mut World) {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: MINIMAP_RESOLUTION,
+                height: MINIMAP_RESOLUTION,
+                ..default()
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Bgra8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+        let image = world.resource_mut::<Assets<Image>>().add(image);
+
+        let size = self.area.size();
+        let center = self.area.center();
+        world.spawn((
+            Camera3d::default(),
+            Camera {
+                target: image.clone().into(),
+                // Draw before the main camera so the texture is ready for the UI
+                order: -1,
+                clear_color: ClearColorConfig::Custom(MINIMAP_CLEAR_COLOR),
+                ..default()
+            },
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: size.x,
+                    height: size.y,
+                },
+                far: 2f32 * MINIMAP_ALTITUDE,
+                ..OrthographicProjection::default_3d()
+            }),
+            Transform::from_xyz(center.x, MINIMAP_ALTITUDE, center.y)
+                .looking_at(Vec3::new(center.x, 0f32, center.y), Vec3::NEG_Z),
+            Minimap,
+        ));
+
+        let (justify_content, align_items, frame_size) = minimap_layout(false);
+        world
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100f32),
+                    height: Val::Percent(100f32),
+                    padding: UiRect::all(Val::Px(MINIMAP_MARGIN)),
+                    justify_content,
+                    align_items,
+                    ..default()
+                },
+                UiTargetCamera(self.ui_camera),
+                Minimap,
+                MinimapRoot { tactical: false },
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|column| {
+                        column.spawn((
+                            ImageNode::new(image),
+                            Node {
+                                width: frame_size,
+                                height: frame_size,
+                                border: UiRect::all(Val::Px(FRAME_BORDER)),
+                                ..default()
+                            },
+                            BorderColor(FRAME_COLOR),
+                            MinimapFrame { area: self.area },
+                        ));
+                        column.spawn((
+                            Text::default(),
+                            TextFont {
+                                font_size: 14f32,
+                                ..default()
+                            },
+                            TextColor(FRAME_COLOR),
+                            MinimapCaption,
+                        ));
+                    });
+            });
+    }
+}
+
+/// Root content placement and frame size for either mode
+fn minimap_layout(tactical: bool) -> (JustifyContent, AlignItems, Val) {
+    if tactical {
+        (
+            JustifyContent::Center,
+            AlignItems::Center,
+            Val::Vh(TACTICAL_SIZE_VH),
+        )
+    } else {
+        (
+            JustifyContent::FlexEnd,
+            AlignItems::FlexStart,
+            Val::Px(MINIMAP_SIZE),
+        )
+    }
+}
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_tactical_map,
+                spawn_minimap_icons,
+                update_minimap_icons,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// M swaps between the corner minimap and the full-screen tactical map
+fn toggle_tactical_map(
+    input: Res<ButtonInput<KeyCode>>,
+    mut roots: Query<(&mut Node, &mut MinimapRoot)>,
+    mut frames: Query<&mut Node, (With<MinimapFrame>, Without<MinimapRoot>)>,
+) {
+    if !input.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+    for (mut node, mut root) in &mut roots {
+        root.tactical = !root.tactical;
+        let (justify_content, align_items, frame_size) = minimap_layout(root.tactical);
+        node.justify_content = justify_content;
+        node.align_items = align_items;
+        for mut frame in &mut frames {
+            frame.width = frame_size;
+            frame.height = frame_size;
+        }
+    }
+}
+
+fn spawn_minimap_icons(
+    mut commands: Commands,
+    frames: Query<Entity, With<MinimapFrame>>,
+    markers: Query<(Entity, &MinimapMarker)>,
+    icons: Query<&MinimapIcon>,
+) {
+    let Ok(frame) = frames.single() else {
+        return;
+    };
+    let tracked: HashSet<Entity> = icons.iter().map(|icon| icon.0).collect();
+    for (entity, marker) in &markers {
+        if tracked.contains(&entity) {
+            continue;
+        }
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(ICON_SIZE),
+                height: Val::Px(ICON_SIZE),
+                // Center the icon on its position
+                margin: UiRect {
+                    left: Val::Px(-ICON_SIZE / 2f32),
+                    top: Val::Px(-ICON_SIZE / 2f32),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(marker.color),
+            BorderRadius::MAX,
+            MinimapIcon(entity),
+            ChildOf(frame),
+            // The tick points the way the entity faces
+            children![(
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(2f32),
+                    height: Val::Px(ICON_NOSE_LENGTH),
+                    bottom: Val::Px(ICON_SIZE / 2f32),
+                    ..default()
+                },
+                BackgroundColor(marker.color),
+            )],
+        ));
+    }
+}
+
+fn update_minimap_icons(
+    mut commands: Commands,
+    frames: Query<&MinimapFrame>,
+    mut icons: Query<(Entity, &MinimapIcon, &ChildOf, &mut Node, &mut Transform)>,
+    markers: Query<&GlobalTransform, With<MinimapMarker>>,
+) {
+    for (icon_entity, icon, parent, mut node, mut transform) in &mut icons {
+        let Ok(target) = markers.get(icon.0) else {
+            commands.entity(icon_entity).despawn();
+            continue;
+        };
+        let Ok(frame) = frames.get(parent.parent()) else {
+            continue;
+        };
+
+        // Percent offsets keep the icon in place when the frame is resized
+        let position = target.translation().xz();
+        let uv = ((position - frame.area.min) / frame.area.size()).clamp(Vec2::ZERO, Vec2::ONE);
+        node.left = Val::Percent(uv.x * 100f32);
+        node.top = Val::Percent(uv.y * 100f32);
+
+        // Clockwise from north; UI y points down so positive Z rotation is clockwise
+        let forward = target.forward();
+        let heading = f32::atan2(forward.x, -forward.z);
+        transform.rotation = Quat::from_rotation_z(heading);
+    }
+}
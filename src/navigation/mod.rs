@@ -0,0 +1,567 @@
+use std::{cmp::Reverse, collections::BinaryHeap, sync::Arc};
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
+};
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::terrain::heightfield::Heightfield;
+
+// Step costs; crossing a flat cell straight costs 10, diagonally about √2 as
+// much, and a cell's multiplier scales both
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+// Line of sight is checked at this many points per cell along the line
+const SIGHT_SAMPLES_PER_CELL: f32 = 4f32;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// How steepness turns into walking cost
+#[derive(Debug, Clone, Copy)]
+pub struct SlopeCost {
+    /// Rise over run beyond which ground can't be walked
+    pub max_slope: f32,
+    /// Extra cost multiplier per unit of rise over run
+    pub penalty: f32,
+}
+
+/// Grid of walkable cells over a world plane, each with a cost to cross it.
+///
+/// Cell `(x, y)` covers `origin + (x, y) * cell_size` to one cell further; a
+/// negative cell size runs the grid the other way, so tile rows can count
+/// up while world rows count down. Units move to any of the eight
+/// neighbours, but never diagonally past a blocked cell.
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    size: UVec2,
+    origin: Vec2,
+    cell_size: Vec2,
+    /// Cost to step straight into each cell, `None` where blocked
+    costs: Vec<Option<u32>>,
+}
+
+impl NavGrid {
+    /// Grid of flat, open cells
+    pub fn new(size: UVec2, origin: Vec2, cell_size: Vec2) -> Self {
+        Self {
+            size,
+            origin,
+            cell_size,
+            costs: vec![Some(STRAIGHT_COST); (size.x * size.y) as usize],
+        }
+    }
+
+    /// Grid whose cells cost more the steeper the heightfield is under their
+    /// centers, and are blocked past the maximum slope
+    pub fn from_heightfield(
+        heightfield: &Heightfield,
+        size: UVec2,
+        origin: Vec2,
+        cell_size: Vec2,
+        slope: SlopeCost,
+    ) -> Self {
+        let mut grid = Self::new(size, origin, cell_size);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let cell = UVec2::new(x, y);
+                let steepness = heightfield
+                    .nearest(grid.cell_center(cell))
+                    .map_or(0f32, |sample| heightfield.slope(sample.x, sample.y));
+                let cost =
+                    (steepness <= slope.max_slope).then_some(1f32 + slope.penalty * steepness);
+                grid.set_cost(cell, cost);
+            }
+        }
+        grid
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    /// Sets how many flat cells crossing a cell is worth, at least one;
+    /// `None` blocks it
+    pub fn set_cost(&mut self, cell: UVec2, multiplier: Option<f32>) {
+        let index = self.index(cell);
+        self.costs[index] = multiplier
+            .map(|multiplier| (multiplier.max(1f32) * STRAIGHT_COST as f32).round() as u32);
+    }
+
+    /// How many flat cells crossing a cell is worth, `None` where blocked
+    pub fn cost(&self, cell: UVec2) -> Option<f32> {
+        self.costs[self.index(cell)].map(|cost| cost as f32 / STRAIGHT_COST as f32)
+    }
+
+    /// Blocks the cells under a tilemap's tiles that `blocked` picks out;
+    /// `offset` is the cell under the tilemap's first tile
+    pub fn block_tiles(
+        &mut self,
+        storage: &TileStorage,
+        offset: UVec2,
+        blocked: impl Fn(Entity) -> bool,
+    ) {
+        for y in 0..storage.size.y {
+            for x in 0..storage.size.x {
+                let cell = offset + UVec2::new(x, y);
+                if cell.cmpge(self.size).any() {
+                    continue;
+                }
+                if storage.get(&TilePos { x, y }).is_some_and(&blocked) {
+                    self.set_cost(cell, None);
+                }
+            }
+        }
+    }
+
+    /// Cells off the grid count as blocked
+    fn step_cost(&self, cell: IVec2) -> Option<u32> {
+        if cell.cmplt(IVec2::ZERO).any() || cell.cmpge(self.size.as_ivec2()).any() {
+            return None;
+        }
+        self.costs[self.index(cell.as_uvec2())]
+    }
+
+    fn is_blocked(&self, cell: IVec2) -> bool {
+        self.step_cost(cell).is_none()
+    }
+
+    /// Every blocked cell
+    pub fn blocked_cells(&self) -> impl Iterator<Item = UVec2> + '_ {
+        (0..self.size.y)
+            .flat_map(move |y| (0..self.size.x).map(move |x| UVec2::new(x, y)))
+            .filter(|cell| self.costs[self.index(*cell)].is_none())
+    }
+
+    pub fn cell_center(&self, cell: UVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    /// Cell under a world position, which may be off the grid
+    fn cell_under(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / self.cell_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    /// Cell under a world position, if it's on the grid
+    pub fn cell_at(&self, position: Vec2) -> Option<UVec2> {
+        let cell = self.cell_under(position);
+        (cell.cmpge(IVec2::ZERO).all() && cell.cmplt(self.size.as_ivec2()).all())
+            .then_some(cell.as_uvec2())
+    }
+
+    /// Cell under a world position, or the grid cell nearest it when it's
+    /// off the grid
+    pub fn nearest_cell(&self, position: Vec2) -> UVec2 {
+        self.cell_under(position)
+            .clamp(IVec2::ZERO, self.size.as_ivec2() - 1)
+            .as_uvec2()
+    }
+
+    /// Whether a circle overlaps a blocked cell or leaves the grid
+    pub fn blocks_circle(&self, center: Vec2, radius: f32) -> bool {
+        let (a, b) = (
+            self.cell_under(center - radius),
+            self.cell_under(center + radius),
+        );
+        let (min, max) = (a.min(b), a.max(b));
+        (min.y..=max.y).any(|y| {
+            (min.x..=max.x).any(|x| {
+                let cell = IVec2::new(x, y);
+                if !self.is_blocked(cell) {
+                    return false;
+                }
+                let corner = self.origin + cell.as_vec2() * self.cell_size;
+                let far = corner + self.cell_size;
+                let closest = center.clamp(corner.min(far), corner.max(far));
+                closest.distance_squared(center) < radius * radius
+            })
+        })
+    }
+
+    /// Whether the straight line between two points stays clear of blocked
+    /// cells
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        let cell_length = self.cell_size.abs().min_element();
+        let samples =
+            ((from.distance(to) / cell_length * SIGHT_SAMPLES_PER_CELL).ceil() as u32).max(1);
+        (0..=samples).all(|i| {
+            let point = from.lerp(to, i as f32 / samples as f32);
+            !self.is_blocked(self.cell_under(point))
+        })
+    }
+
+    /// Open neighbours of a cell, and whether each is a diagonal step; the
+    /// same pairs connect whichever way they're walked
+    fn neighbours(&self, cell: UVec2) -> impl Iterator<Item = (UVec2, bool)> + '_ {
+        let cell = cell.as_ivec2();
+        NEIGHBOURS.iter().filter_map(move |offset| {
+            let next = cell + *offset;
+            let diagonal = offset.x != 0 && offset.y != 0;
+            // No squeezing diagonally between two blocked corners
+            let squeezed = diagonal
+                && (self.is_blocked(cell + IVec2::new(offset.x, 0))
+                    || self.is_blocked(cell + IVec2::new(0, offset.y)));
+            (!self.is_blocked(next) && !squeezed).then_some((next.as_uvec2(), diagonal))
+        })
+    }
+
+    /// Cost of stepping into an open cell
+    fn step_into(&self, cell: UVec2, diagonal: bool) -> u32 {
+        let cost = self.costs[self.index(cell)].unwrap_or(u32::MAX / DIAGONAL_COST);
+        if diagonal {
+            cost * DIAGONAL_COST / STRAIGHT_COST
+        } else {
+            cost
+        }
+    }
+
+    /// Cheapest path between two open cells by A*, excluding `from`
+    fn find_path(&self, from: UVec2, to: UVec2) -> Option<NavPath> {
+        if self.is_blocked(from.as_ivec2()) || self.is_blocked(to.as_ivec2()) {
+            return None;
+        }
+        // No cell is cheaper than flat ground, so octile distance over flat
+        // ground never overestimates and the first path to `to` is cheapest
+        let heuristic = |cell: UVec2| {
+            let delta = (cell.as_ivec2() - to.as_ivec2()).abs().as_uvec2();
+            let (short, long) = (delta.min_element(), delta.max_element());
+            DIAGONAL_COST * short + STRAIGHT_COST * (long - short)
+        };
+
+        let mut costs = vec![u32::MAX; self.costs.len()];
+        let mut came_from: Vec<Option<UVec2>> = vec![None; self.costs.len()];
+        let mut explored = Vec::new();
+        let mut open = BinaryHeap::new();
+        costs[self.index(from)] = 0;
+        open.push(Reverse((heuristic(from), 0, from.x, from.y)));
+
+        while let Some(Reverse((_, cost, x, y))) = open.pop() {
+            let cell = UVec2::new(x, y);
+            if cell == to {
+                let mut cells = Vec::new();
+                let mut step = to;
+                while step != from {
+                    cells.push(step);
+                    step = came_from[self.index(step)]?;
+                }
+                cells.reverse();
+                return Some(NavPath {
+                    cells,
+                    cost: cost as f32 / STRAIGHT_COST as f32,
+                    explored,
+                });
+            }
+            // A cheaper way here was found after this entry was queued
+            if cost > costs[self.index(cell)] {
+                continue;
+            }
+            explored.push(cell);
+            for (next, diagonal) in self.neighbours(cell) {
+                let next_cost = cost + self.step_into(next, diagonal);
+                let index = self.index(next);
+                if next_cost < costs[index] {
+                    costs[index] = next_cost;
+                    came_from[index] = Some(cell);
+                    open.push(Reverse((
+                        next_cost + heuristic(next),
+                        next_cost,
+                        next.x,
+                        next.y,
+                    )));
+                }
+            }
+        }
+        None
+    }
+
+    /// Cheapest way toward `target` from every cell that can reach it, by
+    /// Dijkstra outward from the target
+    fn flow_field(&self, target: UVec2) -> FlowField {
+        let mut costs = vec![u32::MAX; self.costs.len()];
+        let mut open = BinaryHeap::new();
+        if !self.is_blocked(target.as_ivec2()) {
+            costs[self.index(target)] = 0;
+            open.push(Reverse((0, target.x, target.y)));
+        }
+        while let Some(Reverse((cost, x, y))) = open.pop() {
+            let cell = UVec2::new(x, y);
+            if cost > costs[self.index(cell)] {
+                continue;
+            }
+            // Units walk the other way, from the neighbour into this cell
+            for (next, diagonal) in self.neighbours(cell) {
+                let next_cost = cost + self.step_into(cell, diagonal);
+                let index = self.index(next);
+                if next_cost < costs[index] {
+                    costs[index] = next_cost;
+                    open.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        // Each cell points at the neighbour it's cheapest to step into and go
+        // on from
+        let next = (0..self.size.y)
+            .flat_map(|y| (0..self.size.x).map(move |x| UVec2::new(x, y)))
+            .map(|cell| {
+                if cell == target || costs[self.index(cell)] == u32::MAX {
+                    return None;
+                }
+                self.neighbours(cell)
+                    .min_by_key(|(next, diagonal)| {
+                        costs[self.index(*next)].saturating_add(self.step_into(*next, *diagonal))
+                    })
+                    .map(|(next, _)| next)
+            })
+            .collect();
+        FlowField {
+            size: self.size,
+            target,
+            next,
+        }
+    }
+}
+
+/// A route found by a [`PathRequest`]
+#[derive(Component, Debug, Clone, Default)]
+pub struct NavPath {
+    /// Cells to walk, the first step first and the goal last
+    pub cells: Vec<UVec2>,
+    /// What walking it costs, in flat cells crossed
+    pub cost: f32,
+    /// Cells the search expanded on the way, in order, for debugging
+    pub explored: Vec<UVec2>,
+}
+
+/// Searches for a path off the main thread; once done it's replaced by a
+/// [`NavPath`], or removed if there is no path
+#[derive(Component)]
+pub struct PathRequest(Task<Option<NavPath>>);
+
+impl PathRequest {
+    pub fn new(grid: &Arc<NavGrid>, from: UVec2, to: UVec2) -> Self {
+        let grid = grid.clone();
+        Self(AsyncComputeTaskPool::get().spawn(async move { grid.find_path(from, to) }))
+    }
+}
+
+/// Which way to go from every cell to reach one target; one field steers any
+/// number of units
+#[derive(Component, Debug, Clone)]
+pub struct FlowField {
+    size: UVec2,
+    target: UVec2,
+    /// Next cell toward the target, `None` at the target and where it can't
+    /// be reached
+    next: Vec<Option<UVec2>>,
+}
+
+impl FlowField {
+    pub fn target(&self) -> UVec2 {
+        self.target
+    }
+
+    /// Next cell to head for from `cell`
+    pub fn next(&self, cell: UVec2) -> Option<UVec2> {
+        if cell.cmpge(self.size).any() {
+            return None;
+        }
+        self.next[(cell.y * self.size.x + cell.x) as usize]
+    }
+}
+
+/// Builds a flow field off the main thread; once done it's replaced by the
+/// [`FlowField`]
+#[derive(Component)]
+pub struct FlowFieldRequest(Task<FlowField>);
+
+impl FlowFieldRequest {
+    pub fn new(grid: &Arc<NavGrid>, target: UVec2) -> Self {
+        let grid = grid.clone();
+        Self(AsyncComputeTaskPool::get().spawn(async move { grid.flow_field(target) }))
+    }
+}
+
+/// Finishes path and flow field requests for any experiment that makes them
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (finish_path_requests, finish_flow_field_requests),
+        );
+    }
+}
+
+fn finish_path_requests(mut commands: Commands, mut requests: Query<(Entity, &mut PathRequest)>) {
+    for (entity, mut request) in &mut requests {
+        let Some(path) = block_on(future::poll_once(&mut request.0)) else {
+            continue;
+        };
+        let mut entity = commands.entity(entity);
+        entity.remove::<PathRequest>();
+        match path {
+            Some(path) => entity.insert(path),
+            None => entity.remove::<NavPath>(),
+        };
+    }
+}
+
+fn finish_flow_field_requests(
+    mut commands: Commands,
+    mut requests: Query<(Entity, &mut FlowFieldRequest)>,
+) {
+    for (entity, mut request) in &mut requests {
+        if let Some(field) = block_on(future::poll_once(&mut request.0)) {
+            commands
+                .entity(entity)
+                .remove::<FlowFieldRequest>()
+                .insert(field);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(size: UVec2) -> NavGrid {
+        NavGrid::new(size, Vec2::ZERO, Vec2::ONE)
+    }
+
+    /// A grid with uneven costs and a few walls, some cells fenced off
+    fn rough_grid() -> NavGrid {
+        let mut grid = grid(UVec2::new(8, 6));
+        for y in 0..6 {
+            for x in 0..8 {
+                let cost = 1 + (x * 7 + y * 13) % 5;
+                grid.set_cost(UVec2::new(x, y), Some(cost as f32));
+            }
+        }
+        for cell in [(3, 0), (3, 1), (3, 2), (3, 3), (5, 2), (5, 3), (5, 4)] {
+            grid.set_cost(UVec2::from(cell), None);
+        }
+        // (7, 0) is cut off from the rest
+        grid.set_cost(UVec2::new(6, 0), None);
+        grid.set_cost(UVec2::new(6, 1), None);
+        grid.set_cost(UVec2::new(7, 1), None);
+        grid
+    }
+
+    #[test]
+    fn a_star_takes_the_cheapest_path() {
+        let mut grid = grid(UVec2::new(5, 3));
+        grid.set_cost(UVec2::new(2, 0), Some(10f32));
+        grid.set_cost(UVec2::new(2, 1), Some(10f32));
+
+        let path = grid.find_path(UVec2::new(0, 1), UVec2::new(4, 1)).unwrap();
+        // Straight through costs 13; around the costly cells 1 + 1.4 + 1.4 + 1
+        assert!((path.cost - 4.8).abs() < 1e-4, "cost {}", path.cost);
+        assert!(path.cells.contains(&UVec2::new(2, 2)));
+        assert_eq!(path.cells.last(), Some(&UVec2::new(4, 1)));
+        assert!(!path.cells.contains(&UVec2::new(0, 1)));
+    }
+
+    #[test]
+    fn diagonals_never_cut_past_a_blocked_corner() {
+        let mut grid = grid(UVec2::new(2, 2));
+        grid.set_cost(UVec2::new(1, 0), None);
+        grid.set_cost(UVec2::new(0, 1), None);
+        assert!(grid.find_path(UVec2::ZERO, UVec2::ONE).is_none());
+
+        // With one corner open the path goes round it, two straight steps
+        grid.set_cost(UVec2::new(0, 1), Some(1f32));
+        let path = grid.find_path(UVec2::ZERO, UVec2::ONE).unwrap();
+        assert_eq!(path.cells, [UVec2::new(0, 1), UVec2::ONE]);
+        assert!((path.cost - 2f32).abs() < 1e-4);
+    }
+
+    #[test]
+    fn no_path_to_blocked_or_walled_off_cells() {
+        let grid = rough_grid();
+        assert!(grid.find_path(UVec2::ZERO, UVec2::new(3, 1)).is_none());
+        assert!(grid.find_path(UVec2::ZERO, UVec2::new(7, 0)).is_none());
+        assert!(grid.find_path(UVec2::new(3, 1), UVec2::ZERO).is_none());
+    }
+
+    #[test]
+    fn flow_field_steps_cost_the_same_as_a_star() {
+        let grid = rough_grid();
+        let target = UVec2::new(7, 5);
+        let field = grid.flow_field(target);
+        assert_eq!(field.target(), target);
+        assert_eq!(field.next(target), None);
+
+        for y in 0..6 {
+            for x in 0..8 {
+                let start = UVec2::new(x, y);
+                if start == target {
+                    continue;
+                }
+                let Some(path) = grid.find_path(start, target) else {
+                    assert_eq!(field.next(start), None, "{start} can't reach the target");
+                    continue;
+                };
+                let (mut cell, mut cost) = (start, 0);
+                while cell != target {
+                    let next = field.next(cell).unwrap();
+                    let step = next.as_ivec2() - cell.as_ivec2();
+                    assert!(
+                        step.abs().max_element() == 1,
+                        "{cell} to {next} isn't a step"
+                    );
+                    cost += grid.step_into(next, step.x != 0 && step.y != 0);
+                    cell = next;
+                }
+                let cost = cost as f32 / STRAIGHT_COST as f32;
+                assert!(
+                    (cost - path.cost).abs() < 1e-4,
+                    "from {start}: flow field {cost}, A* {}",
+                    path.cost
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn line_of_sight_stops_at_blocked_cells() {
+        let mut grid = grid(UVec2::new(5, 5));
+        grid.set_cost(UVec2::new(2, 2), None);
+        assert!(!grid.line_of_sight(Vec2::new(0.5, 2.5), Vec2::new(4.5, 2.5)));
+        assert!(grid.line_of_sight(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)));
+        // Leaving the grid counts as blocked
+        assert!(!grid.line_of_sight(Vec2::new(0.5, 0.5), Vec2::new(-1f32, 0.5)));
+    }
+
+    #[test]
+    fn cells_under_a_flipped_grid() {
+        // Rows counting up while world y counts down, as under a tilemap
+        let grid = NavGrid::new(
+            UVec2::new(4, 4),
+            Vec2::new(0f32, 40f32),
+            Vec2::new(10f32, -10f32),
+        );
+        assert_eq!(grid.cell_at(Vec2::new(5f32, 35f32)), Some(UVec2::ZERO));
+        assert_eq!(grid.cell_center(UVec2::new(1, 3)), Vec2::new(15f32, 5f32));
+        assert_eq!(grid.cell_at(Vec2::new(5f32, 45f32)), None);
+        assert_eq!(grid.nearest_cell(Vec2::new(-20f32, 45f32)), UVec2::ZERO);
+        assert_eq!(grid.nearest_cell(Vec2::new(90f32, -5f32)), UVec2::new(3, 3));
+    }
+}
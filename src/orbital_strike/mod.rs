@@ -0,0 +1,206 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::growth::{CrimsonColony, DEFAULT_MAX_GROWTH_AGE, GrowthPatch};
+
+// Damage constants; growth and colonies start with 1 health
+const MATURE_RESISTANCE: f32 = 2f32; // extra damage a fully mature patch shrugs off, as a multiple
+const WITHER_RATE: f32 = 0.2; // health per second lost on freshly scorched ground
+const MIN_PATCH_SCALE: f32 = 0.2; // wounded patches shrink down to this
+
+// Scorch constants
+const SCORCH_SECS: f32 = 20f32; // how long scorched ground blocks regrowth
+const SCORCH_HEIGHT: f32 = 0.005; // above the ground, below the growth
+const SCORCH_COLOR: Color = Color::srgba(0.08, 0.05, 0.03, 0.85);
+
+/// Fires a beam at the ground; everything within `radius` of `center` on the
+/// XZ plane is hit, hardest in the middle
+#[derive(Event, Debug, Clone, Copy)]
+pub struct OrbitalStrike {
+    pub center: Vec3,
+    pub radius: f32,
+    /// Damage at the center; 1 destroys fresh growth outright
+    pub strength: f32,
+}
+
+impl OrbitalStrike {
+    /// Share of the strength reaching a point, falling to nothing at the edge
+    fn falloff(&self, point: Vec3) -> f32 {
+        let distance = self.center.xz().distance(point.xz()) / self.radius;
+        (1f32 - distance * distance).max(0f32)
+    }
+}
+
+/// Ground a strike burned; growth can't spread onto it and growth left on it
+/// keeps withering until it cools
+#[derive(Component)]
+pub struct ScorchMark {
+    center: Vec3,
+    radius: f32,
+    cooling: Timer,
+}
+
+impl ScorchMark {
+    /// 1 when fresh, 0 once cooled
+    fn heat(&self) -> f32 {
+        self.cooling.fraction_remaining()
+    }
+
+    fn falloff(&self, point: Vec3) -> f32 {
+        let distance = self.center.xz().distance(point.xz()) / self.radius;
+        (1f32 - distance * distance).max(0f32)
+    }
+
+    /// Whether regrowth is blocked at a point
+    pub fn covers(&self, point: Vec3) -> bool {
+        self.center.xz().distance(point.xz()) <= self.radius
+    }
+}
+
+/// Damage a patch takes from an intensity; mature growth resists more
+fn patch_damage(patch: &GrowthPatch, intensity: f32) -> f32 {
+    let maturity = (patch.age / DEFAULT_MAX_GROWTH_AGE).clamp(0f32, 1f32);
+    intensity / (1f32 + MATURE_RESISTANCE * maturity)
+}
+
+/// Resolves orbital strikes against the crimson growth in any experiment
+/// that has some. Experiments send [`OrbitalStrike`] and despawn leftover
+/// [`ScorchMark`]s on exit.
+pub struct OrbitalStrikePlugin;
+
+impl Plugin for OrbitalStrikePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OrbitalStrike>().add_systems(
+            Update,
+            (
+                apply_orbital_strikes,
+                wither_scorched_growth,
+                cool_scorch_marks,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn apply_orbital_strikes(
+    mut commands: Commands,
+    mut strikes: EventReader<OrbitalStrike>,
+    mut patches: Query<(Entity, &mut GrowthPatch, &mut Transform), Without<CrimsonColony>>,
+    mut colonies: Query<(Entity, &mut CrimsonColony, &Transform), Without<GrowthPatch>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for strike in strikes.read() {
+        let mut destroyed = 0;
+        let mut wounded = 0;
+        for (entity, mut patch, mut transform) in &mut patches {
+            let intensity = strike.falloff(transform.translation) * strike.strength;
+            if intensity <= 0f32 {
+                continue;
+            }
+            patch.health -= patch_damage(&patch, intensity);
+            if patch.health <= 0f32 {
+                commands.entity(entity).despawn();
+                destroyed += 1;
+            } else {
+                transform.scale = Vec3::splat(patch.health.max(MIN_PATCH_SCALE));
+                wounded += 1;
+            }
+        }
+
+        // The growth recoils: colonies pull back out of the zone and have to
+        // grow across it again, and an origin inside it is hurt
+        for (entity, mut colony, transform) in &mut colonies {
+            let distance = strike.center.xz().distance(transform.translation.xz());
+            if distance > colony.radius + strike.radius {
+                continue;
+            }
+            colony.radius = colony.radius.min((distance - strike.radius).max(0f32));
+            colony.expansion_complete = false;
+            let intensity = strike.falloff(transform.translation) * strike.strength;
+            if intensity <= 0f32 {
+                continue;
+            }
+            colony.health -= intensity;
+            if colony.health <= 0f32 {
+                commands.entity(entity).despawn();
+                info!("Colony at {} destroyed", transform.translation.xz());
+            } else {
+                info!(
+                    "Colony at {} hit, {:.0}% health left",
+                    transform.translation.xz(),
+                    colony.health * 100f32
+                );
+            }
+        }
+        info!(
+            "Orbital strike at {}: {destroyed} patches destroyed, {wounded} withering",
+            strike.center.xz()
+        );
+
+        commands.spawn((
+            ScorchMark {
+                center: strike.center,
+                radius: strike.radius,
+                cooling: Timer::from_seconds(SCORCH_SECS, TimerMode::Once),
+            },
+            Mesh3d(meshes.add(Circle::new(strike.radius))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: SCORCH_COLOR,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })),
+            // Circles face +Z; lay it flat on the ground
+            Transform::from_translation(strike.center.with_y(strike.center.y + SCORCH_HEIGHT))
+                .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+        ));
+    }
+}
+
+/// Growth left on scorched ground loses health while the ground is hot
+fn wither_scorched_growth(
+    mut commands: Commands,
+    time: Res<Time>,
+    marks: Query<&ScorchMark>,
+    mut patches: Query<(Entity, &mut GrowthPatch, &mut Transform)>,
+) {
+    if marks.is_empty() {
+        return;
+    }
+    for (entity, mut patch, mut transform) in &mut patches {
+        let heat: f32 = marks
+            .iter()
+            .map(|mark| mark.heat() * mark.falloff(transform.translation))
+            .sum();
+        if heat <= 0f32 {
+            continue;
+        }
+        patch.health -= patch_damage(&patch, WITHER_RATE * heat * time.delta_secs());
+        if patch.health <= 0f32 {
+            commands.entity(entity).despawn();
+        } else {
+            transform.scale = Vec3::splat(patch.health.max(MIN_PATCH_SCALE));
+        }
+    }
+}
+
+/// Fades scorch marks as they cool and removes them once regrowth can resume
+fn cool_scorch_marks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut marks: Query<(Entity, &mut ScorchMark, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, mut mark, material) in &mut marks {
+        mark.cooling.tick(time.delta());
+        if mark.cooling.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = SCORCH_COLOR.with_alpha(SCORCH_COLOR.alpha() * mark.heat());
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use noise::{Fbm, NoiseFn, Perlin};
+
+use super::{heightfield::Heightfield, hydrology::WaterMap};
+
+// Secondary noise constants
+const MOISTURE_SEED_OFFSET: u32 = 1;
+const TEMPERATURE_SEED_OFFSET: u32 = 2;
+const CLIMATE_NOISE_SCALE: f64 = 900.0;
+
+// Classification thresholds, in terms of height normalized to [-1, 1]
+const PEAK_HEIGHT: f32 = 0.4;
+const HIGHLAND_HEIGHT: f32 = 0.15;
+const LOWLAND_HEIGHT: f32 = -0.4;
+const CLIFF_SLOPE: f32 = 0.9; // rise over run, a little steeper than 40°
+const ALTITUDE_COOLING: f32 = 0.6; // how much temperature drops from valley floor to peak
+
+/// Broad terrain classification used for rendering and gameplay rules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum Biome {
+    Wetland,
+    #[default]
+    Grassland,
+    Scrubland,
+    Badlands,
+    Cliffs,
+    Snowcap,
+}
+
+impl Biome {
+    /// Every biome, in declaration order; a biome's index doubles as its save id
+    pub const ALL: [Biome; 6] = [
+        Biome::Wetland,
+        Biome::Grassland,
+        Biome::Scrubland,
+        Biome::Badlands,
+        Biome::Cliffs,
+        Biome::Snowcap,
+    ];
+
+    /// Splat weights for the terrain material layers: vegetation, earth, rock, snow
+    pub fn splat(&self) -> [f32; 4] {
+        match self {
+            Biome::Wetland => [0.7, 0.3, 0.0, 0.0],
+            Biome::Grassland => [1.0, 0.0, 0.0, 0.0],
+            Biome::Scrubland => [0.4, 0.6, 0.0, 0.0],
+            Biome::Badlands => [0.0, 0.8, 0.2, 0.0],
+            Biome::Cliffs => [0.0, 0.1, 0.9, 0.0],
+            Biome::Snowcap => [0.0, 0.0, 0.2, 0.8],
+        }
+    }
+}
+
+/// Climate inputs for a single terrain sample, each in [0, 1]
+#[derive(Debug, Clone, Copy)]
+pub struct Climate {
+    pub moisture: f32,
+    pub temperature: f32,
+}
+
+/// Secondary noise fields driving biome selection
+pub struct ClimateNoise {
+    moisture: Fbm<Perlin>,
+    temperature: Fbm<Perlin>,
+}
+
+impl ClimateNoise {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            moisture: Fbm::new(seed.wrapping_add(MOISTURE_SEED_OFFSET)),
+            temperature: Fbm::new(seed.wrapping_add(TEMPERATURE_SEED_OFFSET)),
+        }
+    }
+
+    /// Climate at a world XZ position with the given normalized height
+    pub fn sample(&self, position: Vec2, height: f32) -> Climate {
+        let point = [
+            position.x as f64 / CLIMATE_NOISE_SCALE,
+            position.y as f64 / CLIMATE_NOISE_SCALE,
+        ];
+        let moisture = (self.moisture.get(point) as f32 * 0.5 + 0.5).clamp(0.0, 1.0);
+        let temperature = (self.temperature.get(point) as f32 * 0.5 + 0.5
+            - height.max(0.0) * ALTITUDE_COOLING)
+            .clamp(0.0, 1.0);
+        Climate {
+            moisture,
+            temperature,
+        }
+    }
+}
+
+/// Picks a biome from normalized height, slope, and climate
+pub fn classify(height: f32, slope: f32, climate: Climate) -> Biome {
+    if slope > CLIFF_SLOPE {
+        Biome::Cliffs
+    } else if height > PEAK_HEIGHT && climate.temperature < 0.5 {
+        Biome::Snowcap
+    } else if height > HIGHLAND_HEIGHT {
+        if climate.moisture < 0.5 {
+            Biome::Badlands
+        } else {
+            Biome::Scrubland
+        }
+    } else if height < LOWLAND_HEIGHT && climate.moisture > 0.4 {
+        Biome::Wetland
+    } else if climate.moisture < 0.35 && climate.temperature > 0.5 {
+        Biome::Scrubland
+    } else {
+        Biome::Grassland
+    }
+}
+
+/// Per-sample biome ids and splat weights aligned with a [`Heightfield`]
+#[derive(Debug, Clone)]
+pub struct BiomeMap {
+    width: u32,
+    biomes: Vec<Biome>,
+    splats: Vec<[f32; 4]>,
+}
+
+impl BiomeMap {
+    /// Classifies every sample of `heightfield`; `max_height` normalizes heights to [-1, 1].
+    ///
+    /// Ground near rivers and lakes is at least as moist as the water map says.
+    pub fn classify(
+        heightfield: &Heightfield,
+        max_height: f32,
+        climate: &ClimateNoise,
+        water: &WaterMap,
+    ) -> Self {
+        let size = heightfield.size();
+        let mut biomes = Vec::with_capacity((size.x * size.y) as usize);
+        let mut splats = Vec::with_capacity((size.x * size.y) as usize);
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let biome = classify_sample(heightfield, max_height, climate, water, x, y);
+                biomes.push(biome);
+                splats.push(blend_splat(biome, heightfield.slope(x, y)));
+            }
+        }
+
+        Self {
+            width: size.x,
+            biomes,
+            splats,
+        }
+    }
+
+    /// Classifies one sample again after its height changed
+    pub fn reclassify(
+        &mut self,
+        heightfield: &Heightfield,
+        max_height: f32,
+        climate: &ClimateNoise,
+        water: &WaterMap,
+        x: u32,
+        y: u32,
+    ) {
+        let biome = classify_sample(heightfield, max_height, climate, water, x, y);
+        self.set(heightfield, x, y, biome);
+    }
+
+    /// Overrides one sample's biome, keeping the slope-based rock blend
+    pub fn set(&mut self, heightfield: &Heightfield, x: u32, y: u32, biome: Biome) {
+        let index = (y * self.width + x) as usize;
+        self.biomes[index] = biome;
+        self.splats[index] = blend_splat(biome, heightfield.slope(x, y));
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Biome {
+        self.biomes[(y * self.width + x) as usize]
+    }
+
+    pub fn splat(&self, x: u32, y: u32) -> [f32; 4] {
+        self.splats[(y * self.width + x) as usize]
+    }
+}
+
+fn classify_sample(
+    heightfield: &Heightfield,
+    max_height: f32,
+    climate: &ClimateNoise,
+    water: &WaterMap,
+    x: u32,
+    y: u32,
+) -> Biome {
+    let height = heightfield.get(x, y) / max_height;
+    let slope = heightfield.slope(x, y);
+    let mut climate = climate.sample(heightfield.grid_to_world(x, y), height);
+    climate.moisture = climate.moisture.max(water.moisture(x, y));
+    classify(height, slope, climate)
+}
+
+/// Blends toward rock as the ground steepens so biome edges on hillsides don't
+/// show hard seams
+fn blend_splat(biome: Biome, slope: f32) -> [f32; 4] {
+    let rockiness = (slope / CLIFF_SLOPE).clamp(0.0, 1.0).powi(2);
+    let mut splat = biome.splat();
+    for weight in splat.iter_mut() {
+        *weight *= 1.0 - rockiness;
+    }
+    splat[2] += rockiness;
+    splat
+}
@@ -0,0 +1,410 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io, path::Path};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use super::{
+    SUBDIVISIONS, TerrainMesh, TerrainStore, TerrainWorld, WaterMesh, biome::Biome, poi,
+    scatter::Prop, spawn_water,
+};
+
+// Delta samples along each side of a chunk, matching its mesh vertices
+const CHUNK_SAMPLES: u32 = SUBDIVISIONS + 2;
+
+// How quickly smooth and flatten pull heights toward their target, per unit of strength
+const BLEND_RATE: f32 = 0.1;
+
+// Edit files start with this tag and version so stale formats are rejected
+#[cfg(not(target_arch = "wasm32"))]
+const EDITS_MAGIC: &[u8; 4] = b"RSED";
+#[cfg(not(target_arch = "wasm32"))]
+const EDITS_VERSION: u32 = 1;
+
+/// What a brush stroke does to the samples under it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Brush {
+    Raise,
+    Lower,
+    /// Pulls heights toward the average of their neighbors
+    Smooth,
+    /// Pulls heights toward a fixed height, usually the one under the cursor when the stroke began
+    Flatten(f32),
+    /// Overrides the biome classification
+    Paint(Biome),
+}
+
+/// Hand edits for one chunk: a height offset and an optional painted biome per sample
+#[derive(Debug, Clone)]
+struct ChunkEdits {
+    heights: Vec<f32>,
+    biomes: Vec<Option<Biome>>,
+}
+
+impl Default for ChunkEdits {
+    fn default() -> Self {
+        let count = (CHUNK_SAMPLES * CHUNK_SAMPLES) as usize;
+        Self {
+            heights: vec![0f32; count],
+            biomes: vec![None; count],
+        }
+    }
+}
+
+/// Sculpted height deltas and painted biomes layered over generated terrain.
+///
+/// Chunks share their edge samples; each shared sample is stored once, by the
+/// chunk with the larger coordinate, so deltas never apply twice.
+#[derive(Resource, Debug, Default)]
+pub struct TerrainEdits {
+    chunks: HashMap<IVec2, ChunkEdits>,
+}
+
+impl TerrainEdits {
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    fn local_index(local: UVec2) -> usize {
+        (local.y * CHUNK_SAMPLES + local.x) as usize
+    }
+
+    fn painted(&self, chunk: IVec2, local: UVec2) -> Option<Biome> {
+        self.chunks
+            .get(&chunk)
+            .and_then(|edits| edits.biomes[Self::local_index(local)])
+    }
+
+    /// Writes the edits and the seed they were made against.
+    ///
+    /// Layout, little-endian: magic, version, seed, samples per chunk side and
+    /// chunk count, then per chunk its coordinate, f32 height deltas, and one
+    /// byte per sample for the painted biome (0 for none, otherwise
+    /// [`Biome::ALL`] index + 1).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &Path, seed: u32) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(EDITS_MAGIC);
+        bytes.extend_from_slice(&EDITS_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&seed.to_le_bytes());
+        bytes.extend_from_slice(&CHUNK_SAMPLES.to_le_bytes());
+        bytes.extend_from_slice(&(self.chunks.len() as u32).to_le_bytes());
+
+        let mut chunks: Vec<_> = self.chunks.iter().collect();
+        chunks.sort_by_key(|(chunk, _)| (chunk.y, chunk.x));
+        for (chunk, edits) in chunks {
+            bytes.extend_from_slice(&chunk.x.to_le_bytes());
+            bytes.extend_from_slice(&chunk.y.to_le_bytes());
+            for height in &edits.heights {
+                bytes.extend_from_slice(&height.to_le_bytes());
+            }
+            bytes.extend(edits.biomes.iter().map(|biome| match biome {
+                Some(biome) => *biome as u8 + 1,
+                None => 0,
+            }));
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, bytes)
+    }
+
+    /// Reads edits written by [`TerrainEdits::save`], returning them with their seed
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &Path) -> io::Result<(u32, Self)> {
+        let bytes = fs::read(path)?;
+        let mut reader = bytes.as_slice();
+        if &read_word(&mut reader)? != EDITS_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a terrain edits file",
+            ));
+        }
+        let version = u32::from_le_bytes(read_word(&mut reader)?);
+        let seed = u32::from_le_bytes(read_word(&mut reader)?);
+        let samples = u32::from_le_bytes(read_word(&mut reader)?);
+        let count = u32::from_le_bytes(read_word(&mut reader)?);
+        if version != EDITS_VERSION || samples != CHUNK_SAMPLES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported terrain edits (version {version}, {samples} samples per chunk)"
+                ),
+            ));
+        }
+
+        let mut edits = Self::default();
+        for _ in 0..count {
+            let chunk = IVec2::new(
+                i32::from_le_bytes(read_word(&mut reader)?),
+                i32::from_le_bytes(read_word(&mut reader)?),
+            );
+            let mut chunk_edits = ChunkEdits::default();
+            for height in chunk_edits.heights.iter_mut() {
+                *height = f32::from_le_bytes(read_word(&mut reader)?);
+            }
+            let biomes = read_bytes(&mut reader, chunk_edits.biomes.len())?;
+            for (biome, id) in chunk_edits.biomes.iter_mut().zip(biomes) {
+                *biome = id
+                    .checked_sub(1)
+                    .and_then(|index| Biome::ALL.get(index as usize).copied());
+            }
+            edits.chunks.insert(chunk, chunk_edits);
+        }
+        Ok((seed, edits))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_bytes<'a>(reader: &mut &'a [u8], count: usize) -> io::Result<&'a [u8]> {
+    if reader.len() < count {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "terrain edits file is truncated",
+        ));
+    }
+    let (head, tail) = reader.split_at(count);
+    *reader = tail;
+    Ok(head)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_word(reader: &mut &[u8]) -> io::Result<[u8; 4]> {
+    Ok(read_bytes(reader, 4)?.try_into().expect("4 bytes"))
+}
+
+impl TerrainWorld {
+    /// The chunk that stores a heightfield sample's edits and the sample's
+    /// position within it
+    fn edit_owner(&self, cell: UVec2) -> (IVec2, UVec2) {
+        let stride = SUBDIVISIONS + 1;
+        let last = 2 * self.settings.chunk_radius as u32;
+        let chunk = (cell / stride).min(UVec2::splat(last));
+        let local = cell - chunk * stride;
+        (
+            chunk.as_ivec2() - IVec2::splat(self.settings.chunk_radius),
+            local,
+        )
+    }
+
+    /// Every chunk whose mesh includes a heightfield sample; up to four at corners
+    fn chunks_containing(&self, cell: UVec2) -> impl Iterator<Item = IVec2> + use<> {
+        let (chunk, local) = self.edit_owner(cell);
+        let radius = self.settings.chunk_radius;
+        let xs = [Some(chunk.x), (local.x == 0).then_some(chunk.x - 1)];
+        let ys = [Some(chunk.y), (local.y == 0).then_some(chunk.y - 1)];
+        xs.into_iter()
+            .flatten()
+            .flat_map(move |x| ys.into_iter().flatten().map(move |y| IVec2::new(x, y)))
+            .filter(move |chunk| chunk.x >= -radius && chunk.y >= -radius)
+    }
+
+    /// Layers saved edits over freshly generated terrain
+    pub fn apply_edits(&mut self, edits: &TerrainEdits) {
+        if edits.chunks.is_empty() {
+            return;
+        }
+        let size = self.heightfield.size();
+        let mut painted = Vec::new();
+        for (chunk, chunk_edits) in &edits.chunks {
+            let radius = self.settings.chunk_radius;
+            if chunk.x.abs() > radius || chunk.y.abs() > radius {
+                continue;
+            }
+            let origin = self.chunk_grid_origin(*chunk);
+            for y in 0..CHUNK_SAMPLES {
+                for x in 0..CHUNK_SAMPLES {
+                    let cell = origin + UVec2::new(x, y);
+                    if cell.x >= size.x || cell.y >= size.y {
+                        continue;
+                    }
+                    let local = TerrainEdits::local_index(UVec2::new(x, y));
+                    let index = self.heightfield.index(cell.x, cell.y);
+                    self.heightfield.heights_mut()[index] += chunk_edits.heights[local];
+                    if let Some(biome) = chunk_edits.biomes[local] {
+                        painted.push((cell, biome));
+                    }
+                }
+            }
+        }
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                self.reclassify(x, y);
+            }
+        }
+        for (cell, biome) in painted {
+            self.biomes.set(&self.heightfield, cell.x, cell.y, biome);
+        }
+    }
+
+    /// Applies one brush dab centered at a world XZ position and records it in
+    /// `edits`. `amount` is world units of height for raise/lower and a blend
+    /// weight for smooth/flatten; scale it by frame time for continuous strokes.
+    ///
+    /// Returns the chunks whose meshes need refreshing.
+    pub fn apply_brush(
+        &mut self,
+        edits: &mut TerrainEdits,
+        brush: Brush,
+        center: Vec2,
+        radius: f32,
+        amount: f32,
+    ) -> HashSet<IVec2> {
+        let size = self.heightfield.size();
+        let spacing = self.heightfield.spacing();
+        let grid = self.heightfield.world_to_grid(center);
+        let reach = Vec2::splat(radius / spacing);
+        let max = (size - UVec2::ONE).as_vec2();
+        let min_cell = (grid - reach).floor().clamp(Vec2::ZERO, max).as_uvec2();
+        let max_cell = (grid + reach).ceil().clamp(Vec2::ZERO, max).as_uvec2();
+
+        // Work out every new height before writing any, so smoothing reads
+        // the terrain as it was before this dab
+        let mut changes = Vec::new();
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                let distance = (Vec2::new(x as f32, y as f32) - grid).length() * spacing;
+                if distance >= radius {
+                    continue;
+                }
+                let falloff = 1f32 - distance / radius;
+                let weight = falloff * falloff * (3f32 - 2f32 * falloff);
+                let height = self.heightfield.get(x, y);
+                let blend = (amount * weight * BLEND_RATE).min(1f32);
+                let target = match brush {
+                    Brush::Raise => height + amount * weight,
+                    Brush::Lower => height - amount * weight,
+                    Brush::Smooth => {
+                        let (x, y) = (x as i32, y as i32);
+                        let mut total = 0f32;
+                        for dy in -1..=1 {
+                            for dx in -1..=1 {
+                                total += self.heightfield.get_clamped(x + dx, y + dy);
+                            }
+                        }
+                        height + (total / 9f32 - height) * blend
+                    }
+                    Brush::Flatten(level) => height + (level - height) * blend,
+                    Brush::Paint(_) => height,
+                };
+                changes.push((UVec2::new(x, y), target - height));
+            }
+        }
+
+        let mut touched = HashSet::new();
+        for (cell, delta) in changes {
+            let (chunk, local) = self.edit_owner(cell);
+            let chunk_edits = edits.chunks.entry(chunk).or_default();
+            let local = TerrainEdits::local_index(local);
+            if let Brush::Paint(biome) = brush {
+                chunk_edits.biomes[local] = Some(biome);
+            } else {
+                chunk_edits.heights[local] += delta;
+                let index = self.heightfield.index(cell.x, cell.y);
+                self.heightfield.heights_mut()[index] += delta;
+            }
+            touched.extend(self.chunks_containing(cell));
+        }
+
+        // Slopes change one sample beyond the brush, so reclassify a ring wider
+        let min_cell = min_cell.saturating_sub(UVec2::ONE);
+        let max_cell = (max_cell + UVec2::ONE).min(size - UVec2::ONE);
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                let (chunk, local) = self.edit_owner(UVec2::new(x, y));
+                match edits.painted(chunk, local) {
+                    Some(biome) => self.biomes.set(&self.heightfield, x, y, biome),
+                    None => self.reclassify(x, y),
+                }
+                touched.extend(self.chunks_containing(UVec2::new(x, y)));
+            }
+        }
+        touched
+    }
+
+    fn reclassify(&mut self, x: u32, y: u32) {
+        self.biomes.reclassify(
+            &self.heightfield,
+            self.settings.height,
+            &self.climate,
+            &self.water,
+            x,
+            y,
+        );
+    }
+}
+
+/// Reshapes the meshes of already spawned chunks to match the current
+/// heightfield and biomes, rebuilds their water surfaces, and settles their
+/// props and points of interest back onto the ground
+pub struct RefreshTerrainChunks(pub HashSet<IVec2>);
+
+impl Command for RefreshTerrainChunks {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, terrain: Mut<TerrainWorld>| {
+            let handles: Vec<(IVec2, Handle<Mesh>)> = self
+                .0
+                .iter()
+                .filter_map(|chunk| {
+                    world
+                        .resource::<TerrainStore>()
+                        .0
+                        .get(chunk)
+                        .map(|handle| (*chunk, handle.clone()))
+                })
+                .collect();
+            let chunks: HashMap<Entity, IVec2> = world
+                .query_filtered::<(Entity, &Mesh3d), With<TerrainMesh>>()
+                .iter(world)
+                .filter_map(|(entity, mesh)| {
+                    handles
+                        .iter()
+                        .find(|(_, handle)| *handle == mesh.0)
+                        .map(|(chunk, _)| (entity, *chunk))
+                })
+                .collect();
+            let chunk_entities: HashSet<Entity> = chunks.keys().copied().collect();
+
+            let mut meshes = world.resource_mut::<Assets<Mesh>>();
+            for (chunk, handle) in handles {
+                if let Some(mesh) = meshes.get_mut(&handle) {
+                    terrain.shape_chunk_mesh(chunk, mesh);
+                }
+            }
+
+            // Water follows the new ground: reshaped, added or dried up
+            let waters: HashMap<Entity, (Entity, Handle<Mesh>)> = world
+                .query_filtered::<(Entity, &ChildOf, &Mesh3d), With<WaterMesh>>()
+                .iter(world)
+                .filter(|(_, parent, _)| chunk_entities.contains(&parent.parent()))
+                .map(|(entity, parent, mesh)| (parent.parent(), (entity, mesh.0.clone())))
+                .collect();
+            for (chunk_entity, chunk) in &chunks {
+                match (terrain.water_mesh(*chunk), waters.get(chunk_entity)) {
+                    (Some(water_mesh), Some((_, handle))) => {
+                        if let Some(mesh) = world.resource_mut::<Assets<Mesh>>().get_mut(handle) {
+                            *mesh = water_mesh;
+                        }
+                    }
+                    (Some(water_mesh), None) => spawn_water(world, water_mesh, *chunk_entity),
+                    (None, Some((water, _))) => world.entity_mut(*water).despawn(),
+                    (None, None) => {}
+                }
+            }
+
+            // Chunks sit at y = 0, so a prop's local height is its ground height
+            let mut props =
+                world.query_filtered::<(&ChildOf, &mut Transform, &GlobalTransform), With<Prop>>();
+            for (parent, mut transform, global) in props.iter_mut(world) {
+                if chunk_entities.contains(&parent.parent()) {
+                    transform.translation.y = terrain.height_at(global.translation().xz());
+                }
+            }
+            poi::settle_points_of_interest(world, &terrain, &chunk_entities);
+        });
+    }
+}
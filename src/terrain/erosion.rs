@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::heightfield::Heightfield;
+
+// Droplet constants, tuned for heights normalized to roughly [-1, 1]
+const INERTIA: f32 = 0.05; // how strongly a droplet keeps its heading
+const SEDIMENT_CAPACITY: f32 = 4f32;
+const MIN_SEDIMENT_CAPACITY: f32 = 0.01;
+const ERODE_SPEED: f32 = 0.3;
+const DEPOSIT_SPEED: f32 = 0.3;
+const EVAPORATE_SPEED: f32 = 0.01;
+const GRAVITY: f32 = 4f32;
+const MAX_DROPLET_LIFETIME: u32 = 30;
+const EROSION_RADIUS: i32 = 3;
+
+// Thermal constants
+const TALUS_SLOPE: f32 = 0.7; // rise over run beyond which material slumps
+const THERMAL_RATE: f32 = 0.25;
+
+/// Tunable parameters for the optional erosion pass
+#[derive(Debug, Clone, Copy)]
+pub struct ErosionSettings {
+    /// Number of simulated rain droplets
+    pub droplets: u32,
+    /// Number of thermal talus passes over the whole heightfield
+    pub thermal_iterations: u32,
+}
+
+/// Runs hydraulic then thermal erosion over the heightfield in place.
+///
+/// `height_scale` normalizes heights so the droplet constants behave the same
+/// regardless of terrain amplitude. Droplets are seeded from `seed`, so the
+/// result is deterministic.
+pub fn erode(
+    heightfield: &mut Heightfield,
+    settings: &ErosionSettings,
+    height_scale: f32,
+    seed: u32,
+) {
+    let size = heightfield.size();
+    for height in heightfield.heights_mut() {
+        *height /= height_scale;
+    }
+
+    hydraulic(heightfield, settings.droplets, seed);
+    let talus = TALUS_SLOPE * heightfield.spacing() / height_scale;
+    for _ in 0..settings.thermal_iterations {
+        thermal(heightfield, talus);
+    }
+
+    for height in heightfield.heights_mut() {
+        *height *= height_scale;
+    }
+    debug!(
+        "Eroded {}x{} heightfield with {} droplets",
+        size.x, size.y, settings.droplets
+    );
+}
+
+/// Height and gradient at a fractional grid position, which must be at least
+/// one sample away from the far edges
+fn height_and_gradient(heightfield: &Heightfield, position: Vec2) -> (f32, Vec2) {
+    let cell = position.floor();
+    let t = position - cell;
+    let (x, y) = (cell.x as u32, cell.y as u32);
+
+    let nw = heightfield.get(x, y);
+    let ne = heightfield.get(x + 1, y);
+    let sw = heightfield.get(x, y + 1);
+    let se = heightfield.get(x + 1, y + 1);
+
+    let gradient = Vec2::new(
+        (ne - nw) * (1f32 - t.y) + (se - sw) * t.y,
+        (sw - nw) * (1f32 - t.x) + (se - ne) * t.x,
+    );
+    let height = nw * (1f32 - t.x) * (1f32 - t.y)
+        + ne * t.x * (1f32 - t.y)
+        + sw * (1f32 - t.x) * t.y
+        + se * t.x * t.y;
+    (height, gradient)
+}
+
+fn hydraulic(heightfield: &mut Heightfield, droplets: u32, seed: u32) {
+    let size = heightfield.size();
+    let max = (size - UVec2::splat(2)).as_vec2();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+
+    // Weighted brush spreading erosion over nearby samples
+    let mut brush = Vec::new();
+    for dy in -EROSION_RADIUS..=EROSION_RADIUS {
+        for dx in -EROSION_RADIUS..=EROSION_RADIUS {
+            let weight = EROSION_RADIUS as f32 - Vec2::new(dx as f32, dy as f32).length();
+            if weight > 0f32 {
+                brush.push((IVec2::new(dx, dy), weight));
+            }
+        }
+    }
+    let brush_total: f32 = brush.iter().map(|(_, weight)| weight).sum();
+
+    for _ in 0..droplets {
+        let mut position = Vec2::new(rng.gen_range(0f32..max.x), rng.gen_range(0f32..max.y));
+        let mut direction = Vec2::ZERO;
+        let mut speed = 1f32;
+        let mut water = 1f32;
+        let mut sediment = 0f32;
+
+        for _ in 0..MAX_DROPLET_LIFETIME {
+            let cell = position.floor();
+            let offset = position - cell;
+            let (height, gradient) = height_and_gradient(heightfield, position);
+
+            direction = direction * INERTIA - gradient * (1f32 - INERTIA);
+            if direction.length_squared() < f32::EPSILON {
+                break;
+            }
+            direction = direction.normalize();
+            position += direction;
+            if position.x < 0f32 || position.y < 0f32 || position.x >= max.x || position.y >= max.y
+            {
+                break;
+            }
+
+            let (new_height, _) = height_and_gradient(heightfield, position);
+            let delta = new_height - height;
+            let capacity = (-delta * speed * water * SEDIMENT_CAPACITY).max(MIN_SEDIMENT_CAPACITY);
+
+            if sediment > capacity || delta > 0f32 {
+                // Drop sediment into the four samples around the old position,
+                // filling pits when moving uphill
+                let amount = if delta > 0f32 {
+                    delta.min(sediment)
+                } else {
+                    (sediment - capacity) * DEPOSIT_SPEED
+                };
+                sediment -= amount;
+                let (x, y) = (cell.x as u32, cell.y as u32);
+                let deposits = [
+                    (x, y, (1f32 - offset.x) * (1f32 - offset.y)),
+                    (x + 1, y, offset.x * (1f32 - offset.y)),
+                    (x, y + 1, (1f32 - offset.x) * offset.y),
+                    (x + 1, y + 1, offset.x * offset.y),
+                ];
+                for (x, y, weight) in deposits {
+                    let index = heightfield.index(x, y);
+                    heightfield.heights_mut()[index] += amount * weight;
+                }
+            } else {
+                // Erode no more than the height difference so we never dig holes
+                let amount = ((capacity - sediment) * ERODE_SPEED).min(-delta);
+                for (brush_offset, weight) in &brush {
+                    let sample = cell.as_ivec2() + *brush_offset;
+                    if sample.x < 0
+                        || sample.y < 0
+                        || sample.x >= size.x as i32
+                        || sample.y >= size.y as i32
+                    {
+                        continue;
+                    }
+                    let index = heightfield.index(sample.x as u32, sample.y as u32);
+                    let removed = amount * weight / brush_total;
+                    heightfield.heights_mut()[index] -= removed;
+                    sediment += removed;
+                }
+            }
+
+            speed = (speed * speed + delta * GRAVITY).max(0f32).sqrt();
+            water *= 1f32 - EVAPORATE_SPEED;
+        }
+    }
+}
+
+/// Moves material from each sample to lower neighbors where the slope exceeds `talus`
+fn thermal(heightfield: &mut Heightfield, talus: f32) {
+    let size = heightfield.size();
+    let mut changes = vec![0f32; heightfield.heights().len()];
+
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let height = heightfield.get(x, y);
+            for (dx, dy) in [(1i32, 0i32), (0, 1)] {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if nx >= size.x as i32 || ny >= size.y as i32 {
+                    continue;
+                }
+                let neighbor = heightfield.get(nx as u32, ny as u32);
+                let difference = height - neighbor;
+                if difference.abs() <= talus {
+                    continue;
+                }
+                let moved = (difference.abs() - talus) * THERMAL_RATE * 0.5 * difference.signum();
+                changes[heightfield.index(x, y)] -= moved;
+                changes[heightfield.index(nx as u32, ny as u32)] += moved;
+            }
+        }
+    }
+
+    for (height, change) in heightfield.heights_mut().iter_mut().zip(changes) {
+        *height += change;
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+};
+use image::{ImageBuffer, Luma, Rgb};
+
+use super::{
+    MESH_SIZE, TerrainStore, TerrainWorld, WATER_COLOR, material::TerrainMaterialExtension,
+};
+
+// Exports land here, one folder per seed, relative to the working directory
+const EXPORT_DIR: &str = "exports";
+
+// glTF enums for buffer view targets and accessor component types
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// Writes the current terrain to disk: a 16-bit heightmap PNG, a biome color
+/// PNG, and a glTF scene of every chunk mesh in [`TerrainStore`]
+pub struct ExportTerrain;
+
+impl Command for ExportTerrain {
+    fn apply(self, world: &mut World) {
+        let Some(terrain) = world.get_resource::<TerrainWorld>() else {
+            warn!("No terrain generated yet, nothing to export");
+            return;
+        };
+        let dir = PathBuf::from(EXPORT_DIR).join(format!("terrain_{}", terrain.settings.seed));
+        let meshes = world.resource::<Assets<Mesh>>();
+        let store = world.resource::<TerrainStore>();
+
+        match export(terrain, store, meshes, &dir) {
+            Ok(()) => info!("Exported terrain to {}", dir.display()),
+            Err(err) => error!("Failed to export terrain to {}: {err}", dir.display()),
+        }
+    }
+}
+
+fn export(
+    terrain: &TerrainWorld,
+    store: &TerrainStore,
+    meshes: &Assets<Mesh>,
+    dir: &Path,
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    write_heightmap(terrain, &dir.join("heightmap.png"))?;
+    write_color_map(terrain, &dir.join("colormap.png"))?;
+    write_gltf(store, meshes, dir, "terrain")
+}
+
+/// Heights mapped onto the full 16-bit range; the min/max are logged so the
+/// image can be scaled back to world units
+fn write_heightmap(terrain: &TerrainWorld, path: &Path) -> io::Result<()> {
+    let size = terrain.heightfield.size();
+    let heights = terrain.heightfield.heights();
+    let min = heights.iter().copied().fold(f32::MAX, f32::min);
+    let max = heights.iter().copied().fold(f32::MIN, f32::max);
+    let range = (max - min).max(f32::EPSILON);
+
+    let pixels = heights
+        .iter()
+        .map(|height| ((height - min) / range * u16::MAX as f32).round() as u16)
+        .collect();
+    let image = ImageBuffer::<Luma<u16>, Vec<u16>>::from_raw(size.x, size.y, pixels)
+        .expect("one pixel per height sample");
+    image.save(path).map_err(io::Error::other)?;
+    info!(
+        "Heightmap {}x{} spans {min:.2} to {max:.2} world units",
+        size.x, size.y
+    );
+    Ok(())
+}
+
+/// Biome layer colors blended by splat weight, with water painted over the top
+fn write_color_map(terrain: &TerrainWorld, path: &Path) -> io::Result<()> {
+    let size = terrain.heightfield.size();
+    let layers = TerrainMaterialExtension::default();
+    let water = WATER_COLOR.to_linear();
+
+    let image = ImageBuffer::from_fn(size.x, size.y, |x, y| {
+        let mut color = splat_color(&layers, terrain.biomes.splat(x, y));
+        if terrain.water.level(x, y).is_some() {
+            color = color.mix(&water, water.alpha);
+        }
+        let [r, g, b, _] = Color::from(color).to_srgba().to_u8_array();
+        Rgb([r, g, b])
+    });
+    image.save(path).map_err(io::Error::other)
+}
+
+fn splat_color(layers: &TerrainMaterialExtension, splat: [f32; 4]) -> LinearRgba {
+    let total = splat.iter().sum::<f32>().max(f32::EPSILON);
+    let [vegetation, earth, rock, snow] = splat.map(|weight| weight / total);
+    let color = layers.vegetation * vegetation
+        + layers.earth * earth
+        + layers.rock * rock
+        + layers.snow * snow;
+    color.with_alpha(1f32)
+}
+
+/// Writes `<name>.gltf` and `<name>.bin` with one node per chunk, placed at the
+/// chunk offset so the scene lines up with the game world
+fn write_gltf(
+    store: &TerrainStore,
+    meshes: &Assets<Mesh>,
+    dir: &Path,
+    name: &str,
+) -> io::Result<()> {
+    let layers = TerrainMaterialExtension::default();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut gltf_meshes = Vec::new();
+    let mut nodes = Vec::new();
+
+    let mut chunks: Vec<_> = store.0.iter().collect();
+    chunks.sort_by_key(|(chunk, _)| (chunk.y, chunk.x));
+
+    for (chunk, handle) in chunks {
+        let Some(mesh) = meshes.get(handle) else {
+            continue;
+        };
+        let (
+            Some(VertexAttributeValues::Float32x3(positions)),
+            Some(VertexAttributeValues::Float32x3(normals)),
+            Some(indices),
+        ) = (
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+            mesh.indices(),
+        )
+        else {
+            warn!("Skipping chunk {chunk} with unexpected mesh layout");
+            continue;
+        };
+        // Splat weights mean nothing outside our shader, so bake them to colors
+        let colors: Vec<[f32; 4]> = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(splats)) => splats
+                .iter()
+                .map(|splat| splat_color(&layers, *splat).to_f32_array())
+                .collect(),
+            _ => vec![[1f32; 4]; positions.len()],
+        };
+        let indices: Vec<u32> = match indices {
+            Indices::U16(indices) => indices.iter().map(|&index| index as u32).collect(),
+            Indices::U32(indices) => indices.clone(),
+        };
+
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), position| {
+                let position = Vec3::from_array(*position);
+                (min.min(position), max.max(position))
+            },
+        );
+        let bounds = format!(
+            r#","min":[{},{},{}],"max":[{},{},{}]"#,
+            min.x, min.y, min.z, max.x, max.y, max.z
+        );
+
+        let mut attribute = |data: &[u8], target: u32, component: u32, kind: &str, extra: &str| {
+            let count = data.len() / component_size(kind);
+            buffer_views.push(format!(
+                r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{target}}}"#,
+                buffer.len(),
+                data.len()
+            ));
+            accessors.push(format!(
+                r#"{{"bufferView":{},"componentType":{component},"count":{count},"type":"{kind}"{extra}}}"#,
+                buffer_views.len() - 1
+            ));
+            buffer.extend_from_slice(data);
+            accessors.len() - 1
+        };
+        let position = attribute(
+            &f32_bytes(positions.as_flattened()),
+            ARRAY_BUFFER,
+            FLOAT,
+            "VEC3",
+            &bounds,
+        );
+        let normal = attribute(
+            &f32_bytes(normals.as_flattened()),
+            ARRAY_BUFFER,
+            FLOAT,
+            "VEC3",
+            "",
+        );
+        let color = attribute(
+            &f32_bytes(colors.as_flattened()),
+            ARRAY_BUFFER,
+            FLOAT,
+            "VEC4",
+            "",
+        );
+        let index_bytes: Vec<u8> = indices
+            .iter()
+            .flat_map(|index| index.to_le_bytes())
+            .collect();
+        let index = attribute(
+            &index_bytes,
+            ELEMENT_ARRAY_BUFFER,
+            UNSIGNED_INT,
+            "SCALAR",
+            "",
+        );
+
+        gltf_meshes.push(format!(
+            r#"{{"name":"chunk_{}_{}","primitives":[{{"attributes":{{"POSITION":{position},"NORMAL":{normal},"COLOR_0":{color}}},"indices":{index}}}]}}"#,
+            chunk.x, chunk.y
+        ));
+        let offset = chunk.as_vec2() * MESH_SIZE;
+        nodes.push(format!(
+            r#"{{"name":"chunk_{}_{}","mesh":{},"translation":[{},0,{}]}}"#,
+            chunk.x,
+            chunk.y,
+            gltf_meshes.len() - 1,
+            offset.x,
+            offset.y
+        ));
+    }
+
+    let bin_name = format!("{name}.bin");
+    fs::write(dir.join(&bin_name), &buffer)?;
+
+    let scene_nodes: Vec<String> = (0..nodes.len()).map(|node| node.to_string()).collect();
+    let json = format!(
+        r#"{{"asset":{{"version":"2.0","generator":"resurgence"}},"scene":0,"scenes":[{{"nodes":[{}]}}],"nodes":[{}],"meshes":[{}],"accessors":[{}],"bufferViews":[{}],"buffers":[{{"uri":"{bin_name}","byteLength":{}}}]}}"#,
+        scene_nodes.join(","),
+        nodes.join(","),
+        gltf_meshes.join(","),
+        accessors.join(","),
+        buffer_views.join(","),
+        buffer.len()
+    );
+    let mut file = fs::File::create(dir.join(format!("{name}.gltf")))?;
+    file.write_all(json.as_bytes())
+}
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+/// Size in bytes of one element of a glTF accessor type; all our components are 4 bytes
+fn component_size(kind: &str) -> usize {
+    match kind {
+        "VEC3" => 12,
+        "VEC4" => 16,
+        _ => 4,
+    }
+}
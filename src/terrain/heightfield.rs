@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+/// A regular grid of height samples laid out on the world XZ plane.
+///
+/// Sample `(x, y)` sits at `origin + (x, y) * spacing`, where the grid `y` axis
+/// maps to world `z`.
+#[derive(Debug, Clone)]
+pub struct Heightfield {
+    size: UVec2,
+    origin: Vec2,
+    spacing: f32,
+    heights: Vec<f32>,
+}
+
+impl Heightfield {
+    /// Builds a heightfield by evaluating `height` at every sample's world position
+    pub fn from_fn(size: UVec2, origin: Vec2, spacing: f32, height: impl Fn(Vec2) -> f32) -> Self {
+        let mut heights = Vec::with_capacity((size.x * size.y) as usize);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                heights.push(height(origin + Vec2::new(x as f32, y as f32) * spacing));
+            }
+        }
+        Self {
+            size,
+            origin,
+            spacing,
+            heights,
+        }
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    pub fn heights_mut(&mut self) -> &mut [f32] {
+        &mut self.heights
+    }
+
+    pub fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.size.x + x) as usize
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        self.heights[self.index(x, y)]
+    }
+
+    /// Height at a grid coordinate, clamping out-of-range coordinates to the edge
+    pub fn get_clamped(&self, x: i32, y: i32) -> f32 {
+        let x = x.clamp(0, self.size.x as i32 - 1) as u32;
+        let y = y.clamp(0, self.size.y as i32 - 1) as u32;
+        self.get(x, y)
+    }
+
+    /// World XZ position of a grid sample
+    pub fn grid_to_world(&self, x: u32, y: u32) -> Vec2 {
+        self.origin + Vec2::new(x as f32, y as f32) * self.spacing
+    }
+
+    /// Fractional grid coordinate of a world XZ position
+    pub fn world_to_grid(&self, position: Vec2) -> Vec2 {
+        (position - self.origin) / self.spacing
+    }
+
+    /// Nearest grid sample to a world XZ position, if it falls inside the grid
+    pub fn nearest(&self, position: Vec2) -> Option<UVec2> {
+        let grid = self.world_to_grid(position).round();
+        if grid.x < 0f32
+            || grid.y < 0f32
+            || grid.x >= self.size.x as f32
+            || grid.y >= self.size.y as f32
+        {
+            return None;
+        }
+        Some(grid.as_uvec2())
+    }
+
+    /// Bilinearly interpolated height at a world XZ position, clamped to the grid edges
+    pub fn sample(&self, position: Vec2) -> f32 {
+        let grid = self.world_to_grid(position);
+        let max = (self.size - UVec2::ONE).as_vec2();
+        let grid = grid.clamp(Vec2::ZERO, max);
+        let base = grid.floor();
+        let t = grid - base;
+        let (x, y) = (base.x as i32, base.y as i32);
+
+        let h00 = self.get_clamped(x, y);
+        let h10 = self.get_clamped(x + 1, y);
+        let h01 = self.get_clamped(x, y + 1);
+        let h11 = self.get_clamped(x + 1, y + 1);
+
+        let bottom = h00 + (h10 - h00) * t.x;
+        let top = h01 + (h11 - h01) * t.x;
+        bottom + (top - bottom) * t.y
+    }
+
+    /// Height gradient (rise over run along world X and Z) at a grid sample
+    pub fn gradient(&self, x: u32, y: u32) -> Vec2 {
+        let (x, y) = (x as i32, y as i32);
+        let dx = self.get_clamped(x + 1, y) - self.get_clamped(x - 1, y);
+        let dz = self.get_clamped(x, y + 1) - self.get_clamped(x, y - 1);
+        Vec2::new(dx, dz) / (2f32 * self.spacing)
+    }
+
+    /// Steepness at a grid sample as rise over run; 1.0 is a 45° slope
+    pub fn slope(&self, x: u32, y: u32) -> f32 {
+        self.gradient(x, y).length()
+    }
+
+    /// Surface normal at a world XZ position
+    pub fn normal_at(&self, position: Vec2) -> Vec3 {
+        let step = self.spacing;
+        let dx = self.sample(position + Vec2::X * step) - self.sample(position - Vec2::X * step);
+        let dz = self.sample(position + Vec2::Y * step) - self.sample(position - Vec2::Y * step);
+        Vec3::new(-dx, 2f32 * step, -dz).normalize()
+    }
+}
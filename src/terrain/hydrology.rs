@@ -0,0 +1,296 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use super::heightfield::Heightfield;
+
+// Priority-flood nudge so filled lake beds still drain toward their outlet
+const FLOOD_EPSILON: f32 = 1e-4;
+
+// Lakes shallower than this are treated as damp ground rather than water
+const MIN_LAKE_DEPTH: f32 = 0.5;
+// A basin's lake covers at most this fraction of the area draining into it
+const LAKE_CATCHMENT_RATIO: f32 = 0.05;
+
+// River constants
+const RIVER_THRESHOLD: f32 = 600f32; // upstream cells needed before a channel forms
+const RIVER_DEPTH_SCALE: f32 = 1.5f32;
+const RIVER_MAX_DEPTH: f32 = 6f32;
+const RIVER_MAX_HALF_WIDTH: i32 = 2;
+const RIVER_WATER_FRACTION: f32 = 0.7; // how full a carved channel is
+
+// Moisture spreads this many cells from water, in two box-blur passes
+const MOISTURE_RADIUS: i32 = 12;
+
+const NEIGHBORS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Water surface, flow, and moisture for every sample of a [`Heightfield`]
+#[derive(Debug, Clone)]
+pub struct WaterMap {
+    width: u32,
+    /// Water surface height per sample, `None` where the ground is dry
+    levels: Vec<Option<f32>>,
+    /// Dampness in [0, 1], highest in and around water
+    moisture: Vec<f32>,
+}
+
+impl WaterMap {
+    pub fn level(&self, x: u32, y: u32) -> Option<f32> {
+        self.levels[(y * self.width + x) as usize]
+    }
+
+    pub fn moisture(&self, x: u32, y: u32) -> f32 {
+        self.moisture[(y * self.width + x) as usize]
+    }
+}
+
+/// Min-heap entry for the priority flood
+struct FloodCell {
+    height: f32,
+    index: usize,
+}
+
+impl PartialEq for FloodCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FloodCell {}
+
+impl PartialOrd for FloodCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloodCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so BinaryHeap pops the lowest cell first
+        other
+            .height
+            .total_cmp(&self.height)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+/// Runs the hydrology pass: fills depressions into lakes, routes flow downhill,
+/// carves river channels into `heightfield`, and floods everything below
+/// `sea_level`.
+///
+/// The result only depends on the heightfield, so it is deterministic per seed
+/// and seamless across chunks carved from the same field.
+pub fn simulate(heightfield: &mut Heightfield, sea_level: f32) -> WaterMap {
+    let size = heightfield.size();
+    let (width, height) = (size.x as i32, size.y as i32);
+    let count = (size.x * size.y) as usize;
+    let neighbor = |index: usize, (dx, dy): (i32, i32)| -> Option<usize> {
+        let x = (index % size.x as usize) as i32 + dx;
+        let y = (index / size.x as usize) as i32 + dy;
+        (x >= 0 && y >= 0 && x < width && y < height).then_some((y * width + x) as usize)
+    };
+
+    // Priority flood from the map edges, which act as outlets
+    let mut filled = heightfield.heights().to_vec();
+    let mut closed = vec![false; count];
+    let mut open = BinaryHeap::new();
+    for index in 0..count {
+        let (x, y) = (
+            (index % size.x as usize) as i32,
+            (index / size.x as usize) as i32,
+        );
+        if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+            closed[index] = true;
+            open.push(FloodCell {
+                height: filled[index],
+                index,
+            });
+        }
+    }
+    while let Some(cell) = open.pop() {
+        for offset in NEIGHBORS {
+            let Some(next) = neighbor(cell.index, offset) else {
+                continue;
+            };
+            if closed[next] {
+                continue;
+            }
+            closed[next] = true;
+            filled[next] = filled[next].max(filled[cell.index] + FLOOD_EPSILON);
+            open.push(FloodCell {
+                height: filled[next],
+                index: next,
+            });
+        }
+    }
+
+    // Steepest descent over the filled surface, then accumulate flow from the
+    // highest cells down
+    let mut downstream = vec![None; count];
+    for (index, slot) in downstream.iter_mut().enumerate() {
+        let mut steepest = 0f32;
+        for offset in NEIGHBORS {
+            let Some(next) = neighbor(index, offset) else {
+                continue;
+            };
+            let distance = if offset.0 != 0 && offset.1 != 0 {
+                std::f32::consts::SQRT_2
+            } else {
+                1f32
+            };
+            let drop = (filled[index] - filled[next]) / distance;
+            if drop > steepest {
+                steepest = drop;
+                *slot = Some(next);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_unstable_by(|a, b| filled[*b].total_cmp(&filled[*a]));
+    let mut flow = vec![1f32; count];
+    for &index in &order {
+        if let Some(next) = downstream[index] {
+            flow[next] += flow[index];
+        }
+    }
+
+    // Each depression the flood raised becomes a basin; it only fills as far
+    // as its catchment can keep it topped up
+    let heights = heightfield.heights_mut();
+    let mut levels: Vec<Option<f32>> = vec![None; count];
+    let mut visited = vec![false; count];
+    for start in 0..count {
+        if visited[start] || filled[start] <= heights[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut basin = vec![start];
+        let mut cursor = 0;
+        while cursor < basin.len() {
+            let index = basin[cursor];
+            cursor += 1;
+            for offset in NEIGHBORS {
+                if let Some(next) = neighbor(index, offset)
+                    && !visited[next]
+                    && filled[next] > heights[next]
+                {
+                    visited[next] = true;
+                    basin.push(next);
+                }
+            }
+        }
+
+        let catchment = basin.iter().map(|&index| flow[index]).fold(0f32, f32::max);
+        let max_cells = ((catchment * LAKE_CATCHMENT_RATIO) as usize).clamp(1, basin.len());
+        basin.sort_unstable_by(|a, b| heights[*a].total_cmp(&heights[*b]));
+        let spill = basin
+            .iter()
+            .map(|&index| filled[index])
+            .fold(f32::MIN, f32::max);
+        let level = if max_cells < basin.len() {
+            heights[basin[max_cells]]
+        } else {
+            spill
+        };
+        for &index in &basin[..max_cells] {
+            if level - heights[index] > MIN_LAKE_DEPTH {
+                levels[index] = Some(level);
+            }
+        }
+    }
+    let lakes: Vec<bool> = levels.iter().map(Option::is_some).collect();
+
+    // Carve channels where enough water has gathered, widening with flow
+    for index in 0..count {
+        if flow[index] < RIVER_THRESHOLD || lakes[index] {
+            continue;
+        }
+        let strength = (flow[index] / RIVER_THRESHOLD).sqrt();
+        let depth = (strength * RIVER_DEPTH_SCALE).min(RIVER_MAX_DEPTH);
+        let half_width = (strength.floor() as i32 - 1).clamp(0, RIVER_MAX_HALF_WIDTH);
+        let bed = filled[index] - depth;
+        let surface = bed + depth * RIVER_WATER_FRACTION;
+
+        for dy in -half_width..=half_width {
+            for dx in -half_width..=half_width {
+                let Some(bank) = neighbor(index, (dx, dy)) else {
+                    continue;
+                };
+                if heights[bank] > bed {
+                    heights[bank] = bed;
+                }
+                if levels[bank].is_none_or(|level| level < surface) {
+                    levels[bank] = Some(surface);
+                }
+            }
+        }
+    }
+
+    // Anything under the sea is ocean
+    for (level, ground) in levels.iter_mut().zip(heights.iter()) {
+        if *ground < sea_level {
+            *level = Some(level.map_or(sea_level, |level| level.max(sea_level)));
+        }
+    }
+
+    // Moisture bleeds outward from water and strong drainage lines
+    let mut moisture: Vec<f32> = (0..count)
+        .map(|index| {
+            if levels[index].is_some() {
+                1f32
+            } else {
+                (flow[index] / RIVER_THRESHOLD).min(1f32)
+            }
+        })
+        .collect();
+    for _ in 0..2 {
+        moisture = box_blur(&moisture, size.x as usize, size.y as usize, MOISTURE_RADIUS);
+    }
+    for (index, value) in moisture.iter_mut().enumerate() {
+        if levels[index].is_some() {
+            *value = 1f32;
+        }
+    }
+
+    WaterMap {
+        width: size.x,
+        levels,
+        moisture,
+    }
+}
+
+/// Separable box blur with edge clamping
+fn box_blur(values: &[f32], width: usize, height: usize, radius: i32) -> Vec<f32> {
+    let window = (2 * radius + 1) as f32;
+    let clamp = |value: i32, max: usize| value.clamp(0, max as i32 - 1) as usize;
+
+    let mut horizontal = vec![0f32; values.len()];
+    for y in 0..height {
+        let row = &values[y * width..(y + 1) * width];
+        let mut sum: f32 = (-radius..=radius).map(|dx| row[clamp(dx, width)]).sum();
+        for x in 0..width {
+            horizontal[y * width + x] = sum / window;
+            sum += row[clamp(x as i32 + radius + 1, width)] - row[clamp(x as i32 - radius, width)];
+        }
+    }
+
+    let mut vertical = vec![0f32; values.len()];
+    for x in 0..width {
+        let column = |y: usize| horizontal[y * width + x];
+        let mut sum: f32 = (-radius..=radius).map(|dy| column(clamp(dy, height))).sum();
+        for y in 0..height {
+            vertical[y * width + x] = sum / window;
+            sum += column(clamp(y as i32 + radius + 1, height))
+                - column(clamp(y as i32 - radius, height));
+        }
+    }
+    vertical
+}
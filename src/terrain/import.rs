@@ -0,0 +1,185 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader},
+    path::Path,
+};
+
+use bevy::prelude::*;
+use tiff::{
+    decoder::{Decoder, DecodingResult},
+    tags::Tag,
+};
+
+// GeoTIFF files keep their no-data value in this GDAL-specific ASCII tag
+const GDAL_NODATA_TAG: u16 = 42113;
+
+/// A grid of height values read from disk, normalized to [0, 1]
+#[derive(Debug, Clone)]
+pub struct HeightMap {
+    size: UVec2,
+    values: Vec<f32>,
+}
+
+impl HeightMap {
+    /// Loads a heightmap image or DEM, picking the format from the file extension:
+    /// `.asc` for ESRI ASCII grids, `.tif`/`.tiff` for GeoTIFF, anything else as an image.
+    ///
+    /// DEM elevations are stretched so the lowest sample is 0 and the highest is 1.
+    /// Images keep their own range, so a hand-painted mid-gray stays mid-height.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let heights = match extension.as_deref() {
+            Some("asc") => Self::from_elevations(read_ascii_grid(path)?)?,
+            Some("tif" | "tiff") => Self::from_elevations(read_geotiff(path)?)?,
+            _ => read_image(path)?,
+        };
+        info!(
+            "Loaded {}x{} heightmap from {}",
+            heights.size.x,
+            heights.size.y,
+            path.display()
+        );
+        Ok(heights)
+    }
+
+    /// Normalizes raw elevations, filling no-data holes with the lowest valid elevation
+    fn from_elevations((size, elevations): (UVec2, Vec<Option<f32>>)) -> io::Result<Self> {
+        let valid = elevations.iter().flatten().copied();
+        let min = valid.clone().fold(f32::MAX, f32::min);
+        let max = valid.fold(f32::MIN, f32::max);
+        if min > max {
+            return Err(invalid_data("DEM has no valid elevations"));
+        }
+        info!("DEM elevations span {min:.2} to {max:.2}");
+
+        let range = (max - min).max(f32::EPSILON);
+        let values = elevations
+            .into_iter()
+            .map(|elevation| (elevation.unwrap_or(min) - min) / range)
+            .collect();
+        Ok(Self { size, values })
+    }
+
+    /// Bilinearly interpolated value at `uv`, where (0, 0) is the first row and
+    /// column and (1, 1) is the last
+    pub fn sample(&self, uv: Vec2) -> f32 {
+        let max = (self.size - UVec2::ONE).as_vec2();
+        let grid = (uv * max).clamp(Vec2::ZERO, max);
+        let base = grid.floor();
+        let t = grid - base;
+        let (x, y) = (base.x as u32, base.y as u32);
+        let get = |x: u32, y: u32| {
+            let (x, y) = (x.min(self.size.x - 1), y.min(self.size.y - 1));
+            self.values[(y * self.size.x + x) as usize]
+        };
+
+        let bottom = get(x, y) + (get(x + 1, y) - get(x, y)) * t.x;
+        let top = get(x, y + 1) + (get(x + 1, y + 1) - get(x, y + 1)) * t.x;
+        bottom + (top - bottom) * t.y
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Grayscale image of any bit depth, converted to [0, 1]
+fn read_image(path: &Path) -> io::Result<HeightMap> {
+    let image = image::open(path).map_err(io::Error::other)?.into_luma16();
+    let (width, height) = image.dimensions();
+    if width < 2 || height < 2 {
+        return Err(invalid_data("heightmap must be at least 2x2 pixels"));
+    }
+    let values = image
+        .into_raw()
+        .into_iter()
+        .map(|value| value as f32 / u16::MAX as f32)
+        .collect();
+    Ok(HeightMap {
+        size: UVec2::new(width, height),
+        values,
+    })
+}
+
+/// ESRI ASCII grid: a short `key value` header followed by rows of elevations, north first
+fn read_ascii_grid(path: &Path) -> io::Result<(UVec2, Vec<Option<f32>>)> {
+    let text = fs::read_to_string(path)?;
+    let mut tokens = text.split_whitespace().peekable();
+    let (mut columns, mut rows, mut nodata) = (None, None, None);
+
+    // Header keys are case-insensitive and only nodata is optional
+    while let Some(key) = tokens.next_if(|token| token.parse::<f32>().is_err()) {
+        let value = tokens
+            .next()
+            .ok_or_else(|| invalid_data(format!("missing value for {key}")))?;
+        let parse_error = || invalid_data(format!("bad value for {key}: {value}"));
+        match key.to_ascii_lowercase().as_str() {
+            "ncols" => columns = Some(value.parse::<u32>().map_err(|_| parse_error())?),
+            "nrows" => rows = Some(value.parse::<u32>().map_err(|_| parse_error())?),
+            "nodata_value" => nodata = Some(value.parse::<f32>().map_err(|_| parse_error())?),
+            _ => {} // corner and cell size only matter for georeferencing
+        }
+    }
+    let (Some(columns), Some(rows)) = (columns, rows) else {
+        return Err(invalid_data("ASCII grid header needs ncols and nrows"));
+    };
+
+    let elevations = tokens
+        .map(|token| {
+            let elevation: f32 = token
+                .parse()
+                .map_err(|_| invalid_data(format!("bad elevation: {token}")))?;
+            Ok((Some(elevation) != nodata).then_some(elevation))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    check_grid(UVec2::new(columns, rows), elevations)
+}
+
+/// Single-band GeoTIFF elevations; only the first band of multi-band files is used
+fn read_geotiff(path: &Path) -> io::Result<(UVec2, Vec<Option<f32>>)> {
+    let mut decoder = Decoder::new(BufReader::new(File::open(path)?)).map_err(io::Error::other)?;
+    let (width, height) = decoder.dimensions().map_err(io::Error::other)?;
+    let nodata = decoder
+        .get_tag_ascii_string(Tag::Unknown(GDAL_NODATA_TAG))
+        .ok()
+        .and_then(|value| value.trim_matches(char::from(0)).trim().parse::<f32>().ok());
+
+    let samples: Vec<f32> = match decoder.read_image().map_err(io::Error::other)? {
+        DecodingResult::U8(values) => values.into_iter().map(f32::from).collect(),
+        DecodingResult::U16(values) => values.into_iter().map(f32::from).collect(),
+        DecodingResult::U32(values) => values.into_iter().map(|value| value as f32).collect(),
+        DecodingResult::U64(values) => values.into_iter().map(|value| value as f32).collect(),
+        DecodingResult::F32(values) => values,
+        DecodingResult::F64(values) => values.into_iter().map(|value| value as f32).collect(),
+        DecodingResult::I8(values) => values.into_iter().map(f32::from).collect(),
+        DecodingResult::I16(values) => values.into_iter().map(f32::from).collect(),
+        DecodingResult::I32(values) => values.into_iter().map(|value| value as f32).collect(),
+        DecodingResult::I64(values) => values.into_iter().map(|value| value as f32).collect(),
+    };
+    let bands = samples.len() / (width as usize * height as usize).max(1);
+    let elevations = samples
+        .into_iter()
+        .step_by(bands.max(1))
+        .map(|elevation| (elevation.is_finite() && Some(elevation) != nodata).then_some(elevation))
+        .collect();
+    check_grid(UVec2::new(width, height), elevations)
+}
+
+fn check_grid(size: UVec2, elevations: Vec<Option<f32>>) -> io::Result<(UVec2, Vec<Option<f32>>)> {
+    if size.x < 2 || size.y < 2 {
+        return Err(invalid_data("DEM must be at least 2x2 cells"));
+    }
+    if elevations.len() != (size.x * size.y) as usize {
+        return Err(invalid_data(format!(
+            "expected {} elevations for a {}x{} grid, found {}",
+            size.x * size.y,
+            size.x,
+            size.y,
+            elevations.len()
+        )));
+    }
+    Ok((size, elevations))
+}
@@ -0,0 +1,141 @@
+use bevy::{
+    color::palettes::tailwind::{AMBER_800, GREEN_600, STONE_500},
+    image::{ImageAddressMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor},
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+const TERRAIN_SHADER_PATH: &str = "shaders/terrain.wgsl";
+const VEGETATION_TEXTURE_PATH: &str = "textures/terrain/vegetation.png";
+const EARTH_TEXTURE_PATH: &str = "textures/terrain/earth.png";
+const ROCK_TEXTURE_PATH: &str = "textures/terrain/rock.png";
+const SNOW_TEXTURE_PATH: &str = "textures/terrain/snow.png";
+
+/// Terrain material: PBR lighting with per-vertex splat weights blending the biome layers
+pub type TerrainMaterial = ExtendedMaterial<StandardMaterial, TerrainMaterialExtension>;
+
+/// Extension carrying the layer textures and tints; splat weights arrive in the mesh's vertex color.
+///
+/// Layers are in the same order as [`super::biome::Biome::splat`]. Each tint
+/// multiplies its layer's albedo texture, so without textures the tints alone
+/// are the palette.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct TerrainMaterialExtension {
+    #[uniform(100)]
+    pub vegetation: LinearRgba,
+    #[uniform(101)]
+    pub earth: LinearRgba,
+    #[uniform(102)]
+    pub rock: LinearRgba,
+    #[uniform(103)]
+    pub snow: LinearRgba,
+    /// World XZ rectangle the fog texture covers, as (origin x, origin z, width, depth)
+    #[uniform(104)]
+    pub fog_bounds: Vec4,
+    /// Fog of war coverage; without one the terrain is fully revealed
+    #[texture(105)]
+    #[sampler(106)]
+    pub fog: Option<Handle<Image>>,
+    #[texture(107)]
+    #[sampler(108)]
+    pub vegetation_texture: Option<Handle<Image>>,
+    #[texture(109)]
+    #[sampler(110)]
+    pub earth_texture: Option<Handle<Image>>,
+    #[texture(111)]
+    #[sampler(112)]
+    pub rock_texture: Option<Handle<Image>>,
+    #[texture(113)]
+    #[sampler(114)]
+    pub snow_texture: Option<Handle<Image>>,
+}
+
+impl Default for TerrainMaterialExtension {
+    fn default() -> Self {
+        Self {
+            vegetation: GREEN_600.into(),
+            earth: AMBER_800.into(),
+            rock: STONE_500.into(),
+            snow: LinearRgba::WHITE,
+            fog_bounds: Vec4::new(0f32, 0f32, 1f32, 1f32),
+            fog: None,
+            vegetation_texture: None,
+            earth_texture: None,
+            rock_texture: None,
+            snow_texture: None,
+        }
+    }
+}
+
+impl MaterialExtension for TerrainMaterialExtension {
+    fn fragment_shader() -> ShaderRef {
+        TERRAIN_SHADER_PATH.into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        TERRAIN_SHADER_PATH.into()
+    }
+}
+
+/// Albedo texture for each layer, loaded once and shared by every chunk
+#[derive(Resource)]
+pub struct TerrainTextures {
+    vegetation: Handle<Image>,
+    earth: Handle<Image>,
+    rock: Handle<Image>,
+    snow: Handle<Image>,
+}
+
+impl FromWorld for TerrainTextures {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        // The textures tile across the whole terrain, so they have to repeat
+        let load = |path: &'static str| {
+            asset_server.load_with_settings(path, |settings: &mut ImageLoaderSettings| {
+                settings.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+                    address_mode_u: ImageAddressMode::Repeat,
+                    address_mode_v: ImageAddressMode::Repeat,
+                    ..ImageSamplerDescriptor::linear()
+                });
+            })
+        };
+        Self {
+            vegetation: load(VEGETATION_TEXTURE_PATH),
+            earth: load(EARTH_TEXTURE_PATH),
+            rock: load(ROCK_TEXTURE_PATH),
+            snow: load(SNOW_TEXTURE_PATH),
+        }
+    }
+}
+
+/// Terrain material over the layer textures, darkened by the fog of war when
+/// given its texture and bounds
+pub fn terrain_material(
+    textures: &TerrainTextures,
+    fog: Option<(Handle<Image>, Vec4)>,
+) -> TerrainMaterial {
+    // The textures carry the layer colors, so they're left untinted
+    let mut extension = TerrainMaterialExtension {
+        vegetation: LinearRgba::WHITE,
+        earth: LinearRgba::WHITE,
+        rock: LinearRgba::WHITE,
+        snow: LinearRgba::WHITE,
+        vegetation_texture: Some(textures.vegetation.clone()),
+        earth_texture: Some(textures.earth.clone()),
+        rock_texture: Some(textures.rock.clone()),
+        snow_texture: Some(textures.snow.clone()),
+        ..default()
+    };
+    if let Some((texture, bounds)) = fog {
+        extension.fog = Some(texture);
+        extension.fog_bounds = bounds;
+    }
+    ExtendedMaterial {
+        base: StandardMaterial {
+            perceptual_roughness: 0.9,
+            ..default()
+        },
+        extension,
+    }
+}
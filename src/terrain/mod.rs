@@ -0,0 +1,483 @@
+pub mod biome;
+pub mod edit;
+pub mod erosion;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod export;
+pub mod heightfield;
+pub mod hydrology;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod import;
+pub mod material;
+pub mod poi;
+pub mod scatter;
+pub mod tiles;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    pbr::MaterialPlugin,
+    platform::collections::HashMap,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+};
+use noise::{BasicMulti, NoiseFn, Perlin};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use self::{
+    biome::{Biome, BiomeMap, ClimateNoise},
+    edit::TerrainEdits,
+    erosion::ErosionSettings,
+    heightfield::Heightfield,
+    hydrology::WaterMap,
+    material::{TerrainMaterial, TerrainTextures, terrain_material},
+};
+use crate::fog_of_war::FogOfWar;
+
+// Terrain constants
+pub const TERRAIN_HEIGHT: f32 = 70f32;
+pub const MESH_SIZE: f32 = 1000f32;
+pub const SUBDIVISIONS: u32 = 200u32;
+const NOISE_SEED: u32 = 900u32;
+const NOISE_SCALE: f64 = 300.0;
+const CHUNK_RADIUS: i32 = 1; // 3x3 grid of chunks
+const SEA_LEVEL: f32 = -30f32;
+const EROSION_DROPLETS: u32 = 80_000;
+const THERMAL_ITERATIONS: u32 = 10;
+const WATER_COLOR: Color = Color::srgba(0.1, 0.3, 0.5, 0.75);
+
+#[derive(Component)]
+pub struct TerrainMesh;
+
+#[derive(Component)]
+pub struct WaterMesh;
+
+#[derive(Resource)]
+pub struct TerrainStore(pub HashMap<IVec2, Handle<Mesh>>);
+
+/// Where terrain heights come from before erosion and hydrology
+#[derive(Debug, Clone, Default)]
+pub enum HeightSource {
+    /// Multi-octave noise seeded from [`TerrainSettings::seed`]
+    #[default]
+    Noise,
+    /// A heightmap or DEM stretched over every chunk in range; each normalized
+    /// value becomes `value * scale + offset` world units
+    #[cfg(not(target_arch = "wasm32"))]
+    Imported {
+        heights: std::sync::Arc<import::HeightMap>,
+        scale: f32,
+        offset: f32,
+    },
+}
+
+/// Tunable parameters for terrain generation
+#[derive(Resource, Debug, Clone)]
+pub struct TerrainSettings {
+    pub seed: u32,
+    pub height: f32,
+    pub noise_scale: f64,
+    pub chunk_radius: i32,
+    pub sea_level: f32,
+    pub source: HeightSource,
+    /// Erosion applied before meshing; `None` keeps the raw noise
+    pub erosion: Option<ErosionSettings>,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            seed: NOISE_SEED,
+            height: TERRAIN_HEIGHT,
+            noise_scale: NOISE_SCALE,
+            chunk_radius: CHUNK_RADIUS,
+            sea_level: SEA_LEVEL,
+            source: HeightSource::Noise,
+            erosion: Some(ErosionSettings {
+                droplets: EROSION_DROPLETS,
+                thermal_iterations: THERMAL_ITERATIONS,
+            }),
+        }
+    }
+}
+
+impl TerrainSettings {
+    /// World XZ rectangle covered by the chunk grid
+    pub fn world_rect(&self) -> Rect {
+        let half = (self.chunk_radius as f32 + 0.5) * MESH_SIZE;
+        Rect::new(-half, -half, half, half)
+    }
+}
+
+/// Generated terrain for every chunk in range: heights, water, and per-sample biomes
+#[derive(Resource)]
+pub struct TerrainWorld {
+    pub settings: TerrainSettings,
+    pub heightfield: Heightfield,
+    pub water: WaterMap,
+    pub biomes: BiomeMap,
+    pub climate: ClimateNoise,
+}
+
+impl TerrainWorld {
+    /// Samples the height source over the whole chunk range, erodes it, runs
+    /// hydrology, and classifies biomes
+    pub fn generate(settings: &TerrainSettings) -> Self {
+        let noise = BasicMulti::<Perlin>::new(settings.seed);
+        let chunks = (2 * settings.chunk_radius + 1) as u32;
+        let samples = chunks * (SUBDIVISIONS + 1) + 1;
+        let spacing = MESH_SIZE / (SUBDIVISIONS + 1) as f32;
+        let origin = Vec2::splat(-(chunks as f32) * MESH_SIZE / 2f32);
+        #[cfg(not(target_arch = "wasm32"))]
+        let extent = (samples - 1) as f32 * spacing;
+
+        let mut heightfield = Heightfield::from_fn(UVec2::splat(samples), origin, spacing, |pos| {
+            match &settings.source {
+                HeightSource::Noise => {
+                    let val = noise.get([
+                        pos.x as f64 / settings.noise_scale,
+                        pos.y as f64 / settings.noise_scale,
+                    ]);
+                    val as f32 * settings.height // safe: Perlin is -1 to 1
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                HeightSource::Imported {
+                    heights,
+                    scale,
+                    offset,
+                } => heights.sample((pos - origin) / extent) * scale + offset,
+            }
+        });
+        if let Some(erosion) = &settings.erosion {
+            erosion::erode(&mut heightfield, erosion, settings.height, settings.seed);
+        }
+        let water = hydrology::simulate(&mut heightfield, settings.sea_level);
+        let climate = ClimateNoise::new(settings.seed);
+        let biomes = BiomeMap::classify(&heightfield, settings.height, &climate, &water);
+
+        Self {
+            settings: settings.clone(),
+            heightfield,
+            water,
+            biomes,
+            climate,
+        }
+    }
+
+    /// All chunk coordinates covered by this world
+    pub fn chunk_coords(&self) -> impl Iterator<Item = IVec2> + use<> {
+        let radius = self.settings.chunk_radius;
+        (-radius..=radius).flat_map(move |x| (-radius..=radius).map(move |y| IVec2::new(x, y)))
+    }
+
+    /// Terrain height at a world XZ position
+    pub fn height_at(&self, position: Vec2) -> f32 {
+        self.heightfield.sample(position)
+    }
+
+    /// Height of whatever is on top at a world XZ position: water or ground
+    pub fn surface_height_at(&self, position: Vec2) -> f32 {
+        self.height_at(position) + self.water_depth_at(position)
+    }
+
+    /// Terrain surface normal at a world XZ position
+    pub fn normal_at(&self, position: Vec2) -> Vec3 {
+        self.heightfield.normal_at(position)
+    }
+
+    /// Depth of standing or running water at a world XZ position, zero on dry land
+    pub fn water_depth_at(&self, position: Vec2) -> f32 {
+        self.heightfield
+            .nearest(position)
+            .and_then(|cell| self.water.level(cell.x, cell.y))
+            .map_or(0f32, |level| (level - self.height_at(position)).max(0f32))
+    }
+
+    /// Ground moisture in [0, 1] at a world XZ position
+    pub fn moisture_at(&self, position: Vec2) -> f32 {
+        self.heightfield
+            .nearest(position)
+            .map_or(0f32, |cell| self.water.moisture(cell.x, cell.y))
+    }
+
+    /// First point where a ray hits the ground within `max_distance`,
+    /// marching in half-sample steps and then refining by bisection
+    pub fn raycast(&self, ray: Ray3d, max_distance: f32) -> Option<Vec3> {
+        let step = self.heightfield.spacing() / 2f32;
+        let above = |distance: f32| {
+            let point = ray.get_point(distance);
+            point.y > self.height_at(point.xz())
+        };
+        if !above(0f32) {
+            return None;
+        }
+
+        let mut distance = 0f32;
+        while distance < max_distance {
+            let next = (distance + step).min(max_distance);
+            if !above(next) {
+                let (mut near, mut far) = (distance, next);
+                for _ in 0..8 {
+                    let middle = (near + far) / 2f32;
+                    if above(middle) {
+                        near = middle;
+                    } else {
+                        far = middle;
+                    }
+                }
+                return Some(ray.get_point(far));
+            }
+            distance = next;
+        }
+        None
+    }
+
+    /// Random stream for one chunk's placement pass, stable for the same seed,
+    /// chunk, and `salt`
+    fn chunk_rng(&self, chunk: IVec2, salt: u64) -> ChaCha8Rng {
+        let chunk_key = ((chunk.x as u32 as u64) << 32) | chunk.y as u32 as u64;
+        // Golden-ratio multiply spreads neighboring chunk keys across the seed space
+        let mixed = chunk_key.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        ChaCha8Rng::seed_from_u64(((self.settings.seed as u64) << 32 ^ salt).wrapping_add(mixed))
+    }
+
+    /// First heightfield sample covered by a chunk
+    fn chunk_grid_origin(&self, chunk: IVec2) -> UVec2 {
+        ((chunk + IVec2::splat(self.settings.chunk_radius)) * (SUBDIVISIONS + 1) as i32).as_uvec2()
+    }
+
+    /// Flat quads at the water surface for every chunk cell touching water, in
+    /// chunk-local coordinates
+    fn water_mesh(&self, chunk: IVec2) -> Option<Mesh> {
+        let origin = self.chunk_grid_origin(chunk);
+        let spacing = MESH_SIZE / (SUBDIVISIONS + 1) as f32;
+        let half = MESH_SIZE / 2f32;
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        for y in 0..=SUBDIVISIONS {
+            for x in 0..=SUBDIVISIONS {
+                // Only corners still under water count, so sculpted banks and
+                // filled lake beds dry out
+                let corners = [(0, 0), (1, 0), (0, 1), (1, 1)];
+                let Some(level) = corners
+                    .iter()
+                    .filter_map(|(dx, dy)| {
+                        let (cx, cy) = (origin.x + x + dx, origin.y + y + dy);
+                        self.water
+                            .level(cx, cy)
+                            .filter(|level| *level > self.heightfield.get(cx, cy))
+                    })
+                    .reduce(f32::max)
+                else {
+                    continue;
+                };
+
+                let base = positions.len() as u32;
+                for (dx, dy) in corners {
+                    positions.push([
+                        (x + dx) as f32 * spacing - half,
+                        level,
+                        (y + dy) as f32 * spacing - half,
+                    ]);
+                }
+                indices.extend_from_slice(&[
+                    base,
+                    base + 2,
+                    base + 1,
+                    base + 1,
+                    base + 2,
+                    base + 3,
+                ]);
+            }
+        }
+
+        if positions.is_empty() {
+            return None;
+        }
+        let normals = vec![[0f32, 1f32, 0f32]; positions.len()];
+        Some(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_indices(Indices::U32(indices)),
+        )
+    }
+
+    /// Moves a chunk mesh's vertices onto the heightfield, stores splat weights
+    /// in the vertex color for the terrain material, and recomputes normals
+    fn shape_chunk_mesh(&self, chunk: IVec2, mesh: &mut Mesh) {
+        let chunk_offset = chunk.as_vec2() * MESH_SIZE;
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            let mut splats: Vec<[f32; 4]> = Vec::with_capacity(positions.len());
+            for pos in positions.iter_mut() {
+                let world_pos = Vec2::new(pos[0], pos[2]) + chunk_offset;
+                match self.heightfield.nearest(world_pos) {
+                    Some(cell) => {
+                        pos[1] = self.heightfield.get(cell.x, cell.y);
+                        splats.push(self.biomes.splat(cell.x, cell.y));
+                    }
+                    None => {
+                        pos[1] = self.height_at(world_pos);
+                        splats.push(Biome::default().splat());
+                    }
+                }
+            }
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, splats);
+        }
+        mesh.compute_normals();
+    }
+
+    /// Biome of the terrain cell nearest a world XZ position
+    pub fn biome_at(&self, position: Vec2) -> Option<Biome> {
+        self.heightfield
+            .nearest(position)
+            .map(|cell| self.biomes.get(cell.x, cell.y))
+    }
+}
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<TerrainMaterial>::default())
+            .init_resource::<TerrainSettings>()
+            .init_resource::<TerrainEdits>()
+            .init_resource::<TerrainTextures>()
+            .insert_resource(TerrainStore(HashMap::default()));
+    }
+}
+
+/// Generates the terrain world from the current settings, layers any
+/// [`TerrainEdits`] on top, and spawns every chunk
+pub struct GenerateTerrain;
+
+impl Command for GenerateTerrain {
+    fn apply(self, world: &mut World) {
+        let settings = world.resource::<TerrainSettings>().clone();
+        let mut terrain = TerrainWorld::generate(&settings);
+        terrain.apply_edits(world.resource::<TerrainEdits>());
+        let chunks: Vec<IVec2> = terrain.chunk_coords().collect();
+        world.insert_resource(terrain);
+        for chunk in chunks {
+            SpawnTerrain(chunk).apply(world);
+        }
+    }
+}
+
+/// Despawns every terrain chunk and generates the world again from the current settings
+pub struct RegenerateTerrain;
+
+impl Command for RegenerateTerrain {
+    fn apply(self, world: &mut World) {
+        let chunks: Vec<Entity> = world
+            .query_filtered::<Entity, With<TerrainMesh>>()
+            .iter(world)
+            .collect();
+        for chunk in chunks {
+            world.despawn(chunk);
+        }
+        world.resource_mut::<TerrainStore>().0.clear();
+        GenerateTerrain.apply(world);
+    }
+}
+
+/// Spawns a chunk's water surface; it rides along as a child so it despawns
+/// with the chunk
+fn spawn_water(world: &mut World, water_mesh: Mesh, chunk_entity: Entity) {
+    let water_handle = world
+        .get_resource_mut::<Assets<Mesh>>()
+        .expect("meshes to be available")
+        .add(water_mesh);
+    let water_material = world
+        .get_resource_mut::<Assets<StandardMaterial>>()
+        .expect("StandardMaterial db to be available")
+        .add(StandardMaterial {
+            base_color: WATER_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.1,
+            reflectance: 0.6,
+            ..default()
+        });
+    world.spawn((
+        Mesh3d(water_handle),
+        MeshMaterial3d(water_material),
+        Transform::IDENTITY,
+        WaterMesh,
+        ChildOf(chunk_entity),
+    ));
+}
+
+pub struct SpawnTerrain(pub IVec2);
+
+impl Command for SpawnTerrain {
+    fn apply(self, world: &mut World) {
+        if world
+            .get_resource_mut::<TerrainStore>()
+            .expect("TerrainStore to be available")
+            .0
+            .get(&self.0)
+            .is_some()
+        {
+            warn!("Mesh already exists");
+            return;
+        }
+
+        let terrain = world
+            .get_resource::<TerrainWorld>()
+            .expect("TerrainWorld to be generated");
+        let chunk_offset = self.0.as_vec2() * MESH_SIZE;
+
+        let mut mesh = Mesh::from(
+            Plane3d::default()
+                .mesh()
+                .size(MESH_SIZE, MESH_SIZE)
+                .subdivisions(SUBDIVISIONS),
+        );
+
+        terrain.shape_chunk_mesh(self.0, &mut mesh);
+        let water_mesh = terrain.water_mesh(self.0);
+
+        let mesh_handle = world
+            .get_resource_mut::<Assets<Mesh>>()
+            .expect("meshes to be available")
+            .add(mesh);
+
+        let fog = world
+            .get_resource::<FogOfWar>()
+            .map(|fog| (fog.texture(), fog.bounds()));
+        let material = terrain_material(world.resource::<TerrainTextures>(), fog);
+        let material = world
+            .get_resource_mut::<Assets<TerrainMaterial>>()
+            .expect("TerrainMaterial db to be available")
+            .add(material);
+
+        let chunk_entity = world
+            .spawn((
+                Mesh3d(mesh_handle.clone()),
+                MeshMaterial3d(material),
+                Transform::from_xyz(chunk_offset.x, 0f32, chunk_offset.y),
+                GlobalTransform::default(),
+                TerrainMesh,
+            ))
+            .id();
+
+        if let Some(water_mesh) = water_mesh {
+            spawn_water(world, water_mesh, chunk_entity);
+        }
+
+        poi::spawn_points_of_interest(world, self.0, chunk_entity);
+        scatter::spawn_props(world, self.0, chunk_entity);
+
+        // Store the mesh handle
+        world
+            .get_resource_mut::<TerrainStore>()
+            .expect("TerrainStore to be available")
+            .0
+            .insert(self.0, mesh_handle);
+    }
+}
@@ -0,0 +1,328 @@
+use bevy::{
+    color::palettes::tailwind,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use rand::{Rng, seq::SliceRandom};
+
+use super::{MESH_SIZE, TerrainWorld, biome::Biome};
+use crate::fog_of_war::HiddenByFog;
+
+// Poisson-disk spacing between points of interest, in world units
+const POI_SPACING: f32 = 120f32;
+// Candidates tried around each accepted point before it is retired
+const POISSON_ATTEMPTS: u32 = 30;
+// Keeps points half the spacing from chunk edges so neighbors never crowd each other
+const EDGE_MARGIN: f32 = POI_SPACING / 2f32;
+// Mixed into the terrain seed so POI layouts don't correlate with other seeded passes
+const POI_SEED_SALT: u64 = 0x5045_4f49;
+
+// Landing zones need every sample within this radius to be nearly flat
+const LANDING_RADIUS: f32 = 20f32;
+const LANDING_MAX_SLOPE: f32 = 0.12;
+
+/// The kinds of places worth visiting on the terrain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoiKind {
+    /// Pre-Growth skyscraper foundation, mostly buried
+    Skyscraper,
+    Bunker,
+    /// Transit tunnel mouth cut into a hillside
+    TransitTunnel,
+    /// Scrap worth collecting
+    Salvage,
+    /// Flat, dry ground a dropship can set down on
+    LandingZone,
+}
+
+impl PoiKind {
+    /// Relative odds of each kind being tried first at a candidate point
+    const WEIGHTS: [(PoiKind, u32); 5] = [
+        (PoiKind::Skyscraper, 2),
+        (PoiKind::Bunker, 2),
+        (PoiKind::TransitTunnel, 1),
+        (PoiKind::Salvage, 5),
+        (PoiKind::LandingZone, 2),
+    ];
+
+    /// Approximate footprint radius in world units
+    pub fn radius(&self) -> f32 {
+        match self {
+            PoiKind::Skyscraper => 14f32,
+            PoiKind::Bunker => 8f32,
+            PoiKind::TransitTunnel => 7f32,
+            PoiKind::Salvage => 3f32,
+            PoiKind::LandingZone => LANDING_RADIUS,
+        }
+    }
+
+    /// Whether this kind can sit at a world XZ position
+    fn fits(&self, terrain: &TerrainWorld, position: Vec2) -> bool {
+        let Some(cell) = terrain.heightfield.nearest(position) else {
+            return false;
+        };
+        if terrain.water_depth_at(position) > 0f32 {
+            return false;
+        }
+        let slope = terrain.heightfield.slope(cell.x, cell.y);
+        let biome = terrain.biomes.get(cell.x, cell.y);
+        let height = terrain.heightfield.get(cell.x, cell.y) / terrain.settings.height;
+
+        match self {
+            // Cities sat in the lowlands, where the Growth is now thickest
+            PoiKind::Skyscraper => {
+                slope < 0.35
+                    && height < 0.2
+                    && matches!(biome, Biome::Grassland | Biome::Wetland | Biome::Scrubland)
+            }
+            PoiKind::Bunker => slope < 0.5 && matches!(biome, Biome::Badlands | Biome::Scrubland),
+            // Tunnels punch into hillsides, so they want a real slope
+            PoiKind::TransitTunnel => {
+                (0.35..0.85).contains(&slope) && !matches!(biome, Biome::Snowcap)
+            }
+            PoiKind::Salvage => slope < 0.6 && !matches!(biome, Biome::Snowcap),
+            PoiKind::LandingZone => {
+                !matches!(biome, Biome::Wetland | Biome::Cliffs) && terrain.is_flat(position)
+            }
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            PoiKind::Skyscraper => tailwind::SLATE_500.into(),
+            PoiKind::Bunker => tailwind::STONE_700.into(),
+            PoiKind::TransitTunnel => tailwind::ZINC_800.into(),
+            PoiKind::Salvage => tailwind::YELLOW_500.into(),
+            PoiKind::LandingZone => tailwind::SKY_400.into(),
+        }
+    }
+
+    /// Placeholder prefab shape and its offset from the ground point
+    fn mesh(&self) -> (Mesh, Vec3) {
+        match self {
+            PoiKind::Skyscraper => (Cuboid::new(22f32, 12f32, 22f32).into(), Vec3::Y * 3f32),
+            PoiKind::Bunker => (Cuboid::new(14f32, 4f32, 10f32).into(), Vec3::Y),
+            PoiKind::TransitTunnel => (
+                Torus::new(4f32, 6f32)
+                    .mesh()
+                    .build()
+                    .rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                Vec3::Y * 2f32,
+            ),
+            PoiKind::Salvage => (Cuboid::new(2f32, 2f32, 3f32).into(), Vec3::Y),
+            PoiKind::LandingZone => (
+                Cylinder::new(LANDING_RADIUS, 0.3f32).into(),
+                Vec3::Y * 0.2f32,
+            ),
+        }
+    }
+}
+
+/// A placed point of interest; children of their terrain chunk
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PointOfInterest {
+    pub kind: PoiKind,
+}
+
+/// Where and how a point of interest sits on the terrain
+#[derive(Debug, Clone, Copy)]
+pub struct PoiPlacement {
+    pub kind: PoiKind,
+    /// World position on the ground
+    pub position: Vec3,
+    pub yaw: f32,
+}
+
+/// Shared prefab meshes and materials, one per kind
+#[derive(Resource)]
+struct PoiAssets(HashMap<PoiKind, (Handle<Mesh>, Handle<StandardMaterial>, Vec3)>);
+
+impl FromWorld for PoiAssets {
+    fn from_world(world: &mut World) -> Self {
+        let kinds = PoiKind::WEIGHTS.map(|(kind, _)| kind);
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let shapes: Vec<_> = kinds
+            .iter()
+            .map(|kind| {
+                let (mesh, offset) = kind.mesh();
+                (meshes.add(mesh), offset)
+            })
+            .collect();
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(
+            kinds
+                .into_iter()
+                .zip(shapes)
+                .map(|(kind, (mesh, offset))| {
+                    let material = materials.add(StandardMaterial {
+                        base_color: kind.color(),
+                        perceptual_roughness: 0.8,
+                        ..default()
+                    });
+                    (kind, (mesh, material, offset))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl TerrainWorld {
+    /// Whether the ground within the landing radius of a world XZ position is nearly level
+    fn is_flat(&self, position: Vec2) -> bool {
+        let spacing = self.heightfield.spacing();
+        let steps = (LANDING_RADIUS / spacing).ceil() as i32;
+        (-steps..=steps)
+            .flat_map(|dy| (-steps..=steps).map(move |dx| Vec2::new(dx as f32, dy as f32)))
+            .filter(|offset| offset.length() * spacing <= LANDING_RADIUS)
+            .all(|offset| {
+                self.heightfield
+                    .nearest(position + offset * spacing)
+                    .is_some_and(|cell| self.heightfield.slope(cell.x, cell.y) < LANDING_MAX_SLOPE)
+            })
+    }
+
+    /// Points of interest for a chunk, identical for the same seed and chunk
+    pub fn points_of_interest(&self, chunk: IVec2) -> Vec<PoiPlacement> {
+        let mut rng = self.chunk_rng(chunk, POI_SEED_SALT);
+
+        let min = chunk.as_vec2() * MESH_SIZE - Vec2::splat(MESH_SIZE / 2f32 - EDGE_MARGIN);
+        let extent = MESH_SIZE - 2f32 * EDGE_MARGIN;
+        let total_weight: u32 = PoiKind::WEIGHTS.iter().map(|(_, weight)| weight).sum();
+
+        poisson_disk(&mut rng, extent, POI_SPACING)
+            .into_iter()
+            .filter_map(|local| {
+                let position = min + local;
+                // Try a weighted pick first, then fall back through the rest
+                let mut roll = rng.gen_range(0..total_weight);
+                let first = PoiKind::WEIGHTS
+                    .iter()
+                    .position(|(_, weight)| {
+                        let hit = roll < *weight;
+                        roll = roll.saturating_sub(*weight);
+                        hit
+                    })
+                    .unwrap_or(0);
+                let mut kinds = PoiKind::WEIGHTS.map(|(kind, _)| kind);
+                kinds.rotate_left(first);
+                kinds[1..].shuffle(&mut rng);
+                let yaw = rng.gen_range(0f32..std::f32::consts::TAU);
+
+                let kind = kinds.into_iter().find(|kind| kind.fits(self, position))?;
+                Some(PoiPlacement {
+                    kind,
+                    position: Vec3::new(position.x, self.height_at(position), position.y),
+                    yaw,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Bridson's Poisson-disk sampling over a `size` square, no two points closer than `spacing`
+fn poisson_disk(rng: &mut impl Rng, size: f32, spacing: f32) -> Vec<Vec2> {
+    let cell = spacing / std::f32::consts::SQRT_2;
+    let cells = (size / cell).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; cells * cells];
+    let grid_index = |point: Vec2| {
+        let x = ((point.x / cell) as usize).min(cells - 1);
+        let y = ((point.y / cell) as usize).min(cells - 1);
+        y * cells + x
+    };
+
+    let first = Vec2::new(rng.gen_range(0f32..size), rng.gen_range(0f32..size));
+    let mut points = vec![first];
+    let mut active = vec![0];
+    grid[grid_index(first)] = Some(0);
+
+    while !active.is_empty() {
+        let slot = rng.gen_range(0..active.len());
+        let center = points[active[slot]];
+        let mut placed = false;
+
+        for _ in 0..POISSON_ATTEMPTS {
+            let angle = rng.gen_range(0f32..std::f32::consts::TAU);
+            let distance = rng.gen_range(spacing..2f32 * spacing);
+            let candidate = center + Vec2::from_angle(angle) * distance;
+            if candidate.x < 0f32
+                || candidate.y < 0f32
+                || candidate.x >= size
+                || candidate.y >= size
+            {
+                continue;
+            }
+
+            let (cx, cy) = ((candidate.x / cell) as i32, (candidate.y / cell) as i32);
+            let crowded = (cy - 2..=cy + 2).any(|y| {
+                (cx - 2..=cx + 2).any(|x| {
+                    x >= 0
+                        && y >= 0
+                        && (x as usize) < cells
+                        && (y as usize) < cells
+                        && grid[y as usize * cells + x as usize]
+                            .is_some_and(|other| points[other].distance(candidate) < spacing)
+                })
+            });
+            if !crowded {
+                grid[grid_index(candidate)] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                placed = true;
+                break;
+            }
+        }
+
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+    points
+}
+
+/// Spawns a chunk's points of interest as children of the chunk entity
+pub(super) fn spawn_points_of_interest(world: &mut World, chunk: IVec2, chunk_entity: Entity) {
+    world.init_resource::<PoiAssets>();
+    let placements = world.resource::<TerrainWorld>().points_of_interest(chunk);
+    let chunk_offset = chunk.as_vec2() * MESH_SIZE;
+
+    for placement in placements {
+        let (mesh, material, offset) = world.resource::<PoiAssets>().0[&placement.kind].clone();
+        let local = placement.position - Vec3::new(chunk_offset.x, 0f32, chunk_offset.y);
+        world.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(local + offset)
+                .with_rotation(Quat::from_rotation_y(placement.yaw)),
+            PointOfInterest {
+                kind: placement.kind,
+            },
+            HiddenByFog::Explored,
+            ChildOf(chunk_entity),
+        ));
+    }
+}
+
+/// Sets the points of interest under the given chunk entities back on the
+/// ground after it was reshaped
+pub(super) fn settle_points_of_interest(
+    world: &mut World,
+    terrain: &TerrainWorld,
+    chunk_entities: &HashSet<Entity>,
+) {
+    world.init_resource::<PoiAssets>();
+    let lifts: HashMap<PoiKind, f32> = world
+        .resource::<PoiAssets>()
+        .0
+        .iter()
+        .map(|(kind, (_, _, offset))| (*kind, offset.y))
+        .collect();
+    // Chunks sit at y = 0, so a point's local height is its ground height
+    let mut points =
+        world.query::<(&ChildOf, &PointOfInterest, &mut Transform, &GlobalTransform)>();
+    for (parent, point, mut transform, global) in points.iter_mut(world) {
+        if chunk_entities.contains(&parent.parent()) {
+            transform.translation.y =
+                terrain.height_at(global.translation().xz()) + lifts[&point.kind];
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use bevy::{
+    color::palettes::tailwind, platform::collections::HashMap, prelude::*,
+    render::view::VisibilityRange,
+};
+use rand::Rng;
+
+use super::{MESH_SIZE, TerrainWorld, biome::Biome};
+use crate::fog_of_war::HiddenByFog;
+
+// Props are scattered one candidate per cell of this size, jittered within the cell
+const SCATTER_CELL: f32 = 12f32;
+// Mixed into the terrain seed so prop layouts don't correlate with points of interest
+const SCATTER_SEED_SALT: u64 = 0x5052_4f50;
+
+/// Kinds of small set dressing scattered over the terrain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PropKind {
+    Rock,
+    Boulder,
+    DeadTree,
+    Debris,
+}
+
+impl PropKind {
+    const ALL: [PropKind; 4] = [
+        PropKind::Rock,
+        PropKind::Boulder,
+        PropKind::DeadTree,
+        PropKind::Debris,
+    ];
+
+    /// Steepest ground this prop can sit on, as rise over run
+    fn max_slope(&self) -> f32 {
+        match self {
+            PropKind::Rock => 1.2,
+            PropKind::Boulder => 1.5,
+            PropKind::DeadTree => 0.6,
+            PropKind::Debris => 0.5,
+        }
+    }
+
+    /// Distance from the camera at which the prop starts and finishes fading
+    /// out; small props go first
+    fn fade(&self) -> std::ops::Range<f32> {
+        match self {
+            PropKind::Rock => 250f32..300f32,
+            PropKind::Boulder => 600f32..700f32,
+            PropKind::DeadTree => 500f32..600f32,
+            PropKind::Debris => 150f32..200f32,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            PropKind::Rock => tailwind::STONE_500.into(),
+            PropKind::Boulder => tailwind::STONE_600.into(),
+            PropKind::DeadTree => tailwind::STONE_800.into(),
+            PropKind::Debris => tailwind::ZINC_600.into(),
+        }
+    }
+
+    /// Placeholder shape, built around the ground point
+    fn mesh(&self) -> Mesh {
+        match self {
+            PropKind::Rock => Sphere::new(0.8f32)
+                .mesh()
+                .ico(1)
+                .expect("small subdivision count"),
+            PropKind::Boulder => Sphere::new(2.5f32)
+                .mesh()
+                .ico(1)
+                .expect("small subdivision count"),
+            PropKind::DeadTree => Cylinder::new(0.25f32, 7f32)
+                .mesh()
+                .resolution(6)
+                .build()
+                .translated_by(Vec3::Y * 3.5f32),
+            PropKind::Debris => Cuboid::new(2.5f32, 0.4f32, 1.2f32).into(),
+        }
+    }
+}
+
+/// Odds that a scatter cell holds each prop kind, per biome
+fn densities(biome: Biome) -> &'static [(PropKind, f32)] {
+    match biome {
+        Biome::Wetland => &[(PropKind::DeadTree, 0.06), (PropKind::Debris, 0.02)],
+        Biome::Grassland => &[
+            (PropKind::Rock, 0.04),
+            (PropKind::DeadTree, 0.03),
+            (PropKind::Debris, 0.03),
+        ],
+        Biome::Scrubland => &[
+            (PropKind::Rock, 0.08),
+            (PropKind::DeadTree, 0.05),
+            (PropKind::Debris, 0.03),
+        ],
+        Biome::Badlands => &[
+            (PropKind::Rock, 0.15),
+            (PropKind::Boulder, 0.03),
+            (PropKind::Debris, 0.02),
+        ],
+        Biome::Cliffs => &[(PropKind::Rock, 0.1), (PropKind::Boulder, 0.05)],
+        Biome::Snowcap => &[(PropKind::Rock, 0.03)],
+    }
+}
+
+/// A scattered prop, sitting on the ground at its origin; children of their terrain chunk
+#[derive(Component)]
+pub struct Prop;
+
+/// Shared prop meshes and materials, so every prop of a kind batches into one
+/// instanced draw
+#[derive(Resource)]
+struct PropAssets(HashMap<PropKind, (Handle<Mesh>, Handle<StandardMaterial>)>);
+
+impl FromWorld for PropAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let shapes: Vec<_> = PropKind::ALL
+            .iter()
+            .map(|kind| meshes.add(kind.mesh()))
+            .collect();
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(
+            PropKind::ALL
+                .into_iter()
+                .zip(shapes)
+                .map(|(kind, mesh)| {
+                    let material = materials.add(StandardMaterial {
+                        base_color: kind.color(),
+                        perceptual_roughness: 0.95,
+                        ..default()
+                    });
+                    (kind, (mesh, material))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl TerrainWorld {
+    /// Props for a chunk with their chunk-local transforms, identical for the
+    /// same seed and chunk
+    fn scatter_props(&self, chunk: IVec2) -> Vec<(PropKind, Transform)> {
+        let mut rng = self.chunk_rng(chunk, SCATTER_SEED_SALT);
+        let cells = (MESH_SIZE / SCATTER_CELL) as i32;
+        let half = MESH_SIZE / 2f32;
+        let chunk_offset = chunk.as_vec2() * MESH_SIZE;
+        let mut props = Vec::new();
+
+        for y in 0..cells {
+            for x in 0..cells {
+                // Always draw the same numbers per cell so edits elsewhere
+                // don't reshuffle the whole chunk
+                let jitter = Vec2::new(rng.r#gen::<f32>(), rng.r#gen::<f32>());
+                let roll: f32 = rng.r#gen();
+                let yaw = rng.gen_range(0f32..std::f32::consts::TAU);
+                let tilt = rng.gen_range(-0.15f32..0.15f32);
+                let scale = Vec3::new(
+                    rng.gen_range(0.7f32..1.4f32),
+                    rng.gen_range(0.6f32..1.3f32),
+                    rng.gen_range(0.7f32..1.4f32),
+                );
+
+                let local = (Vec2::new(x as f32, y as f32) + jitter) * SCATTER_CELL - half;
+                let position = local + chunk_offset;
+                let Some(cell) = self.heightfield.nearest(position) else {
+                    continue;
+                };
+                if self.water_depth_at(position) > 0f32 {
+                    continue;
+                }
+                let slope = self.heightfield.slope(cell.x, cell.y);
+
+                let mut threshold = 0f32;
+                let Some(kind) = densities(self.biomes.get(cell.x, cell.y))
+                    .iter()
+                    .find(|(_, density)| {
+                        threshold += density;
+                        roll < threshold
+                    })
+                    .map(|(kind, _)| *kind)
+                    .filter(|kind| slope <= kind.max_slope())
+                else {
+                    continue;
+                };
+
+                let scale = match kind {
+                    PropKind::DeadTree => Vec3::splat(scale.y),
+                    _ => scale,
+                };
+                props.push((
+                    kind,
+                    Transform::from_xyz(local.x, self.height_at(position), local.y)
+                        .with_rotation(Quat::from_rotation_y(yaw) * Quat::from_rotation_x(tilt))
+                        .with_scale(scale),
+                ));
+            }
+        }
+        props
+    }
+}
+
+/// Spawns a chunk's props as children of the chunk entity, so they stream in
+/// and out with it
+pub(super) fn spawn_props(world: &mut World, chunk: IVec2, chunk_entity: Entity) {
+    world.init_resource::<PropAssets>();
+    let props = world.resource::<TerrainWorld>().scatter_props(chunk);
+    let assets = &world.resource::<PropAssets>().0;
+    let bundles: Vec<_> = props
+        .into_iter()
+        .map(|(kind, transform)| {
+            let (mesh, material) = assets[&kind].clone();
+            let fade = kind.fade();
+            (
+                Mesh3d(mesh),
+                MeshMaterial3d(material),
+                transform,
+                VisibilityRange {
+                    start_margin: 0f32..0f32,
+                    end_margin: fade,
+                    use_aabb: false,
+                },
+                Prop,
+                HiddenByFog::Explored,
+                ChildOf(chunk_entity),
+            )
+        })
+        .collect();
+    world.spawn_batch(bundles);
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use super::{TerrainWorld, biome::Biome};
+
+// Water deeper than this reads as open water rather than shallows
+const DEEP_WATER_DEPTH: f32 = 4f32;
+// Dry ground this close above sea level is beach
+const BEACH_HEIGHT: f32 = 3f32;
+
+/// Ground cover of one top-down map tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileKind {
+    DeepWater,
+    ShallowWater,
+    Sand,
+    Marsh,
+    Grass,
+    Scrub,
+    Dirt,
+    Rock,
+    Snow,
+}
+
+impl TileKind {
+    /// Every kind, lowest ground cover first; later kinds are drawn over
+    /// earlier ones where they meet
+    pub const ALL: [TileKind; 9] = [
+        TileKind::DeepWater,
+        TileKind::ShallowWater,
+        TileKind::Sand,
+        TileKind::Marsh,
+        TileKind::Grass,
+        TileKind::Scrub,
+        TileKind::Dirt,
+        TileKind::Rock,
+        TileKind::Snow,
+    ];
+
+    /// Position in [`TileKind::ALL`]
+    pub fn layer(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|kind| kind == self)
+            .expect("every kind is listed")
+    }
+}
+
+impl TerrainWorld {
+    /// What a top-down map shows at a world XZ position: water first, then
+    /// beaches, then the biome
+    pub fn tile_kind_at(&self, position: Vec2) -> TileKind {
+        let depth = self.water_depth_at(position);
+        if depth > DEEP_WATER_DEPTH {
+            return TileKind::DeepWater;
+        }
+        if depth > 0f32 {
+            return TileKind::ShallowWater;
+        }
+        if self.height_at(position) < self.settings.sea_level + BEACH_HEIGHT {
+            return TileKind::Sand;
+        }
+        match self.biome_at(position).unwrap_or_default() {
+            Biome::Wetland => TileKind::Marsh,
+            Biome::Grassland => TileKind::Grass,
+            Biome::Scrubland => TileKind::Scrub,
+            Biome::Badlands => TileKind::Dirt,
+            Biome::Cliffs => TileKind::Rock,
+            Biome::Snowcap => TileKind::Snow,
+        }
+    }
+}